@@ -0,0 +1,109 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use sapir::constraint_system::ConstraintSystem;
+use sapir::frontend::gadgets::poseidon::poseidon::PoseidonChip;
+use sapir::frontend::gadgets::{sha256, to_addr};
+use sapir::poseidon::constants::secp256k1_w3;
+
+type Fp = ark_secq256k1::Fr;
+
+// A hash gadget to compare, with the number of inputs and outputs it takes
+struct HashGadget {
+    name: &'static str,
+    num_pub_inputs: usize,
+    num_priv_inputs: usize,
+    synthesizer: fn(&mut ConstraintSystem<Fp>),
+}
+
+fn keccak256_circuit(cs: &mut ConstraintSystem<Fp>) {
+    let input = cs.alloc_priv_inputs_arr::<512>();
+    let out = to_addr(input);
+    cs.expose_public(out);
+}
+
+fn sha256_circuit(cs: &mut ConstraintSystem<Fp>) {
+    let input = cs.alloc_priv_inputs(512);
+    let out = sha256(cs, &input);
+    for bit in out {
+        cs.expose_public(bit);
+    }
+}
+
+fn poseidon_circuit(cs: &mut ConstraintSystem<Fp>) {
+    let input = cs.alloc_priv_inputs(2);
+
+    let mut poseidon_chip = PoseidonChip::<Fp, 3>::new(cs, secp256k1_w3());
+    poseidon_chip.state[1] = input[0];
+    poseidon_chip.state[2] = input[1];
+    poseidon_chip.permute();
+
+    cs.expose_public(poseidon_chip.state[1]);
+}
+
+const GADGETS: [HashGadget; 3] = [
+    HashGadget {
+        name: "keccak256 (512-bit input)",
+        num_pub_inputs: 1,
+        num_priv_inputs: 512,
+        synthesizer: keccak256_circuit,
+    },
+    HashGadget {
+        name: "sha256 (512-bit input)",
+        num_pub_inputs: 256,
+        num_priv_inputs: 512,
+        synthesizer: sha256_circuit,
+    },
+    HashGadget {
+        name: "poseidon (t = 3, 2 inputs)",
+        num_pub_inputs: 1,
+        num_priv_inputs: 2,
+        synthesizer: poseidon_circuit,
+    },
+];
+
+fn bench_gadgets(c: &mut Criterion) {
+    let mut table = vec![];
+
+    for gadget in &GADGETS {
+        let mut cs = ConstraintSystem::new();
+        cs.set_constraints(&gadget.synthesizer);
+
+        table.push((gadget.name, cs.num_constraints(), cs.num_wires()));
+
+        // Witness generation doesn't check the public output, so any value works here
+        let priv_input = vec![Fp::from(1u32); gadget.num_priv_inputs];
+        let pub_input = vec![Fp::from(0u32); gadget.num_pub_inputs];
+
+        c.bench_function(&format!("witness gen: {}", gadget.name), |b| {
+            b.iter(|| cs.gen_witness(gadget.synthesizer, &pub_input, &priv_input))
+        });
+    }
+
+    // Witness generation of keccak256, with the batches of gates evaluated serially and across threads
+    let keccak256 = &GADGETS[0];
+    let priv_input = vec![Fp::from(1u32); keccak256.num_priv_inputs];
+    let pub_input = vec![Fp::from(0u32); keccak256.num_pub_inputs];
+
+    for parallel in [false, true] {
+        let mut cs = ConstraintSystem::new();
+        cs.set_parallel_witness(parallel);
+
+        let name = if parallel { "parallel" } else { "serial" };
+        c.bench_function(
+            &format!("witness gen ({}): {}", name, keccak256.name),
+            |b| b.iter(|| cs.gen_witness(keccak256.synthesizer, &pub_input, &priv_input)),
+        );
+    }
+
+    println!();
+    println!("{:<30} {:>12} {:>12}", "gadget", "constraints", "wires");
+    for (name, num_constraints, num_wires) in table {
+        println!("{:<30} {:>12} {:>12}", name, num_constraints, num_wires);
+    }
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = bench_gadgets
+}
+criterion_main!(benches);
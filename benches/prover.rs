@@ -0,0 +1,36 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use sapir::constraint_system::ConstraintSystem;
+use sapir::mock_circuit;
+use sapir::spartan::spartan::Spartan;
+
+type Curve = ark_secq256k1::Projective;
+type Fp = ark_secq256k1::Fr;
+
+const NUM_CONS: usize = 1 << 14;
+
+// Compare the prover with and without the witness commitment pipelined
+// with the matrix multiplications. Pipelining requires the `parallel` feature.
+fn bench_prover(c: &mut Criterion) {
+    let synthesizer = mock_circuit(NUM_CONS);
+    let mut cs = ConstraintSystem::new();
+    cs.set_constraints(&synthesizer);
+    let r1cs = cs.to_r1cs();
+
+    let priv_input = vec![Fp::from(1), Fp::from(2)];
+    let pub_input = [priv_input[0] * priv_input[1]];
+    let witness = cs.gen_witness(&synthesizer, &pub_input, &priv_input);
+
+    let mut spartan = Spartan::<Curve>::new(b"bench_prover", r1cs);
+
+    for (name, pipelined) in [("prove (sequential)", false), ("prove (pipelined)", true)] {
+        spartan.pipelined = pipelined;
+        c.bench_function(name, |b| b.iter(|| spartan.prove(&witness, &pub_input)));
+    }
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = bench_prover
+}
+criterion_main!(benches);
@@ -0,0 +1,44 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use sapir::spartan::hyrax::Hyrax;
+use sapir::spartan::sumcheck::verify_many::verify_many;
+use sapir::spartan::sumcheck::{prove_test_sum, SumCheckProof};
+use sapir::spartan::transcript::Transcript;
+
+type Curve = ark_secq256k1::Projective;
+type Fp = ark_secq256k1::Fr;
+
+const NUM_PROOFS: usize = 100;
+const POLY_NUM_VARS: usize = 8;
+const LABEL: &[u8] = b"bench_verify_many";
+
+fn prove_instance(offset: u64) -> (SumCheckProof<Curve>, Vec<Fp>) {
+    let evals = (0..2usize.pow(POLY_NUM_VARS as u32))
+        .map(|i| Fp::from(i as u64 * offset + 3))
+        .collect::<Vec<Fp>>();
+
+    let (proof, _, _, _) = prove_test_sum::<Curve>(vec![evals.clone()], LABEL);
+
+    (proof, evals)
+}
+
+fn bench_verify_many(c: &mut Criterion) {
+    let poly_num_entries = 2usize.pow(POLY_NUM_VARS as u32);
+    let hyrax = Hyrax::<Curve>::new(poly_num_entries, poly_num_entries);
+
+    let proofs = (0..NUM_PROOFS)
+        .map(|i| prove_instance(i as u64 + 1))
+        .collect::<Vec<_>>();
+
+    let transcript = Transcript::<Curve>::new(LABEL);
+
+    c.bench_function("verify_many (100 proofs)", |b| {
+        b.iter(|| verify_many(&proofs, &hyrax, &transcript, LABEL))
+    });
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = bench_verify_many
+}
+criterion_main!(benches);
@@ -0,0 +1,8 @@
+use ark_ff::PrimeField;
+use num_bigint::BigUint;
+
+// An Ethereum address is the last 20 bytes of the Keccak256 digest of the public key.
+// Returns the address as a field element, in the same form that the `to_addr` gadget outputs it.
+pub fn address_from_digest<F: PrimeField>(digest: &[u8; 32]) -> F {
+    F::from(BigUint::from_bytes_be(&digest[12..]))
+}
@@ -1,6 +1,9 @@
 use ark_ff::Field;
 
-use crate::{constraint_system::ConstraintSystem, wasm::prelude::R1CS};
+use crate::{
+    constraint_system::{CircuitInput, ConstraintSystem},
+    wasm::prelude::R1CS,
+};
 
 pub struct Circuit<F: Field> {
     cs: ConstraintSystem<F>,
@@ -19,10 +22,18 @@ impl<F: Field> Circuit<F> {
             .gen_witness(self.synthesizer, pub_inputs, priv_inputs)
     }
 
+    pub fn gen_witness_from_input<I: CircuitInput<F>>(&mut self, input: &I) -> Vec<F> {
+        self.cs.gen_witness_from_input(self.synthesizer, input)
+    }
+
     pub fn is_sat(&self, witness: &[F], pub_inputs: &[F]) -> bool {
         self.cs.is_sat(witness, pub_inputs)
     }
 
+    pub fn public_output(&self, name: &str) -> Option<F> {
+        self.cs.public_output(name)
+    }
+
     pub fn to_r1cs(&self) -> R1CS<F> {
         self.cs.to_r1cs()
     }
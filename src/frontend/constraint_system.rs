@@ -1,9 +1,18 @@
+use crate::frontend::trace::TraceOp;
+use crate::frontend::wires::Wires;
 use crate::r1cs::{Matrix, SparseMatrixEntry, R1CS};
 use crate::timer::{profiler_end, profiler_start};
 use ark_ff::Field;
+use ark_serialize::CanonicalSerialize;
 use core::panic;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 use std::cmp::max;
 use std::collections::BTreeMap;
+use std::fmt;
+#[cfg(feature = "mmap")]
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 pub struct Conditional<F: Field> {
     undecided: Wire<F>,
@@ -67,6 +76,10 @@ impl<F: Field> Wire<F> {
         Wire { id, index, cs }
     }
 
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
     pub fn cs(&self) -> &mut ConstraintSystem<F> {
         unsafe { &mut *self.cs as &mut ConstraintSystem<F> }
     }
@@ -218,6 +231,14 @@ impl<F: Field> Not for Wire<F> {
     }
 }
 
+// A typed input to a circuit, which can be flattened into
+// the public and private inputs expected by `gen_witness`.
+pub trait CircuitInput<F: Field> {
+    // Returns the public inputs and the private inputs,
+    // in the order the synthesizer allocates them.
+    fn flatten(&self) -> (Vec<F>, Vec<F>);
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct CircuitMeta {
     pub num_pub_inputs: usize,
@@ -240,6 +261,41 @@ enum Mode {
     ConstraintsGen,
 }
 
+// Reported when a circuit exceeds the limit set with `ConstraintSystem::set_size_limit`
+#[derive(Debug, Clone)]
+pub struct SizeLimitWarning {
+    pub num_constraints: usize,
+    pub num_wires: usize,
+    // The tags of the gadget that exceeded the limit, separated by "/"
+    pub tag: String,
+}
+
+// An assertion that doesn't hold for the witness being generated,
+// returned by `ConstraintSystem::try_assert_equal`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConstraintError<F: Field> {
+    // The message passed to the assertion
+    pub label: String,
+    pub left: F,
+    pub right: F,
+}
+
+impl<F: Field> fmt::Display for ConstraintError<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({} != {})", self.label, self.left, self.right)
+    }
+}
+
+impl<F: Field> std::error::Error for ConstraintError<F> {}
+
+#[derive(Clone)]
+struct SizeLimit {
+    max_constraints: usize,
+    max_wires: usize,
+    on_exceeded: Arc<dyn Fn(&SizeLimitWarning) + Send + Sync>,
+    exceeded: bool,
+}
+
 // A constraint system (R1CS) that handles the followings:
 // - Allocating and constraining wires (by exposing methods `add`, `mul`, etc.)
 // - Generating the witness (`gen_witness`)
@@ -248,17 +304,24 @@ enum Mode {
 // to a `ConstraintSystem` and calls its method to allocate and constrain wires.
 #[derive(Clone)]
 pub struct ConstraintSystem<F: Field> {
-    pub wires: Vec<F>,
+    pub wires: Wires<F>,
     A_first: BTreeMap<usize, F>,
     B_first: BTreeMap<usize, F>,
     C_first: BTreeMap<usize, F>,
-    A: BTreeMap<u64, F>,
-    B: BTreeMap<u64, F>,
-    C: BTreeMap<u64, F>,
-    A_nonzero_coeffs: Vec<Vec<usize>>,
-    B_nonzero_coeffs: Vec<Vec<usize>>,
-    C_nonzero_coeffs: Vec<Vec<usize>>,
+    // The non-zero entries (wire index, coefficient) of each row of the matrices,
+    // for every constraint but the first.
+    A_rows: Vec<Vec<(usize, F)>>,
+    B_rows: Vec<Vec<(usize, F)>>,
+    C_rows: Vec<Vec<(usize, F)>>,
+    // The wire allocated to store the output of each constraint, if any.
+    output_wires: Vec<Option<usize>>,
+    // The message passed to `assert_equal` for the constraints that have one.
+    labels: BTreeMap<usize, String>,
+    // The tags (joined with "/") under which each constraint was added, if any.
+    constraint_tags: BTreeMap<usize, String>,
     constants: BTreeMap<F, (usize, usize)>,
+    // 2^i at index i, extended as needed by `pow_of_two`
+    pows_of_two: Vec<F>,
     pub next_priv_wire: usize,
     pub next_pub_wire: usize,
     next_constraint: usize,
@@ -270,26 +333,47 @@ pub struct ConstraintSystem<F: Field> {
     num_pub_inputs: Option<usize>,
     num_priv_inputs: Option<usize>,
     pub_wires: Vec<usize>,
+    named_outputs: BTreeMap<String, usize>,
+    // The operations performed during synthesis, if we're recording a trace.
+    trace: Option<Vec<TraceOp<F>>>,
+    trace_depth: usize,
+    // The file to memory-map the wires onto at witness generation, if any.
+    #[cfg(feature = "mmap")]
+    mmap_path: Option<PathBuf>,
+    // Count the constraints without storing them
+    dry_run: bool,
+    // The running hash of the constraints added so far,
+    // and the final circuit hash once the constraints are set.
+    constraints_hash: Option<merlin::Transcript>,
+    circuit_hash: Option<[u8; 32]>,
+    // The soft limit on the size of the circuit set with `set_size_limit`,
+    // and the tags of the gadget being synthesized.
+    size_limit: Option<SizeLimit>,
+    tags: Vec<String>,
+    // Evaluate batches of gates across threads at witness generation (see `assign_outputs`)
+    parallel_witness: bool,
     constrained: bool,
     wires_counted: bool,
 }
 
 impl<F: Field> ConstraintSystem<F> {
     const ONE_WIRE_INDEX: usize = 0;
+    const CIRCUIT_HASH_LABEL: &'static [u8] = b"sapir_circuit_hash";
 
     pub const fn new() -> Self {
         ConstraintSystem {
-            wires: vec![],
+            wires: Wires::new(),
             A_first: BTreeMap::new(),
             B_first: BTreeMap::new(),
             C_first: BTreeMap::new(),
-            A: BTreeMap::new(),
-            B: BTreeMap::new(),
-            C: BTreeMap::new(),
-            A_nonzero_coeffs: Vec::new(),
-            B_nonzero_coeffs: Vec::new(),
-            C_nonzero_coeffs: Vec::new(),
+            A_rows: Vec::new(),
+            B_rows: Vec::new(),
+            C_rows: Vec::new(),
+            output_wires: Vec::new(),
+            labels: BTreeMap::new(),
+            constraint_tags: BTreeMap::new(),
             constants: BTreeMap::new(),
+            pows_of_two: Vec::new(),
             next_priv_wire: 0,
             next_pub_wire: 0,
             next_wire_id: 1,
@@ -300,16 +384,57 @@ impl<F: Field> ConstraintSystem<F> {
             num_pub_inputs: None,
             num_constraints: None,
             pub_wires: vec![],
+            named_outputs: BTreeMap::new(),
+            trace: None,
+            trace_depth: 0,
+            #[cfg(feature = "mmap")]
+            mmap_path: None,
+            dry_run: false,
+            constraints_hash: None,
+            circuit_hash: None,
+            size_limit: None,
+            tags: Vec::new(),
+            parallel_witness: cfg!(feature = "parallel"),
             next_constraint: 1,
             wires_counted: false,
             constrained: false,
         }
     }
 
+    // A constraint system that only counts the wires and constraints of the synthesizer,
+    // without storing the constraints. Use it to estimate the size of a circuit
+    // with `set_constraints`; the R1CS instance can't be built from it.
+    pub fn dry_run() -> Self {
+        let mut cs = Self::new();
+        cs.dry_run = true;
+        cs
+    }
+
     pub fn is_witness_gen(&self) -> bool {
         self.mode == Mode::WitnessGen
     }
 
+    pub(crate) fn start_trace(&mut self) {
+        self.trace = Some(vec![]);
+    }
+
+    pub(crate) fn take_trace(&mut self) -> Option<Vec<TraceOp<F>>> {
+        self.trace.take()
+    }
+
+    fn is_recording(&self) -> bool {
+        self.trace.is_some() && self.phase == Phase::Synthesize && self.trace_depth == 0
+    }
+
+    // Record an operation to the trace.
+    // Operations called from within a recorded operation (e.g. the `mul` in `div`)
+    // are not recorded, since replaying the outer operation calls them again.
+    fn record(&mut self, op: TraceOp<F>) {
+        if self.is_recording() {
+            self.trace.as_mut().unwrap().push(op);
+        }
+    }
+
     fn alloc_wire(&mut self) -> Wire<F> {
         let wire = if self.phase == Phase::CounterWires {
             self.num_total_wires = self.num_total_wires.map_or(Some(2), |x| Some(x + 1));
@@ -329,12 +454,15 @@ impl<F: Field> ConstraintSystem<F> {
         };
 
         self.next_wire_id += 1;
+        self.check_size_limit();
+
         wire
     }
 
     // Allocate an unconstrained variable.
     // Use `alloc_const` to allocate a constant value.
     pub fn alloc_var(&mut self, val: F) -> Wire<F> {
+        self.record(TraceOp::AllocVar(val));
         let wire = self.alloc_wire();
         if self.is_witness_gen() {
             self.wires[wire.index] = val;
@@ -344,6 +472,7 @@ impl<F: Field> ConstraintSystem<F> {
 
     // Allocate a private value and return the index of the allocated wire
     pub fn alloc_priv_input(&mut self) -> Wire<F> {
+        self.record(TraceOp::AllocPrivInput);
         let wire = self.alloc_wire();
         if self.phase == Phase::CounterWires {
             self.num_priv_inputs = self.num_priv_inputs.map_or(Some(1), |x| Some(x + 1));
@@ -357,8 +486,14 @@ impl<F: Field> ConstraintSystem<F> {
         (0..n).map(|_| self.alloc_priv_input()).collect()
     }
 
+    // Same as `alloc_priv_inputs`, for a number of inputs known at compile time
+    pub fn alloc_priv_inputs_arr<const N: usize>(&mut self) -> [Wire<F>; N] {
+        std::array::from_fn(|_| self.alloc_priv_input())
+    }
+
     // Allocate a public input wire.
     pub fn alloc_pub_input(&mut self) -> Wire<F> {
+        self.record(TraceOp::AllocPubInput);
         let wire = if self.phase == Phase::CounterWires {
             self.num_total_wires = self.num_total_wires.map_or(Some(2), |x| Some(x + 1));
             self.num_pub_inputs = self.num_pub_inputs.map_or(Some(1), |x| Some(x + 1));
@@ -372,6 +507,8 @@ impl<F: Field> ConstraintSystem<F> {
         };
 
         self.next_wire_id += 1;
+        self.check_size_limit();
+
         wire
     }
 
@@ -379,8 +516,14 @@ impl<F: Field> ConstraintSystem<F> {
         (0..n).map(|_| self.alloc_pub_input()).collect()
     }
 
+    // Same as `alloc_pub_inputs`, for a number of inputs known at compile time
+    pub fn alloc_pub_inputs_arr<const N: usize>(&mut self) -> [Wire<F>; N] {
+        std::array::from_fn(|_| self.alloc_pub_input())
+    }
+
     // Expose a wire as a public input.
     pub fn expose_public(&mut self, wire: Wire<F>) {
+        self.record(TraceOp::ExposePublic(wire.id));
         if self.phase == Phase::CounterWires {
             self.num_pub_inputs = self.num_pub_inputs.map_or(Some(1), |x| Some(x + 1));
             // We need to count wires so we know which wires to expose.
@@ -388,6 +531,27 @@ impl<F: Field> ConstraintSystem<F> {
         }
     }
 
+    // Expose a wire as a public input, and label it with a name
+    // so that its value can be looked up with `public_output`.
+    pub fn expose_named(&mut self, name: &str, wire: Wire<F>) {
+        self.record(TraceOp::ExposeNamed(name.to_string(), wire.id));
+
+        self.trace_depth += 1;
+        self.expose_public(wire);
+        self.trace_depth -= 1;
+
+        if self.phase == Phase::Synthesize {
+            self.named_outputs.insert(name.to_string(), wire.index);
+        }
+    }
+
+    // Return the value of a wire exposed with `expose_named`.
+    // Returns `None` if there is no such wire or the witness hasn't been generated yet.
+    pub fn public_output(&self, name: &str) -> Option<F> {
+        let index = self.named_outputs.get(name)?;
+        self.wires.get(*index).copied()
+    }
+
     // Allocate a constant value.
     pub fn alloc_const(&mut self, c: F) -> Wire<F> {
         if let Some((id, index)) = self.constants.get(&c) {
@@ -408,11 +572,91 @@ impl<F: Field> ConstraintSystem<F> {
         self.alloc_const(F::ZERO)
     }
 
-    fn next_constraint_offset(&mut self) -> u64 {
-        let next_constraint = self.next_constraint;
+    // 2^i, read from a table that is shared by all the gadgets of the circuit.
+    // The table is extended on demand, so each power is only computed once.
+    pub fn pow_of_two(&mut self, i: usize) -> F {
+        while self.pows_of_two.len() <= i {
+            let next = self.pows_of_two.last().map_or(F::ONE, |pow| pow.double());
+            self.pows_of_two.push(next);
+        }
+
+        self.pows_of_two[i]
+    }
+
+    // Add the rows of the next constraint to the matrices
+    fn push_constraint(&mut self, a: Vec<(usize, F)>, b: Vec<(usize, F)>, c: Vec<(usize, F)>) {
+        self.A_rows.push(a);
+        self.B_rows.push(b);
+        self.C_rows.push(c);
+
         self.next_constraint += 1;
+        self.check_size_limit();
+    }
 
-        (next_constraint as u64) * (self.z_len() as u64)
+    // Count a constraint without storing it (in dry-run mode)
+    fn skip_constraint(&mut self) {
+        self.next_constraint += 1;
+        self.check_size_limit();
+        self.tag_constraint();
+    }
+
+    // Record the tags of the constraint that was just added
+    fn tag_constraint(&mut self) {
+        if !self.tags.is_empty() {
+            self.constraint_tags
+                .insert(self.next_constraint - 1, self.tags.join("/"));
+        }
+    }
+
+    // Set a soft limit on the number of constraints and wires.
+    // `on_exceeded` is called once, when the circuit grows beyond the limit
+    // while the constraints are generated, with the tags of the gadget that exceeded it.
+    pub fn set_size_limit(
+        &mut self,
+        max_constraints: usize,
+        max_wires: usize,
+        on_exceeded: impl Fn(&SizeLimitWarning) + Send + Sync + 'static,
+    ) {
+        self.size_limit = Some(SizeLimit {
+            max_constraints,
+            max_wires,
+            on_exceeded: Arc::new(on_exceeded),
+            exceeded: false,
+        });
+    }
+
+    // Run `f` with `tag` pushed onto the tags that are reported by `set_size_limit`,
+    // e.g. to label the constraints of a gadget.
+    pub fn with_tag<T>(&mut self, tag: &str, f: impl FnOnce(&mut Self) -> T) -> T {
+        self.tags.push(tag.to_string());
+        let out = f(self);
+        self.tags.pop();
+
+        out
+    }
+
+    fn check_size_limit(&mut self) {
+        if self.phase != Phase::Synthesize || self.mode != Mode::ConstraintsGen {
+            return;
+        }
+
+        let num_constraints = self.next_constraint;
+        let num_wires = self.next_wire_id - 1;
+
+        if let Some(limit) = self.size_limit.as_mut() {
+            if !limit.exceeded
+                && (num_constraints > limit.max_constraints || num_wires > limit.max_wires)
+            {
+                limit.exceeded = true;
+
+                let warning = SizeLimitWarning {
+                    num_constraints,
+                    num_wires,
+                    tag: self.tags.join("/"),
+                };
+                (limit.on_exceeded)(&warning);
+            }
+        }
     }
 
     // Assert that the given wire is binary at witness generation.
@@ -426,6 +670,71 @@ impl<F: Field> ConstraintSystem<F> {
         }
     }
 
+    // Record the output wire of the constraint that was just added,
+    // and fold the constraint into the running circuit hash.
+    fn finish_constraint(&mut self, output_wire: Option<usize>) {
+        self.output_wires.push(output_wire);
+        self.tag_constraint();
+
+        if let Some(mut transcript) = self.constraints_hash.take() {
+            self.append_constraint(&mut transcript, self.next_constraint - 1);
+            self.constraints_hash = Some(transcript);
+        }
+    }
+
+    fn append_entry(
+        transcript: &mut merlin::Transcript,
+        label: &'static [u8],
+        col: usize,
+        val: &F,
+    ) {
+        let mut val_bytes = vec![];
+        val.serialize_compressed(&mut val_bytes).unwrap();
+
+        transcript.append_u64(b"col", col as u64);
+        transcript.append_message(label, &val_bytes);
+    }
+
+    // Append the constraint `con` (other than the first one) to the transcript
+    fn append_constraint(&self, transcript: &mut merlin::Transcript, con: usize) {
+        let rows = [
+            (b"A", &self.A_rows[con - 1]),
+            (b"B", &self.B_rows[con - 1]),
+            (b"C", &self.C_rows[con - 1]),
+        ];
+
+        transcript.append_u64(b"row", con as u64);
+        for (label, row) in rows {
+            for (coeff_i, val) in row {
+                Self::append_entry(transcript, label, *coeff_i, val);
+            }
+        }
+    }
+
+    // Append the first constraint, which is only complete after synthesis,
+    // and the dimensions of the instance to the transcript, and squeeze the circuit hash.
+    fn finalize_circuit_hash(&self, mut transcript: merlin::Transcript) -> [u8; 32] {
+        let first_row = [
+            (b"A", &self.A_first),
+            (b"B", &self.B_first),
+            (b"C", &self.C_first),
+        ];
+
+        transcript.append_u64(b"row", 0);
+        for (label, coeffs) in first_row {
+            for (coeff_i, val) in coeffs {
+                Self::append_entry(&mut transcript, label, *coeff_i, val);
+            }
+        }
+
+        transcript.append_u64(b"num_cols", self.z_len() as u64);
+        transcript.append_u64(b"num_input", self.num_pub_inputs.unwrap_or(0) as u64);
+
+        let mut circuit_hash = [0u8; 32];
+        transcript.challenge_bytes(b"circuit_hash", &mut circuit_hash);
+        circuit_hash
+    }
+
     fn increment_tree_val(tree: &mut BTreeMap<usize, F>, key: usize, val: F) {
         if let Some(v) = tree.get(&key) {
             tree.insert(key, *v + val);
@@ -435,6 +744,7 @@ impl<F: Field> ConstraintSystem<F> {
     }
 
     pub fn add(&mut self, w1: Wire<F>, w2: Wire<F>) -> Wire<F> {
+        self.record(TraceOp::Add(w1.id, w2.id));
         let w3 = self.alloc_wire();
 
         if self.phase == Phase::Synthesize {
@@ -442,7 +752,7 @@ impl<F: Field> ConstraintSystem<F> {
                 // If this is a witness generation call,
                 // we assign the output of the gate here.
                 self.wires[w3.index] = self.wires[w1.index] + self.wires[w2.index];
-            } else {
+            } else if !self.dry_run {
                 // (w1 + w2) * 1 - w3 = 0
                 Self::increment_tree_val(&mut self.A_first, w1.index, F::ONE);
                 Self::increment_tree_val(&mut self.A_first, w2.index, F::ONE);
@@ -454,6 +764,11 @@ impl<F: Field> ConstraintSystem<F> {
     }
 
     pub fn sum(&mut self, wires: &[(Wire<F>, bool)]) -> Wire<F> {
+        if self.is_recording() {
+            self.record(TraceOp::Sum(
+                wires.iter().map(|(w, sign)| (w.id, *sign)).collect(),
+            ));
+        }
         let w3 = self.alloc_wire();
 
         if self.phase == Phase::Synthesize {
@@ -469,7 +784,7 @@ impl<F: Field> ConstraintSystem<F> {
                     }
                 }
                 self.wires[w3.index] = sum;
-            } else {
+            } else if !self.dry_run {
                 // (w1 + w2 + ... + w_n) * 1 - w3 = 0
                 for (w, sign) in wires {
                     let increment_by = if *sign { F::ONE } else { -F::ONE };
@@ -484,12 +799,13 @@ impl<F: Field> ConstraintSystem<F> {
     }
 
     pub fn add_const(&mut self, w1: Wire<F>, c: F) -> Wire<F> {
+        self.record(TraceOp::AddConst(w1.id, c));
         let w2 = self.alloc_wire();
 
         if self.phase == Phase::Synthesize {
             if self.is_witness_gen() {
                 self.wires[w2.index] = self.wires[w1.index] + c;
-            } else {
+            } else if !self.dry_run {
                 // (w1 + c) * 1 - w2 = 0
                 Self::increment_tree_val(&mut self.A_first, w1.index, F::ONE);
                 Self::increment_tree_val(&mut self.A_first, Self::ONE_WIRE_INDEX, c);
@@ -501,12 +817,13 @@ impl<F: Field> ConstraintSystem<F> {
     }
 
     pub fn sub(&mut self, w1: Wire<F>, w2: Wire<F>) -> Wire<F> {
+        self.record(TraceOp::Sub(w1.id, w2.id));
         let w3 = self.alloc_wire();
 
         if self.phase == Phase::Synthesize {
             if self.is_witness_gen() {
                 self.wires[w3.index] = self.wires[w1.index] - self.wires[w2.index];
-            } else {
+            } else if !self.dry_run {
                 // (w1 - w2) * 1 - w3 = 0
                 Self::increment_tree_val(&mut self.A_first, w1.index, F::ONE);
                 Self::increment_tree_val(&mut self.A_first, w2.index, -F::ONE);
@@ -519,12 +836,13 @@ impl<F: Field> ConstraintSystem<F> {
 
     // Subtract a constant value from a wire.
     pub fn sub_const(&mut self, w1: Wire<F>, c: F) -> Wire<F> {
+        self.record(TraceOp::SubConst(w1.id, c));
         let w2 = self.alloc_wire();
 
         if self.phase == Phase::Synthesize {
             if self.is_witness_gen() {
                 self.wires[w2.index] = self.wires[w1.index] - c;
-            } else {
+            } else if !self.dry_run {
                 // (w1 - c) * 1 - w2 = 0
                 Self::increment_tree_val(&mut self.A_first, w1.index, F::ONE);
                 Self::increment_tree_val(&mut self.A_first, Self::ONE_WIRE_INDEX, -c);
@@ -536,27 +854,22 @@ impl<F: Field> ConstraintSystem<F> {
     }
 
     pub fn neg(&mut self, w: Wire<F>) -> Wire<F> {
+        self.record(TraceOp::Neg(w.id));
         let w2 = self.alloc_wire();
 
         if self.phase == Phase::Synthesize {
             if self.is_witness_gen() {
                 self.wires[w2.index] = -self.wires[w.index];
+            } else if self.dry_run {
+                self.skip_constraint();
             } else {
                 // w * (1 * -1) - w2 = 0
-
-                let con = self.next_constraint_offset();
-
-                let a_key = con + (w.index as u64);
-                let b_key = con + Self::ONE_WIRE_INDEX as u64;
-                let c_key = con + w2.index as u64;
-
-                self.A.insert(a_key, F::ONE);
-                self.B.insert(b_key, -F::ONE);
-                self.C.insert(c_key, F::ONE);
-
-                self.A_nonzero_coeffs.push(vec![w.index]);
-                self.B_nonzero_coeffs.push(vec![Self::ONE_WIRE_INDEX]);
-                self.C_nonzero_coeffs.push(vec![w2.index]);
+                self.push_constraint(
+                    vec![(w.index, F::ONE)],
+                    vec![(Self::ONE_WIRE_INDEX, -F::ONE)],
+                    vec![(w2.index, F::ONE)],
+                );
+                self.finish_constraint(Some(w2.index));
             }
         }
 
@@ -572,6 +885,11 @@ impl<F: Field> ConstraintSystem<F> {
         b: &[(Wire<F>, F)],
         c: &[(Wire<F>, F)],
     ) -> Wire<F> {
+        if self.is_recording() {
+            let lc = |terms: &[(Wire<F>, F)]| terms.iter().map(|(w, c)| (w.id, *c)).collect();
+            self.record(TraceOp::Constrain(lc(a), lc(b), lc(c)));
+        }
+
         let w3 = self.alloc_wire();
 
         if self.phase == Phase::Synthesize {
@@ -580,62 +898,71 @@ impl<F: Field> ConstraintSystem<F> {
                 let b_comb: F = b.iter().map(|(w, c)| self.wires[w.index] * c).sum();
                 let c_comb: F = c.iter().map(|(w, c)| self.wires[w.index] * c).sum();
                 self.wires[w3.index] = a_comb * b_comb + c_comb;
+            } else if self.dry_run {
+                self.skip_constraint();
             } else {
-                let con = self.next_constraint_offset();
-
-                for a_i in a {
-                    let a_key = con + a_i.0.index as u64;
-                    self.A.insert(a_key, a_i.1);
-                }
+                let a_row = a.iter().map(|(w, c)| (w.index, *c)).collect();
+                let b_row = b.iter().map(|(w, c)| (w.index, *c)).collect();
+                let mut c_row: Vec<(usize, F)> = c.iter().map(|(w, c)| (w.index, -*c)).collect();
+                c_row.push((w3.index, F::ONE));
 
-                for b_i in b {
-                    let b_key = con + b_i.0.index as u64;
-                    self.B.insert(b_key, b_i.1);
-                }
-
-                for c_i in c {
-                    let c_key = con + c_i.0.index as u64;
-                    self.C.insert(c_key, -c_i.1);
-                }
+                self.push_constraint(a_row, b_row, c_row);
+                self.finish_constraint(Some(w3.index));
+            }
+        }
 
-                let c_key = con + w3.index as u64;
-                self.C.insert(c_key, F::ONE);
+        w3
+    }
 
-                let a_nonzero_coeffs = a.iter().map(|(w, _)| w.index).collect();
-                let b_nonzero_coeffs = b.iter().map(|(w, _)| w.index).collect();
-                let mut c_nonzero_coeffs: Vec<usize> = c.iter().map(|(w, _)| w.index).collect();
-                c_nonzero_coeffs.push(w3.index);
+    // Whether gates only need their outputs assigned, which is the case at witness generation.
+    // Gadgets can then assign the outputs of many gates at once with `assign_outputs`
+    // instead of calling `constrain` for each gate.
+    pub(crate) fn is_assigning(&self) -> bool {
+        self.phase == Phase::Synthesize && self.is_witness_gen() && !self.is_recording()
+    }
 
-                self.A_nonzero_coeffs.push(a_nonzero_coeffs);
-                self.B_nonzero_coeffs.push(b_nonzero_coeffs);
-                self.C_nonzero_coeffs.push(c_nonzero_coeffs);
-            }
+    // Allocate an output wire for each pair of input wires, and assign it `eval` of their values.
+    // This is what `constrain` does at witness generation, batched over independent gates.
+    pub(crate) fn assign_outputs(
+        &mut self,
+        inputs: &[(Wire<F>, Wire<F>)],
+        eval: impl Fn(F, F) -> F + Sync,
+    ) -> Vec<Wire<F>> {
+        let out = inputs
+            .iter()
+            .map(|_| self.alloc_wire())
+            .collect::<Vec<Wire<F>>>();
+
+        let input_indices = inputs
+            .iter()
+            .map(|(a, b)| (a.index, b.index))
+            .collect::<Vec<(usize, usize)>>();
+        let vals = eval_pairs(&self.wires, &input_indices, eval, self.parallel_witness);
+
+        for (w, val) in out.iter().zip(vals) {
+            self.wires[w.index] = val;
         }
 
-        w3
+        out
     }
 
     pub fn mul(&mut self, w1: Wire<F>, w2: Wire<F>) -> Wire<F> {
+        self.record(TraceOp::Mul(w1.id, w2.id));
         let w3 = self.alloc_wire();
 
         if self.phase == Phase::Synthesize {
             if self.is_witness_gen() {
                 self.wires[w3.index] = self.wires[w1.index] * self.wires[w2.index];
+            } else if self.dry_run {
+                self.skip_constraint();
             } else {
                 // w1 * w2 - w3 = 0
-                let con = self.next_constraint_offset();
-
-                let a_key = con + w1.index as u64;
-                let b_key = con + w2.index as u64;
-                let c_key = con + w3.index as u64;
-
-                self.A.insert(a_key, F::ONE);
-                self.B.insert(b_key, F::ONE);
-                self.C.insert(c_key, F::ONE);
-
-                self.A_nonzero_coeffs.push(vec![w1.index]);
-                self.B_nonzero_coeffs.push(vec![w2.index]);
-                self.C_nonzero_coeffs.push(vec![w3.index]);
+                self.push_constraint(
+                    vec![(w1.index, F::ONE)],
+                    vec![(w2.index, F::ONE)],
+                    vec![(w3.index, F::ONE)],
+                );
+                self.finish_constraint(Some(w3.index));
             }
         }
 
@@ -644,27 +971,23 @@ impl<F: Field> ConstraintSystem<F> {
 
     // Multiply a wire by a constant value
     pub fn mul_const(&mut self, w1: Wire<F>, c: F) -> Wire<F> {
+        self.record(TraceOp::MulConst(w1.id, c));
         let w3 = self.alloc_wire();
         if self.phase == Phase::Synthesize {
             if self.is_witness_gen() {
                 self.wires[w3.index] = self.wires[w1.index] * c;
+            } else if self.dry_run {
+                self.skip_constraint();
             } else {
                 // w1 * c - w3 = 0
 
                 // w1 * w2 - w3 = 0
-                let con = self.next_constraint_offset();
-
-                let a_key = con + w1.index as u64;
-                let b_key = con + Self::ONE_WIRE_INDEX as u64;
-                let c_key = con + w3.index as u64;
-
-                self.A.insert(a_key, c);
-                self.B.insert(b_key, F::ONE);
-                self.C.insert(c_key, F::ONE);
-
-                self.A_nonzero_coeffs.push(vec![w1.index]);
-                self.B_nonzero_coeffs.push(vec![Self::ONE_WIRE_INDEX]);
-                self.C_nonzero_coeffs.push(vec![w3.index]);
+                self.push_constraint(
+                    vec![(w1.index, c)],
+                    vec![(Self::ONE_WIRE_INDEX, F::ONE)],
+                    vec![(w3.index, F::ONE)],
+                );
+                self.finish_constraint(Some(w3.index));
             }
         }
 
@@ -673,29 +996,23 @@ impl<F: Field> ConstraintSystem<F> {
 
     // w1 * w2 + w3
     pub fn mul_add(&mut self, w1: Wire<F>, w2: Wire<F>, w3: Wire<F>) -> Wire<F> {
+        self.record(TraceOp::MulAdd(w1.id, w2.id, w3.id));
         let out = self.alloc_wire();
 
         if self.phase == Phase::Synthesize {
             if self.is_witness_gen() {
                 self.wires[out.index] =
                     self.wires[w1.index] * self.wires[w2.index] + self.wires[w3.index];
+            } else if self.dry_run {
+                self.skip_constraint();
             } else {
                 // w1 * w2 - ((-1 * w3) + out)  = 0
-                let con = self.next_constraint_offset();
-
-                let a_key = con + w1.index as u64;
-                let b_key = con + w2.index as u64;
-                let c_key = con + w3.index as u64;
-                let out_key = con + out.index as u64;
-
-                self.A.insert(a_key, F::ONE);
-                self.B.insert(b_key, F::ONE);
-                self.C.insert(c_key, -F::ONE);
-                self.C.insert(out_key, F::ONE);
-
-                self.A_nonzero_coeffs.push(vec![w1.index]);
-                self.B_nonzero_coeffs.push(vec![w2.index]);
-                self.C_nonzero_coeffs.push(vec![w3.index, out.index]);
+                self.push_constraint(
+                    vec![(w1.index, F::ONE)],
+                    vec![(w2.index, F::ONE)],
+                    vec![(w3.index, -F::ONE), (out.index, F::ONE)],
+                );
+                self.finish_constraint(Some(out.index));
             }
         }
 
@@ -709,6 +1026,9 @@ impl<F: Field> ConstraintSystem<F> {
     // This function will panic if the denominator is zero.
     // Use `div_or_zero` to handle division by zero.
     pub fn div(&mut self, w1: Wire<F>, w2: Wire<F>) -> Wire<F> {
+        self.record(TraceOp::Div(w1.id, w2.id));
+        self.trace_depth += 1;
+
         let w2_inv = self.alloc_wire();
 
         if self.phase == Phase::Synthesize {
@@ -726,11 +1046,15 @@ impl<F: Field> ConstraintSystem<F> {
         let one = self.one();
         self.assert_equal(w2 * w2_inv, one, "");
 
+        self.trace_depth -= 1;
         w3
     }
 
     // If the denominator is zero, the output is assigned to zero.
     pub fn div_or_zero(&mut self, w1: Wire<F>, w2: Wire<F>) -> Wire<F> {
+        self.record(TraceOp::DivOrZero(w1.id, w2.id));
+        self.trace_depth += 1;
+
         let w2_inv = self.alloc_wire();
 
         if self.phase == Phase::Synthesize {
@@ -748,36 +1072,59 @@ impl<F: Field> ConstraintSystem<F> {
         let conditional = !(w2.is_zero());
         self.assert_equal(w2 * w2_inv, conditional, "");
 
+        self.trace_depth -= 1;
         w3
     }
 
+    // Panics at witness generation if the wires aren't equal
     pub fn assert_equal(&mut self, w1: Wire<F>, w2: Wire<F>, msg: &str) {
+        self.try_assert_equal(w1, w2, msg)
+            .unwrap_or_else(|err| panic!("{}", err.label));
+    }
+
+    // Same as `assert_equal`, but returns an error at witness generation
+    // if the wires aren't equal instead of panicking.
+    pub fn try_assert_equal(
+        &mut self,
+        w1: Wire<F>,
+        w2: Wire<F>,
+        msg: &str,
+    ) -> Result<(), ConstraintError<F>> {
+        if self.is_recording() {
+            self.record(TraceOp::AssertEqual(w1.id, w2.id, msg.to_string()));
+        }
+
         if self.phase == Phase::Synthesize {
             if self.is_witness_gen() {
                 let assigned_w1 = self.wires[w1.index];
                 let assigned_w2 = self.wires[w2.index];
 
                 if assigned_w1 != assigned_w2 {
-                    panic!("{}", msg);
+                    return Err(ConstraintError {
+                        label: msg.to_string(),
+                        left: assigned_w1,
+                        right: assigned_w2,
+                    });
                 }
+            } else if self.dry_run {
+                self.skip_constraint();
             } else {
-                let con = self.next_constraint_offset();
-
                 // W1 * 1 == w2
+                self.push_constraint(
+                    vec![(w1.index, F::ONE)],
+                    vec![(Self::ONE_WIRE_INDEX, F::ONE)],
+                    vec![(w2.index, F::ONE)],
+                );
+                self.finish_constraint(None);
 
-                let a_key = con + w1.index as u64;
-                let b_key = con + Self::ONE_WIRE_INDEX as u64;
-                let c_key = con + w2.index as u64;
-
-                self.A.insert(a_key, F::ONE);
-                self.B.insert(b_key, F::ONE);
-                self.C.insert(c_key, F::ONE);
-
-                self.A_nonzero_coeffs.push(vec![w1.index]);
-                self.B_nonzero_coeffs.push(vec![Self::ONE_WIRE_INDEX]);
-                self.C_nonzero_coeffs.push(vec![w2.index]);
+                if !msg.is_empty() {
+                    self.labels
+                        .insert(self.next_constraint - 1, msg.to_string());
+                }
             }
         }
+
+        Ok(())
     }
 
     pub fn is_equal(&mut self, w1: Wire<F>, w2: Wire<F>) -> Wire<F> {
@@ -790,29 +1137,34 @@ impl<F: Field> ConstraintSystem<F> {
         Conditional::if_then(sel, out, self)
     }
 
+    // Build the sub-circuit only if `cond` is true.
+    // `cond` is decided by the host (not a wire), so it must be the same
+    // in every run of the synthesizer, and no constraints are emitted when it's false.
+    pub fn constrain_lazy<B: FnOnce(&mut Self)>(&mut self, cond: bool, build: B) {
+        if cond {
+            build(self);
+        }
+    }
+
     pub fn assert_zero(&mut self, w: Wire<F>) {
+        self.record(TraceOp::AssertZero(w.id));
+
         if self.phase == Phase::Synthesize {
             if self.is_witness_gen() {
                 let assigned_w = self.wires[w.index];
                 if assigned_w != F::ZERO {
                     panic!("{:?} should be zero but is {:?}", w.id, assigned_w);
                 }
+            } else if self.dry_run {
+                self.skip_constraint();
             } else {
                 // W * W = 0
-
-                let con = self.next_constraint_offset();
-
-                let a_key = con + w.index as u64;
-                let b_key = con + w.index as u64;
-                let c_key = con + Self::ONE_WIRE_INDEX as u64;
-
-                self.A.insert(a_key, F::ONE);
-                self.B.insert(b_key, F::ONE);
-                self.C.insert(c_key, F::ZERO);
-
-                self.A_nonzero_coeffs.push(vec![w.index]);
-                self.B_nonzero_coeffs.push(vec![w.index]);
-                self.C_nonzero_coeffs.push(vec![Self::ONE_WIRE_INDEX]);
+                self.push_constraint(
+                    vec![(w.index, F::ONE)],
+                    vec![(w.index, F::ONE)],
+                    vec![(Self::ONE_WIRE_INDEX, F::ZERO)],
+                );
+                self.finish_constraint(None);
             }
         }
     }
@@ -820,6 +1172,8 @@ impl<F: Field> ConstraintSystem<F> {
     // Return a binary wire that is 1 if the input wire is zero and 0 otherwise.
     pub fn is_zero(&mut self, w: Wire<F>) -> Wire<F> {
         // Taking the same approach as the IsZero template form circomlib
+        self.record(TraceOp::IsZero(w.id));
+        self.trace_depth += 1;
 
         let inv = self.alloc_wire();
         if self.is_witness_gen() {
@@ -837,6 +1191,8 @@ impl<F: Field> ConstraintSystem<F> {
         let out = self.constrain(&[(w, -F::ONE)], &[(inv, F::ONE)], &[(one, F::ONE)]);
 
         self.assert_zero(out * w);
+
+        self.trace_depth -= 1;
         out
     }
 
@@ -862,6 +1218,44 @@ impl<F: Field> ConstraintSystem<F> {
         one - w
     }
 
+    // The number of constraints, including the first constraint that holds all the additions
+    pub fn num_constraints(&self) -> usize {
+        self.num_constraints.expect("Constraints not yet set")
+    }
+
+    // The number of wires, including the wire that is always "1"
+    pub fn num_wires(&self) -> usize {
+        self.num_total_wires
+            .expect("Number of wires not yet counted")
+    }
+
+    // The number of public inputs, including the exposed wires
+    pub fn num_public_inputs(&self) -> usize {
+        self.num_pub_inputs
+            .expect("Number of wires not yet counted")
+    }
+
+    pub fn num_private_inputs(&self) -> usize {
+        self.num_priv_inputs
+            .expect("Number of wires not yet counted")
+    }
+
+    // The number of constraints added under each tag of `with_tag`.
+    // The constraints of a nested tag also count towards the outer tags,
+    // e.g. a constraint added under "to_addr/keccak" is counted in "to_addr" and "to_addr/keccak".
+    // The constraints added outside of `with_tag` aren't counted.
+    pub fn constraint_histogram(&self) -> BTreeMap<String, usize> {
+        let mut histogram = BTreeMap::new();
+        for tag in self.constraint_tags.values() {
+            for (i, _) in tag.match_indices('/') {
+                *histogram.entry(tag[..i].to_string()).or_insert(0) += 1;
+            }
+            *histogram.entry(tag.clone()).or_insert(0) += 1;
+        }
+
+        histogram
+    }
+
     // Return the number of private wires
     pub fn num_vars(&self) -> usize {
         if self.num_total_wires.is_none() {
@@ -931,10 +1325,12 @@ impl<F: Field> ConstraintSystem<F> {
         }
 
         // Assign public and private inputs to the wires
-        self.wires = [&[F::ONE], pub_inputs].concat().to_vec();
-        self.wires.resize(self.priv_wires_offset(), F::ZERO);
-        self.wires.extend_from_slice(priv_inputs);
-        self.wires.resize(self.z_len(), F::ZERO);
+        let priv_wires_offset = self.priv_wires_offset();
+        self.wires = self.alloc_wires(self.z_len());
+        self.wires[Self::ONE_WIRE_INDEX] = F::ONE;
+        self.wires[1..(1 + pub_inputs.len())].copy_from_slice(pub_inputs);
+        self.wires[priv_wires_offset..(priv_wires_offset + priv_inputs.len())]
+            .copy_from_slice(priv_inputs);
 
         self.synthesize(&synthesizer, Mode::WitnessGen);
 
@@ -943,6 +1339,41 @@ impl<F: Field> ConstraintSystem<F> {
         witness
     }
 
+    // Evaluate batches of independent gates across threads at witness generation.
+    // This is the default when the `parallel` feature is enabled, and has no effect without it.
+    pub fn set_parallel_witness(&mut self, parallel: bool) {
+        self.parallel_witness = parallel;
+    }
+
+    // Memory-map the wires onto the file at `path` at witness generation,
+    // instead of keeping them in memory. The file is overwritten.
+    #[cfg(feature = "mmap")]
+    pub fn set_mmap_backing(&mut self, path: &Path) {
+        self.mmap_path = Some(path.to_path_buf());
+    }
+
+    // Allocate `len` zero wires, in the memory-mapped file if one is set.
+    fn alloc_wires(&self, len: usize) -> Wires<F> {
+        #[cfg(feature = "mmap")]
+        if let Some(path) = &self.mmap_path {
+            let wires = crate::frontend::wires::MmapWires::zeros(path, len)
+                .unwrap_or_else(|err| panic!("Failed to map the wires onto {:?}: {}", path, err));
+            return Wires::Mmap(wires);
+        }
+
+        Wires::zeros(len)
+    }
+
+    // Generate the witness from a typed input
+    pub fn gen_witness_from_input<S: Fn(&mut ConstraintSystem<F>), I: CircuitInput<F>>(
+        &mut self,
+        synthesizer: S,
+        input: &I,
+    ) -> Vec<F> {
+        let (pub_inputs, priv_inputs) = input.flatten();
+        self.gen_witness(synthesizer, &pub_inputs, &priv_inputs)
+    }
+
     pub fn set_constraints<S: Fn(&mut ConstraintSystem<F>)>(&mut self, synthesizer: &S) {
         if self.constrained {
             panic!("Constraints already set");
@@ -950,6 +1381,10 @@ impl<F: Field> ConstraintSystem<F> {
 
         self.count_wires(&synthesizer);
 
+        if !self.dry_run {
+            self.constraints_hash = Some(merlin::Transcript::new(Self::CIRCUIT_HASH_LABEL));
+        }
+
         let gen_constraints_timer = profiler_start("Generating constraints");
         self.synthesize(synthesizer, Mode::ConstraintsGen);
         profiler_end(gen_constraints_timer);
@@ -959,14 +1394,40 @@ impl<F: Field> ConstraintSystem<F> {
             self.num_pub_inputs = Some(0);
         }
 
+        // Likewise for circuits without private inputs.
+        if self.num_priv_inputs.is_none() {
+            self.num_priv_inputs = Some(0);
+        }
+
+        if let Some(transcript) = self.constraints_hash.take() {
+            self.circuit_hash = Some(self.finalize_circuit_hash(transcript));
+        }
+
         self.constrained = true;
     }
 
+    // The hash of the constraints, which is maintained while the constraints are generated
+    // so querying it doesn't require going over the R1CS instance again.
+    pub fn circuit_hash(&self) -> [u8; 32] {
+        if !self.constrained {
+            panic!("Constraints not yet set");
+        }
+
+        match self.circuit_hash {
+            Some(circuit_hash) => circuit_hash,
+            None => panic!("Constraints are not hashed in dry-run mode"),
+        }
+    }
+
     pub fn to_r1cs(&self) -> R1CS<F> {
         if !self.constrained {
             panic!("Constraints not yet set");
         }
 
+        if self.dry_run {
+            panic!("Constraints are not stored in dry-run mode");
+        }
+
         let constructing_r1cs = profiler_start("Constructing R1CS");
 
         let mut A_entries = vec![];
@@ -1000,29 +1461,18 @@ impl<F: Field> ConstraintSystem<F> {
         }
 
         for con in 1..self.num_constraints.unwrap() {
-            let offset = con as u64 * self.z_len() as u64;
-            for coeff_i in &self.A_nonzero_coeffs[con - 1] {
-                A_entries.push(SparseMatrixEntry {
+            let rows = [
+                (&mut A_entries, &self.A_rows[con - 1]),
+                (&mut B_entries, &self.B_rows[con - 1]),
+                (&mut C_entries, &self.C_rows[con - 1]),
+            ];
+
+            for (entries, row) in rows {
+                entries.extend(row.iter().map(|(coeff_i, val)| SparseMatrixEntry {
                     row: con,
                     col: *coeff_i,
-                    val: *self.A.get(&(*coeff_i as u64 + offset)).unwrap(),
-                });
-            }
-
-            for coeff_i in &self.B_nonzero_coeffs[con - 1] {
-                B_entries.push(SparseMatrixEntry {
-                    row: con,
-                    col: *coeff_i,
-                    val: *self.B.get(&(*coeff_i as u64 + offset)).unwrap(),
-                });
-            }
-
-            for coeff_i in &self.C_nonzero_coeffs[con - 1] {
-                C_entries.push(SparseMatrixEntry {
-                    row: con,
-                    col: *coeff_i,
-                    val: *self.C.get(&(*coeff_i as u64 + offset)).unwrap(),
-                });
+                    val: *val,
+                }));
             }
         }
 
@@ -1058,6 +1508,76 @@ impl<F: Field> ConstraintSystem<F> {
         }
     }
 
+    // Render the constraints as `(Σ a_i·w_i) * (Σ b_i·w_i) = (Σ c_i·w_i)`, one per line,
+    // where w_i is the wire at index i of the Z vector.
+    // The constant wires are replaced by their values,
+    // and the constraints added by `assert_equal` are annotated with its message.
+    pub fn pretty_print(&self) -> String {
+        let r1cs = self.to_r1cs();
+        let num_constraints = self.num_constraints();
+
+        let mut constant_values = self
+            .constants
+            .iter()
+            .map(|(val, (_, index))| (*index, *val))
+            .collect::<BTreeMap<usize, F>>();
+        constant_values.insert(Self::ONE_WIRE_INDEX, F::ONE);
+
+        // Print the small negative values with a minus sign instead of as p - x
+        let fmt_scalar = |val: F| {
+            if val == F::ZERO {
+                return "0".to_string();
+            }
+
+            let (pos, neg) = (val.to_string(), (-val).to_string());
+            if neg.len() < pos.len() {
+                format!("-{}", neg)
+            } else {
+                pos
+            }
+        };
+
+        let fmt_lc = |matrix: &Matrix<F>| {
+            let mut terms = vec![vec![]; num_constraints];
+            for entry in &matrix.entries {
+                let term = match constant_values.get(&entry.col) {
+                    Some(val) => fmt_scalar(entry.val * val),
+                    None if entry.val == F::ONE => format!("w{}", entry.col),
+                    None if entry.val == -F::ONE => format!("-w{}", entry.col),
+                    None => format!("{}·w{}", fmt_scalar(entry.val), entry.col),
+                };
+                terms[entry.row].push(term);
+            }
+
+            terms
+                .into_iter()
+                .map(|terms| {
+                    if terms.is_empty() {
+                        "0".to_string()
+                    } else {
+                        terms.join(" + ")
+                    }
+                })
+                .collect::<Vec<String>>()
+        };
+
+        let (a, b, c) = (fmt_lc(&r1cs.A), fmt_lc(&r1cs.B), fmt_lc(&r1cs.C));
+
+        let mut out = String::new();
+        for con in 0..num_constraints {
+            out.push_str(&format!(
+                "{}: ({}) * ({}) = ({})",
+                con, a[con], b[con], c[con]
+            ));
+            if let Some(label) = self.constraint_label(con) {
+                out.push_str(&format!(" # {}", label));
+            }
+            out.push('\n');
+        }
+
+        out
+    }
+
     fn synthesize<S: Fn(&mut ConstraintSystem<F>)>(&mut self, synthesizer: &S, mode: Mode) {
         if !self.wires_counted {
             panic!("Number of wires not yet counted");
@@ -1085,66 +1605,249 @@ impl<F: Field> ConstraintSystem<F> {
     }
 
     pub fn is_sat(&self, witness: &[F], public_input: &[F]) -> bool {
+        self.which_unsat(witness, public_input).is_none()
+    }
+
+    // Returns the index of the first constraint that isn't satisfied,
+    // or None if all the constraints are satisfied.
+    // The label of the constraint, if any, can be looked up with `constraint_label`.
+    pub fn which_unsat(&self, witness: &[F], public_input: &[F]) -> Option<usize> {
         let z = R1CS::construct_z(witness, public_input);
 
         if !self.constrained {
             panic!("Constraints not yet set");
         }
 
-        // Check the first constraint, which encodes all the additions
+        if self.dry_run {
+            panic!("Constraints are not stored in dry-run mode");
+        }
 
-        let A_first_eval = self
-            .A_first
-            .keys()
-            .map(|coeff| z[*coeff] * self.A_first.get(coeff).unwrap())
-            .sum::<F>();
+        (0..self.num_constraints.unwrap()).find(|con| !self.is_constraint_sat(*con, &z))
+    }
 
-        let B_first_eval = self
-            .B_first
-            .keys()
-            .map(|coeff| z[*coeff] * self.B_first.get(coeff).unwrap())
-            .sum::<F>();
+    // The message passed to `assert_equal` when adding the constraint, if any
+    pub fn constraint_label(&self, con: usize) -> Option<&str> {
+        self.labels.get(&con).map(|label| label.as_str())
+    }
 
-        let C_first_eval = self
-            .C_first
-            .keys()
-            .map(|coeff| z[*coeff] * self.C_first.get(coeff).unwrap())
-            .sum::<F>();
+    // Check the witness obtained by applying `changed_wires` to `prev_witness`,
+    // assuming that `prev_witness` satisfies the constraints.
+    // `changed_wires` are pairs of an index into the witness and its new value.
+    // Only the constraints that involve a changed wire are evaluated.
+    pub fn is_sat_incremental(
+        &self,
+        prev_witness: &[F],
+        changed_wires: &[(usize, F)],
+        public_input: &[F],
+    ) -> bool {
+        let mut z = R1CS::construct_z(prev_witness, public_input);
 
-        if A_first_eval * B_first_eval != C_first_eval {
-            println!("First constraint not satisfied");
-            return false;
+        if !self.constrained {
+            panic!("Constraints not yet set");
         }
 
-        // Check rest of the constraints
-        for con in 1..self.num_constraints.unwrap() {
-            let offset = con as u64 * self.z_len() as u64;
-            let A_eval: F = self.A_nonzero_coeffs[con - 1]
-                .iter()
-                .map(|coeff| z[*coeff] * self.A.get(&(*coeff as u64 + offset)).unwrap())
-                .sum();
+        if self.dry_run {
+            panic!("Constraints are not stored in dry-run mode");
+        }
 
-            let B_eval: F = self.B_nonzero_coeffs[con - 1]
-                .iter()
-                .map(|coeff| z[*coeff] * self.B.get(&(*coeff as u64 + offset)).unwrap())
-                .sum();
+        // The witness starts at the second half of z
+        let witness_offset = z.len() / 2;
+        let mut changed = Vec::with_capacity(changed_wires.len());
+        for (i, val) in changed_wires {
+            assert!(*i < prev_witness.len(), "Wire {} is out of the witness", i);
+            z[witness_offset + i] = *val;
+            changed.push(witness_offset + i);
+        }
+        changed.sort();
 
-            let C_eval: F = self.C_nonzero_coeffs[con - 1]
-                .iter()
-                .map(|coeff| z[*coeff] * self.C.get(&(*coeff as u64 + offset)).unwrap())
-                .sum();
+        let is_changed = |coeff: &usize| changed.binary_search(coeff).is_ok();
 
-            if A_eval * B_eval != C_eval {
-                println!(
-                    "Constraint {} not satisfied, {} * {} != {}",
-                    con, A_eval, B_eval, C_eval
+        (0..self.num_constraints.unwrap())
+            .filter(|con| self.constraint_involves(*con, is_changed))
+            .all(|con| self.is_constraint_sat(con, &z))
+    }
+
+    // Check the witness against each set of public inputs.
+    // The constraints that don't involve the public inputs are only evaluated once.
+    pub fn is_sat_multi(&self, witness: &[F], pub_inputs: &[Vec<F>]) -> Vec<bool> {
+        if !self.constrained {
+            panic!("Constraints not yet set");
+        }
+
+        if self.dry_run {
+            panic!("Constraints are not stored in dry-run mode");
+        }
+
+        if pub_inputs.is_empty() {
+            return vec![];
+        }
+
+        let num_pub_inputs = self.num_pub_inputs.unwrap();
+        let is_pub = |coeff: &usize| *coeff >= 1 && *coeff <= num_pub_inputs;
+
+        let (pub_dependent, pub_independent): (Vec<usize>, Vec<usize>) =
+            (0..self.num_constraints.unwrap())
+                .partition(|con| self.constraint_involves(*con, is_pub));
+
+        let mut z = R1CS::construct_z(witness, &pub_inputs[0]);
+
+        let pub_independent_sat = pub_independent
+            .iter()
+            .all(|con| self.is_constraint_sat(*con, &z));
+
+        pub_inputs
+            .iter()
+            .map(|pub_input| {
+                assert_eq!(
+                    pub_input.len(),
+                    num_pub_inputs,
+                    "Number of public inputs does not match"
                 );
+
+                // The public inputs follow the one wire in z
+                z[1..=num_pub_inputs].copy_from_slice(pub_input);
+
+                pub_independent_sat
+                    && pub_dependent
+                        .iter()
+                        .all(|con| self.is_constraint_sat(*con, &z))
+            })
+            .collect()
+    }
+
+    // Whether any wire of the constraint satisfies `pred`.
+    fn constraint_involves(&self, con: usize, pred: impl Fn(&usize) -> bool + Copy) -> bool {
+        if con == 0 {
+            self.A_first.keys().any(pred)
+                || self.B_first.keys().any(pred)
+                || self.C_first.keys().any(pred)
+        } else {
+            [&self.A_rows, &self.B_rows, &self.C_rows]
+                .iter()
+                .any(|rows| rows[con - 1].iter().any(|(coeff_i, _)| pred(coeff_i)))
+        }
+    }
+
+    // Check a single constraint against the assignment `z`.
+    fn is_constraint_sat(&self, con: usize, z: &[F]) -> bool {
+        if con == 0 {
+            // The first constraint encodes all the additions
+            let A_first_eval = self
+                .A_first
+                .keys()
+                .map(|coeff| z[*coeff] * self.A_first.get(coeff).unwrap())
+                .sum::<F>();
+
+            let B_first_eval = self
+                .B_first
+                .keys()
+                .map(|coeff| z[*coeff] * self.B_first.get(coeff).unwrap())
+                .sum::<F>();
+
+            let C_first_eval = self
+                .C_first
+                .keys()
+                .map(|coeff| z[*coeff] * self.C_first.get(coeff).unwrap())
+                .sum::<F>();
+
+            if A_first_eval * B_first_eval != C_first_eval {
+                println!("First constraint not satisfied");
                 return false;
             }
+
+            return true;
+        }
+
+        let eval_row =
+            |row: &[(usize, F)]| -> F { row.iter().map(|(coeff, val)| z[*coeff] * val).sum() };
+
+        let A_eval = eval_row(&self.A_rows[con - 1]);
+        let B_eval = eval_row(&self.B_rows[con - 1]);
+        let C_eval = eval_row(&self.C_rows[con - 1]);
+
+        if A_eval * B_eval != C_eval {
+            println!(
+                "Constraint {} not satisfied, {} * {} != {}",
+                con, A_eval, B_eval, C_eval
+            );
+            return false;
         }
 
         true
     }
+
+    // Return the non-zero entries of a row of a matrix, sorted by the wire index,
+    // with the coefficients of a repeated wire summed.
+    fn row_entries(row: &[(usize, F)], skip: Option<usize>) -> Vec<(usize, F)> {
+        let mut entries = BTreeMap::<usize, F>::new();
+        for (i, val) in row {
+            *entries.entry(*i).or_insert(F::ZERO) += val;
+        }
+
+        entries
+            .into_iter()
+            .filter(|(i, val)| Some(*i) != skip && *val != F::ZERO)
+            .collect()
+    }
+
+    fn scale_row(row: &[(usize, F)], s: F) -> Vec<(usize, F)> {
+        row.iter().map(|(i, val)| (*i, *val * s)).collect()
+    }
+
+    // Return the indices of the constraints that are duplicates or scalar multiples
+    // of a preceding constraint.
+    // Constraints that only differ in the wire they allocate for their output
+    // compute the same value twice, so they are reported as well.
+    // This is a best-effort diagnostic, and the first constraint (which encodes all the additions)
+    // is never reported.
+    pub fn find_redundant_constraints(&self) -> Vec<usize> {
+        if !self.constrained {
+            panic!("Constraints not yet set");
+        }
+
+        if self.dry_run {
+            panic!("Constraints are not stored in dry-run mode");
+        }
+
+        let mut seen = BTreeMap::new();
+        let mut redundant = vec![];
+
+        for con in 1..self.num_constraints.unwrap() {
+            let output_wire = self.output_wires[con - 1];
+
+            let a = Self::row_entries(&self.A_rows[con - 1], None);
+            let b = Self::row_entries(&self.B_rows[con - 1], None);
+            let c = Self::row_entries(&self.C_rows[con - 1], output_wire);
+
+            // Normalize the constraint so that scalar multiples map to the same key.
+            let key = if a.is_empty() || b.is_empty() {
+                let c_scale = c.first().map_or(F::ONE, |(_, val)| val.inverse().unwrap());
+                (vec![], vec![], Self::scale_row(&c, c_scale))
+            } else {
+                let a_scale = a[0].1.inverse().unwrap();
+                let b_scale = b[0].1.inverse().unwrap();
+                let a = Self::scale_row(&a, a_scale);
+                let b = Self::scale_row(&b, b_scale);
+                let c = Self::scale_row(&c, a_scale * b_scale);
+
+                // Multiplication is commutative
+                if a <= b {
+                    (a, b, c)
+                } else {
+                    (b, a, c)
+                }
+            };
+
+            let key = (key, output_wire.is_some());
+            if seen.contains_key(&key) {
+                redundant.push(con);
+            } else {
+                seen.insert(key, con);
+            }
+        }
+
+        redundant
+    }
 }
 
 pub fn init_circuit<F: Field>(
@@ -1165,11 +1868,36 @@ macro_rules! init_constraint_system {
     };
 }
 
+// Evaluate `eval` on the values of each pair of wires.
+// The pairs are evaluated across threads if `parallel` is set and the `parallel` feature is enabled.
+fn eval_pairs<F: Field>(
+    wires: &[F],
+    pairs: &[(usize, usize)],
+    eval: impl Fn(F, F) -> F + Sync,
+    parallel: bool,
+) -> Vec<F> {
+    let eval_pair = |(a, b): &(usize, usize)| eval(wires[*a], wires[*b]);
+
+    #[cfg(feature = "parallel")]
+    if parallel {
+        // Keep a 64-bit word on a single thread
+        return pairs.par_iter().with_min_len(64).map(eval_pair).collect();
+    }
+    #[cfg(not(feature = "parallel"))]
+    let _ = parallel;
+
+    pairs.iter().map(eval_pair).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::frontend::test_utils::{synthetic_circuit, test_satisfiability, test_var_pub_input};
-    use ark_ff::Field;
+    use crate::frontend::gadgets::{and, form_le_bits, not_a_and_b, or, to_le_bits, xor};
+    use crate::frontend::test_utils::{
+        mock_circuit, synthetic_circuit, test_satisfiability, test_var_pub_input,
+    };
+    use crate::frontend::trace::{record_trace, replay_trace};
+    use ark_ff::{Field, PrimeField};
 
     type F = ark_secq256k1::Fr;
 
@@ -1224,6 +1952,119 @@ mod tests {
         assert_eq!(witness, expected_witness);
     }
 
+    // A typed input to a circuit that exposes the product of two private inputs
+    struct ProductInput {
+        a: F,
+        b: F,
+    }
+
+    impl CircuitInput<F> for ProductInput {
+        fn flatten(&self) -> (Vec<F>, Vec<F>) {
+            (vec![self.a * self.b], vec![self.a, self.b])
+        }
+    }
+
+    #[test]
+    fn test_gen_witness_from_input() {
+        let synthesizer = |cs: &mut ConstraintSystem<F>| {
+            let a = cs.alloc_priv_input();
+            let b = cs.alloc_priv_input();
+            cs.expose_public(a * b);
+        };
+
+        let input = ProductInput {
+            a: F::from(3u32),
+            b: F::from(4u32),
+        };
+
+        let (pub_input, priv_input) = input.flatten();
+        assert_eq!(pub_input, vec![F::from(12u32)]);
+        assert_eq!(priv_input, vec![F::from(3u32), F::from(4u32)]);
+
+        let mut cs = ConstraintSystem::new();
+        cs.set_constraints(&synthesizer);
+
+        let witness = cs.gen_witness_from_input(synthesizer, &input);
+        assert!(cs.is_sat(&witness, &pub_input));
+    }
+
+    #[test]
+    fn test_expose_named() {
+        let synthesizer = |cs: &mut ConstraintSystem<F>| {
+            let a = cs.alloc_priv_input();
+            let b = cs.alloc_priv_input();
+            cs.expose_named("product", a * b);
+        };
+
+        let mut cs = ConstraintSystem::new();
+        cs.set_constraints(&synthesizer);
+
+        // The witness generation computes the exposed wires,
+        // so we can read the product without knowing it beforehand.
+        cs.gen_witness(synthesizer, &[F::ZERO], &[F::from(3u32), F::from(4u32)]);
+
+        assert_eq!(cs.public_output("product"), Some(F::from(12u32)));
+        assert_eq!(cs.public_output("unknown"), None);
+    }
+
+    // Bitwise gates on two 64-bit words, which are assigned in batches at witness generation
+    fn bitwise_circuit(cs: &mut ConstraintSystem<F>) {
+        let a = cs.alloc_priv_inputs(64);
+        let b = cs.alloc_priv_inputs(64);
+
+        let a_xor_b = xor(&a, &b);
+        let a_and_b = and(&a, &b);
+        let a_or_b = or(&a, &b);
+        let not_a_and_a_xor_b = not_a_and_b(&a, &a_xor_b);
+
+        for bit in [a_xor_b, a_and_b, a_or_b, not_a_and_a_xor_b].concat() {
+            cs.expose_public(bit);
+        }
+    }
+
+    #[test]
+    fn test_parallel_witness() {
+        let (a, b) = (0x0123456789abcdefu64, 0xfedcba9876543210u64);
+        let to_bits = |x: u64| (0..64).map(move |i| F::from((x >> i) & 1));
+        let priv_input = to_bits(a).chain(to_bits(b)).collect::<Vec<F>>();
+        let pub_input = [a ^ b, a & b, a | b, !a & (a ^ b)]
+            .into_iter()
+            .flat_map(to_bits)
+            .collect::<Vec<F>>();
+
+        let gen_witness = |parallel: bool| {
+            let mut cs = ConstraintSystem::new();
+            cs.set_parallel_witness(parallel);
+            cs.gen_witness(bitwise_circuit, &pub_input, &priv_input)
+        };
+
+        let witness = gen_witness(false);
+        assert_eq!(gen_witness(true), witness);
+
+        // The replayed trace adds the gates one by one instead of in batches
+        let trace = record_trace(&bitwise_circuit);
+        let mut replayed_cs = ConstraintSystem::new();
+        let replayed_witness =
+            replayed_cs.gen_witness(replay_trace(&trace), &pub_input, &priv_input);
+        assert_eq!(replayed_witness, witness);
+
+        let mut cs = ConstraintSystem::new();
+        cs.set_constraints(&bitwise_circuit);
+        assert!(cs.is_sat(&witness, &pub_input));
+    }
+
+    #[test]
+    #[should_panic(expected = "Constraints are not stored in dry-run mode")]
+    fn test_find_redundant_constraints_dry_run() {
+        let synthesizer = mock_circuit::<F>(4);
+
+        let mut cs = ConstraintSystem::dry_run();
+        cs.set_constraints(&synthesizer);
+        assert_eq!(cs.num_constraints(), 4);
+
+        cs.find_redundant_constraints();
+    }
+
     // ########################################
     // ########## Test the primitive operations ############
     // ########################################
@@ -1275,6 +2116,381 @@ mod tests {
         };
     }
 
+    #[test]
+    fn test_alloc_const_dedup() {
+        let synthesizer = |cs: &mut ConstraintSystem<F>| {
+            let a = cs.alloc_priv_input();
+
+            let c1 = cs.alloc_const(F::from(7u32));
+            let c2 = cs.alloc_const(F::from(7u32));
+            assert_eq!(c1.id(), c2.id());
+            assert_eq!(c1.index, c2.index);
+
+            let out = a * c1 + a * c2;
+            cs.expose_public(out);
+        };
+
+        let mut cs = ConstraintSystem::new();
+        cs.set_constraints(&synthesizer);
+
+        // a, the constant, the two products, and the sum, besides the wire that is always "1"
+        assert_eq!(cs.num_wires(), 6);
+
+        let witness = cs.gen_witness(synthesizer, &[F::from(42u32)], &[F::from(3u32)]);
+        assert!(cs.is_sat(&witness, &[F::from(42u32)]));
+    }
+
+    #[test]
+    fn test_constraint_histogram() {
+        let synthesizer = |cs: &mut ConstraintSystem<F>| {
+            let a = cs.alloc_priv_input();
+            let b = cs.alloc_priv_input();
+
+            let ab = a * b;
+            cs.with_tag("outer", |cs| {
+                let abb = ab * b;
+                cs.with_tag("inner", |cs| {
+                    let out = abb * a;
+                    cs.expose_public(out);
+                });
+            });
+        };
+
+        let mut cs = ConstraintSystem::new();
+        cs.set_constraints(&synthesizer);
+
+        assert_eq!(cs.num_constraints(), 4);
+
+        let histogram = cs.constraint_histogram();
+        assert_eq!(histogram.len(), 2);
+        assert_eq!(histogram["outer"], 2);
+        assert_eq!(histogram["outer/inner"], 1);
+    }
+
+    #[test]
+    fn test_size_accessors() {
+        let synthesizer = |cs: &mut ConstraintSystem<F>| {
+            let bits = cs.alloc_priv_inputs(8);
+            let out = form_le_bits(&bits);
+
+            cs.expose_public(out);
+        };
+
+        let mut cs = ConstraintSystem::new();
+        cs.set_constraints(&synthesizer);
+
+        // A multiplication by a power of two for each bit,
+        // and the constraint that holds the additions
+        assert_eq!(cs.num_constraints(), 9);
+        // The bits, their multiples, the sum, and the wire that is always "1"
+        assert_eq!(cs.num_wires(), 18);
+        assert_eq!(cs.num_public_inputs(), 1);
+        assert_eq!(cs.num_private_inputs(), 8);
+    }
+
+    #[test]
+    fn test_try_assert_equal() {
+        let synthesizer = |cs: &mut ConstraintSystem<F>| {
+            let a = cs.alloc_priv_input();
+            let b = cs.alloc_pub_input();
+
+            let a_sq = a * a;
+            let result = cs.try_assert_equal(a_sq, b, "square mismatch");
+
+            // Only the witness generation checks the values
+            if let (Some(a_sq), Some(b)) = (a_sq.val(cs), b.val(cs)) {
+                if a_sq == b {
+                    assert_eq!(result, Ok(()));
+                } else {
+                    assert_eq!(
+                        result,
+                        Err(ConstraintError {
+                            label: "square mismatch".to_string(),
+                            left: a_sq,
+                            right: b,
+                        })
+                    );
+                }
+            }
+        };
+
+        let mut cs = ConstraintSystem::new();
+        cs.set_constraints(&synthesizer);
+
+        let witness = cs.gen_witness(synthesizer, &[F::from(9u32)], &[F::from(3u32)]);
+        assert!(cs.is_sat(&witness, &[F::from(9u32)]));
+
+        // The witness is generated without panicking for a mismatched value,
+        // but doesn't satisfy the constraints
+        let witness = cs.gen_witness(synthesizer, &[F::from(10u32)], &[F::from(3u32)]);
+        assert!(!cs.is_sat(&witness, &[F::from(10u32)]));
+    }
+
+    #[test]
+    fn test_which_unsat() {
+        let synthesizer = |cs: &mut ConstraintSystem<F>| {
+            let a = cs.alloc_priv_input();
+            let b = cs.alloc_priv_input();
+            let expected = cs.alloc_pub_input();
+
+            // Constraint 1 (constraint 0 holds the additions)
+            let ab = a * b;
+            // Constraint 2
+            cs.assert_equal(ab, expected, "product mismatch");
+        };
+
+        let priv_input = [F::from(3u32), F::from(4u32)];
+        let pub_input = [F::from(12u32)];
+
+        let mut cs = ConstraintSystem::new();
+        cs.set_constraints(&synthesizer);
+        let witness = cs.gen_witness(synthesizer, &pub_input, &priv_input);
+        assert_eq!(cs.which_unsat(&witness, &pub_input), None);
+
+        // Corrupting the product breaks the multiplication first
+        let mut corrupted_witness = witness.clone();
+        corrupted_witness[2] += F::ONE;
+        assert_eq!(cs.which_unsat(&corrupted_witness, &pub_input), Some(1));
+        assert_eq!(cs.constraint_label(1), None);
+
+        // and corrupting the public input only breaks the labeled assertion
+        let corrupted_pub_input = [F::from(13u32)];
+        assert_eq!(cs.which_unsat(&witness, &corrupted_pub_input), Some(2));
+        assert_eq!(cs.constraint_label(2), Some("product mismatch"));
+    }
+
+    #[test]
+    fn test_is_sat_incremental() {
+        let synthesizer = |cs: &mut ConstraintSystem<F>| {
+            let a = cs.alloc_priv_input();
+            let b = cs.alloc_priv_input();
+            let c = cs.alloc_priv_input();
+
+            let ab = a * b;
+            let out = ab + c;
+            cs.expose_public(out);
+        };
+
+        let priv_input = [F::from(3u32), F::from(4u32), F::from(5u32)];
+        let pub_input = [F::from(17u32)];
+
+        let mut cs = ConstraintSystem::new();
+        cs.set_constraints(&synthesizer);
+        let witness = cs.gen_witness(synthesizer, &pub_input, &priv_input);
+        assert!(cs.is_sat(&witness, &pub_input));
+
+        // Change each wire of the witness in turn, and compare against the full check
+        for i in 0..witness.len() {
+            for val in [witness[i], witness[i] + F::ONE] {
+                let mut new_witness = witness.clone();
+                new_witness[i] = val;
+
+                assert_eq!(
+                    cs.is_sat_incremental(&witness, &[(i, val)], &pub_input),
+                    cs.is_sat(&new_witness, &pub_input)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_is_sat_multi() {
+        // Membership of the public input in the private set {x, y}:
+        // (a - x) * (a - y) == 0
+        let synthesizer = |cs: &mut ConstraintSystem<F>| {
+            let a = cs.alloc_pub_input();
+            let x = cs.alloc_priv_input();
+            let y = cs.alloc_priv_input();
+
+            let prod = cs.constrain(
+                &[(a, F::ONE), (x, -F::ONE)],
+                &[(a, F::ONE), (y, -F::ONE)],
+                &[],
+            );
+            cs.assert_zero(prod);
+        };
+
+        let x = F::from(3u32);
+        let y = F::from(7u32);
+        let pub_inputs = vec![vec![x], vec![y], vec![F::from(5u32)]];
+
+        let mut cs = ConstraintSystem::new();
+        cs.set_constraints(&synthesizer);
+        let witness = cs.gen_witness(synthesizer, &pub_inputs[0], &[x, y]);
+
+        let expected = pub_inputs
+            .iter()
+            .map(|pub_input| cs.is_sat(&witness, pub_input))
+            .collect::<Vec<bool>>();
+
+        assert_eq!(expected, vec![true, true, false]);
+        assert_eq!(cs.is_sat_multi(&witness, &pub_inputs), expected);
+    }
+
+    #[test]
+    fn test_constrain_lazy() {
+        let num_constraints = |cond: bool| {
+            let synthesizer = move |cs: &mut ConstraintSystem<F>| {
+                let a = cs.alloc_priv_input();
+                let b = a * a;
+
+                cs.constrain_lazy(cond, |cs| {
+                    let c = b * b;
+                    cs.assert_zero(c - b);
+                });
+
+                cs.expose_public(b);
+            };
+
+            let mut cs = ConstraintSystem::new();
+            cs.set_constraints(&synthesizer);
+
+            let pub_input = [F::ONE];
+            let witness = cs.gen_witness(synthesizer, &pub_input, &[F::ONE]);
+            assert!(cs.is_sat(&witness, &pub_input));
+
+            cs.num_constraints.unwrap()
+        };
+
+        let baseline = {
+            let synthesizer = |cs: &mut ConstraintSystem<F>| {
+                let a = cs.alloc_priv_input();
+                let b = a * a;
+                cs.expose_public(b);
+            };
+
+            let mut cs = ConstraintSystem::new();
+            cs.set_constraints(&synthesizer);
+            cs.num_constraints.unwrap()
+        };
+
+        // A false condition doesn't emit any constraint
+        assert_eq!(num_constraints(false), baseline);
+        assert_eq!(num_constraints(true), baseline + 2);
+    }
+
+    // Hash all the constraints at once, after synthesis
+    fn batch_circuit_hash(cs: &ConstraintSystem<F>) -> [u8; 32] {
+        let mut transcript = merlin::Transcript::new(ConstraintSystem::<F>::CIRCUIT_HASH_LABEL);
+        for con in 1..cs.num_constraints.unwrap() {
+            cs.append_constraint(&mut transcript, con);
+        }
+
+        cs.finalize_circuit_hash(transcript)
+    }
+
+    #[test]
+    fn test_circuit_hash() {
+        let (synthesizer, _, _, _) = synthetic_circuit();
+        let mut cs = ConstraintSystem::<F>::new();
+        cs.set_constraints(&synthesizer);
+
+        assert_eq!(cs.circuit_hash(), batch_circuit_hash(&cs));
+
+        // A different circuit should have a different hash
+        let synthesizer = |cs: &mut ConstraintSystem<F>| {
+            let a = cs.alloc_priv_input();
+            let b = cs.alloc_pub_input();
+
+            let c = cs.mul_const(a, F::from(3u32));
+            cs.assert_equal(b, c * a, "");
+        };
+
+        let mut other_cs = ConstraintSystem::<F>::new();
+        other_cs.set_constraints(&synthesizer);
+
+        assert_eq!(other_cs.circuit_hash(), batch_circuit_hash(&other_cs));
+        assert_ne!(cs.circuit_hash(), other_cs.circuit_hash());
+    }
+
+    #[test]
+    fn test_alloc_inputs_arr() {
+        let synthesizer = |cs: &mut ConstraintSystem<F>| {
+            let x = cs.alloc_priv_inputs_arr::<3>();
+            let y = cs.alloc_pub_inputs_arr::<3>();
+
+            for (x, y) in x.into_iter().zip(y) {
+                cs.assert_equal(x * x, y, "");
+            }
+        };
+
+        let priv_inputs = [2u32, 3, 4].map(F::from);
+        let pub_inputs = [4u32, 9, 16].map(F::from);
+
+        test_satisfiability(synthesizer, &pub_inputs, &priv_inputs);
+    }
+
+    #[test]
+    fn test_pow_of_two() {
+        let mut cs = ConstraintSystem::<F>::new();
+
+        let mut pow = F::ONE;
+        for i in 0..(F::MODULUS_BIT_SIZE as usize) {
+            assert_eq!(cs.pow_of_two(i), pow);
+            pow *= F::from(2u32);
+        }
+
+        // The bit decompositions of a circuit share the table
+        let synthesizer = |cs: &mut ConstraintSystem<F>| {
+            let x = cs.alloc_priv_input();
+            let y = cs.alloc_priv_input();
+
+            let x_bits = to_le_bits(x);
+            let y_bits = to_le_bits(y);
+            cs.expose_public(form_le_bits(&x_bits) + form_le_bits(&y_bits));
+        };
+
+        let mut cs = ConstraintSystem::<F>::new();
+        cs.set_constraints(&synthesizer);
+        assert_eq!(cs.pows_of_two.len(), F::MODULUS_BIT_SIZE as usize);
+
+        let (x, y) = (F::from(12345u32), F::from(678u32));
+        let witness = cs.gen_witness(synthesizer, &[x + y], &[x, y]);
+        assert!(cs.is_sat(&witness, &[x + y]));
+    }
+
+    #[test]
+    fn test_sparse_rows() {
+        let num_muls = 1 << 12;
+        let synthesizer = |cs: &mut ConstraintSystem<F>| {
+            let x = cs.alloc_priv_input();
+
+            let mut out = x;
+            for _ in 0..num_muls {
+                out = out * x;
+            }
+
+            cs.expose_public(out);
+        };
+
+        let mut cs = ConstraintSystem::<F>::new();
+        cs.set_constraints(&synthesizer);
+
+        // Each row only stores the wires of its constraint,
+        // so the storage grows with the number of constraints and not with their product with the wires.
+        let num_entries = [&cs.A_rows, &cs.B_rows, &cs.C_rows]
+            .iter()
+            .flat_map(|rows| rows.iter())
+            .map(|row| row.len())
+            .sum::<usize>();
+        assert_eq!(num_entries, 3 * num_muls);
+        assert!(cs.num_vars() > num_muls);
+
+        // `is_sat` agrees with the R1CS instance
+        let x = F::from(3u32);
+        let pub_input = [x.pow([num_muls as u64 + 1])];
+        let witness = cs.gen_witness(synthesizer, &pub_input, &[x]);
+        let r1cs = cs.to_r1cs();
+
+        assert!(cs.is_sat(&witness, &pub_input));
+        assert!(r1cs.is_sat(&witness, &pub_input));
+
+        let mut invalid_witness = witness.clone();
+        invalid_witness[1] += F::ONE;
+        assert!(!cs.is_sat(&invalid_witness, &pub_input));
+        assert!(!r1cs.is_sat(&invalid_witness, &pub_input));
+    }
+
     #[test]
     fn test_add() {
         test_op!(add);
@@ -1539,4 +2755,46 @@ mod tests {
             test_var_pub_input(synthesizer, &pub_inputs, &priv_inputs);
         }
     }
+
+    #[allow(unused_must_use)]
+    #[test]
+    fn test_size_limit() {
+        let synthesizer = |cs: &mut ConstraintSystem<F>| {
+            let a = cs.alloc_priv_input();
+            let b = cs.alloc_priv_input();
+
+            let c = cs.with_tag("small", |_| a * b);
+            cs.with_tag("outer", |cs| {
+                cs.with_tag("large", |_| {
+                    for _ in 0..10 {
+                        c * c;
+                    }
+                })
+            });
+        };
+
+        let run = |max_constraints: usize| {
+            let warnings = Arc::new(std::sync::Mutex::new(vec![]));
+            let warnings_clone = warnings.clone();
+
+            let mut cs = ConstraintSystem::<F>::new();
+            cs.set_size_limit(max_constraints, 1000, move |warning| {
+                warnings_clone.lock().unwrap().push(warning.clone());
+            });
+            cs.set_constraints(&synthesizer);
+
+            let warnings = warnings.lock().unwrap().clone();
+            warnings
+        };
+
+        // The additions row and the multiplication of the small gadget fit in the limit,
+        // and the large gadget exceeds it once.
+        let warnings = run(5);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].tag, "outer/large");
+        assert_eq!(warnings[0].num_constraints, 6);
+
+        // No warning within the limit
+        assert!(run(100).is_empty());
+    }
 }
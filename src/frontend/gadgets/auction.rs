@@ -0,0 +1,112 @@
+use super::bitops::range_check;
+use super::poseidon::poseidon::PoseidonChip;
+use super::tree::{hash, SPONGE_WIDTH};
+use crate::frontend::constraint_system::{ConstraintSystem, Wire};
+use ark_ff::PrimeField;
+
+// Verify the reveal of a sealed bid: the commitment made in the bidding phase
+// must be H(bid, nonce), where H is the two-to-one Poseidon hash of the Merkle tree gadget,
+// and the bid must be in [0, 2^num_bits).
+// The nonce should be random, so that the commitment hides low-entropy bids.
+pub fn verify_sealed_bid<F: PrimeField>(
+    cs: &mut ConstraintSystem<F>,
+    bid: Wire<F>,
+    nonce: Wire<F>,
+    commitment: Wire<F>,
+    num_bits: usize,
+    poseidon: PoseidonChip<F, SPONGE_WIDTH>,
+) {
+    range_check(bid, num_bits, cs);
+
+    let expected_commitment = hash(bid, nonce, poseidon);
+    cs.assert_equal(
+        commitment,
+        expected_commitment,
+        "The bid doesn't match the commitment",
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_var_pub_input;
+    use poseidon::{constants::secp256k1_w3, Poseidon};
+
+    type F = ark_secp256k1::Fq;
+
+    const BID_BITS: usize = 16;
+
+    fn commit(bid: F, nonce: F) -> F {
+        let mut poseidon = Poseidon::<F, SPONGE_WIDTH>::new(secp256k1_w3());
+        poseidon.state[0] = F::from(3u32);
+        poseidon.state[1] = bid;
+        poseidon.state[2] = nonce;
+        poseidon.permute();
+
+        poseidon.state[1]
+    }
+
+    fn sealed_bid_circuit(cs: &mut ConstraintSystem<F>) {
+        let commitment = cs.alloc_pub_input();
+        let bid = cs.alloc_pub_input();
+        let nonce = cs.alloc_priv_input();
+
+        let poseidon = PoseidonChip::<F, SPONGE_WIDTH>::new(cs, secp256k1_w3());
+        verify_sealed_bid(cs, bid, nonce, commitment, BID_BITS, poseidon);
+    }
+
+    // A bid, its nonce, and its commitment
+    fn sealed_bid() -> (F, F, F) {
+        let bid = F::from(1000u32);
+        let nonce = F::from(0x5eed_cafeu64);
+
+        (bid, nonce, commit(bid, nonce))
+    }
+
+    #[test]
+    fn test_verify_sealed_bid() {
+        let (bid, nonce, commitment) = sealed_bid();
+        test_var_pub_input(sealed_bid_circuit, &[commitment, bid], &[nonce]);
+    }
+
+    #[test]
+    #[should_panic(expected = "The bid doesn't match the commitment")]
+    fn test_verify_sealed_bid_wrong_bid() {
+        let (bid, nonce, commitment) = sealed_bid();
+
+        let mut cs = ConstraintSystem::new();
+        cs.gen_witness(
+            sealed_bid_circuit,
+            &[commitment, bid + F::from(1u32)],
+            &[nonce],
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "The bid doesn't match the commitment")]
+    fn test_verify_sealed_bid_wrong_nonce() {
+        let (bid, nonce, commitment) = sealed_bid();
+
+        let mut cs = ConstraintSystem::new();
+        cs.gen_witness(
+            sealed_bid_circuit,
+            &[commitment, bid],
+            &[nonce + F::from(1u32)],
+        );
+    }
+
+    // A bid out of range is rejected, even with a matching commitment
+    #[test]
+    #[should_panic(expected = "value is out of range")]
+    fn test_verify_sealed_bid_out_of_range() {
+        let (_, nonce, _) = sealed_bid();
+        let large_bid = F::from(1u64 << BID_BITS);
+
+        let mut cs = ConstraintSystem::new();
+        cs.gen_witness(
+            sealed_bid_circuit,
+            &[commit(large_bid, nonce), large_bid],
+            &[nonce],
+        );
+    }
+}
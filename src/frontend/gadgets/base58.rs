@@ -0,0 +1,143 @@
+use super::bitops::{form_le_bits, to_le_bits_n};
+use super::sha256::sha256;
+use crate::frontend::constraint_system::{ConstraintSystem, Wire};
+use ark_ff::PrimeField;
+
+// The Bitcoin base58 alphabet, where the i-th character is the digit i
+const ALPHABET: [u8; 58] = *b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+// The number of checksum bytes at the end of the decoded string
+const CHECKSUM_LEN: usize = 4;
+
+// Decode a base58check string, given as one wire per ASCII character, into `num_bytes` bytes,
+// and verify that the last 4 bytes are the first 4 bytes of the double SHA-256
+// of the bytes before them.
+// Each leading '1' of the string encodes a leading zero byte, as in Bitcoin addresses.
+// Returns the decoded bytes without the checksum (e.g. the version byte and the hash of an address),
+// as bits that are little-endian within each byte, like `sha256`.
+pub fn verify_base58check<F: PrimeField>(
+    cs: &mut ConstraintSystem<F>,
+    encoded: &[Wire<F>],
+    num_bytes: usize,
+) -> Vec<Wire<F>> {
+    assert!(
+        num_bytes > CHECKSUM_LEN,
+        "The decoded string must be longer than the checksum"
+    );
+    // Each base58 digit is less than 2^6
+    assert!(
+        6 * encoded.len() < F::MODULUS_BIT_SIZE as usize,
+        "The encoded string must fit in a field element"
+    );
+
+    let zero = cs.zero();
+    let one = cs.one();
+    let alphabet = ALPHABET.map(|c| cs.alloc_const(F::from(c)));
+
+    // The decoded number, and the number of leading '1' characters
+    let mut value = zero;
+    let mut num_leading_ones = zero;
+    let mut is_leading = one;
+
+    for c in encoded {
+        let is_digit = alphabet.map(|a| c.is_equal(a));
+
+        let is_valid = cs.sum(&is_digit.map(|is_digit| (is_digit, true)));
+        cs.assert_equal(is_valid, one, "invalid base58 character");
+
+        let digit_terms = is_digit
+            .iter()
+            .enumerate()
+            .map(|(d, is_digit)| (cs.mul_const(*is_digit, F::from(d as u32)), true))
+            .collect::<Vec<(Wire<F>, bool)>>();
+        let digit = cs.sum(&digit_terms);
+
+        value = cs.mul_const(value, F::from(58u32)) + digit;
+
+        is_leading = is_leading * is_digit[0];
+        num_leading_ones = num_leading_ones + is_leading;
+    }
+
+    // The bytes of the number, from the most significant one
+    let bits = to_le_bits_n(value, 8 * num_bytes, cs);
+    let bytes = bits.chunks(8).rev().collect::<Vec<&[Wire<F>]>>();
+
+    let mut num_leading_zeros = zero;
+    let mut is_leading = one;
+    for byte in &bytes {
+        is_leading = is_leading * form_le_bits(byte).is_zero();
+        num_leading_zeros = num_leading_zeros + is_leading;
+    }
+    cs.assert_equal(
+        num_leading_ones,
+        num_leading_zeros,
+        "The leading zeros of the base58 string don't match",
+    );
+
+    let payload = bytes[..(num_bytes - CHECKSUM_LEN)].concat();
+    let checksum = bytes[(num_bytes - CHECKSUM_LEN)..].concat();
+
+    let digest = sha256(cs, &payload);
+    let digest = sha256(cs, &digest);
+    for (bit, expected_bit) in checksum.iter().zip(digest.iter()) {
+        cs.assert_equal(*bit, *expected_bit, "Invalid base58check checksum");
+    }
+
+    payload
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{bytes_to_le_bits, test_var_pub_input};
+
+    type F = ark_secq256k1::Fr;
+
+    // The address of the genesis block coinbase
+    const ADDRESS: &str = "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa";
+    // The version byte and the public key hash of the address
+    const PAYLOAD: &str = "0062e907b15cbf27d5425399ebf6f0fb50ebb88f18";
+
+    // A P2PKH address decodes to the version byte, 20 bytes of hash, and the checksum
+    const NUM_BYTES: usize = 25;
+
+    fn base58check_circuit(cs: &mut ConstraintSystem<F>) {
+        let encoded = cs.alloc_pub_inputs(ADDRESS.len());
+        let payload = verify_base58check(cs, &encoded, NUM_BYTES);
+
+        for bit in payload {
+            cs.expose_public(bit);
+        }
+    }
+
+    fn to_char_felts(s: &str) -> Vec<F> {
+        s.bytes().map(F::from).collect()
+    }
+
+    #[test]
+    fn test_verify_base58check() {
+        let pub_input = [
+            to_char_felts(ADDRESS),
+            bytes_to_le_bits(&hex::decode(PAYLOAD).unwrap()),
+        ]
+        .concat();
+
+        test_var_pub_input(base58check_circuit, &pub_input, &[]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid base58check checksum")]
+    fn test_verify_base58check_corrupted_checksum() {
+        // The last character only changes the last byte of the checksum
+        let corrupted_address = format!("{}b", &ADDRESS[..(ADDRESS.len() - 1)]);
+
+        let pub_input = [
+            to_char_felts(&corrupted_address),
+            bytes_to_le_bits(&hex::decode(PAYLOAD).unwrap()),
+        ]
+        .concat();
+
+        let mut cs = ConstraintSystem::new();
+        cs.gen_witness(base58check_circuit, &pub_input, &[]);
+    }
+}
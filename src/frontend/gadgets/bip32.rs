@@ -0,0 +1,146 @@
+use super::bitops::to_le_bits_n;
+use super::canonical::to_canonical_le_bits;
+use super::ecc::weierstrass::{assert_valid_pubkey, ec_add_complete, ec_mul, secp256k1_generator};
+use super::ecc::AffinePoint;
+use super::sha512::hmac_sha512;
+use crate::frontend::constraint_system::{ConstraintSystem, Wire};
+use ark_ff::PrimeField;
+
+// The indices of hardened keys start at 2^31
+const HARDENED_INDEX_BITS: usize = 31;
+
+// Assert that the child public key and chain code are derived from the parent public key
+// and chain code at `index`, following the public (non-hardened) derivation of BIP-32:
+// I = HMAC-SHA512(parent_chain_code, serP(parent_pubkey) || ser32(index)),
+// child_pubkey = parse256(I_L) * G + parent_pubkey and child_chain_code = I_R.
+// https://github.com/bitcoin/bips/blob/master/bip-0032.mediawiki#public-parent-key--public-child-key
+// The chain codes are bits that are little-endian within each byte, like `sha512`.
+// The index must be less than 2^31, since hardened keys can't be derived from public keys.
+// I_L >= n (the order of secp256k1) is accepted, which happens with probability below 2^-127.
+// The circuit must be over the base field of secp256k1.
+pub fn verify_ckd<F: PrimeField>(
+    cs: &mut ConstraintSystem<F>,
+    parent_pubkey: AffinePoint<F>,
+    parent_chain_code: &[Wire<F>; 256],
+    index: Wire<F>,
+    child_pubkey: AffinePoint<F>,
+    child_chain_code: &[Wire<F>; 256],
+) {
+    assert_eq!(
+        F::MODULUS_BIT_SIZE,
+        256,
+        "The circuit must be over secp256k1"
+    );
+    assert_valid_pubkey(cs, &parent_pubkey);
+
+    let zero = cs.zero();
+    let one = cs.one();
+
+    // serP: the compressed encoding, i.e. 0x02 or 0x03 for the parity of y,
+    // followed by x as 32 big-endian bytes
+    let x_bits = to_canonical_le_bits(parent_pubkey.x, cs);
+    let y_bits = to_canonical_le_bits(parent_pubkey.y, cs);
+
+    let mut data = vec![y_bits[0], one, zero, zero, zero, zero, zero, zero];
+    for j in 0..32 {
+        data.extend_from_slice(&x_bits[8 * (31 - j)..8 * (32 - j)]);
+    }
+
+    // ser32: the index as 4 big-endian bytes
+    let mut index_bits = to_le_bits_n(index, HARDENED_INDEX_BITS, cs);
+    index_bits.push(zero);
+    for j in 0..4 {
+        data.extend_from_slice(&index_bits[8 * (3 - j)..8 * (4 - j)]);
+    }
+
+    let i = hmac_sha512(cs, parent_chain_code, &data);
+
+    // parse256(I_L) is big-endian
+    let i_l_bits = (0..256)
+        .map(|k| i[8 * (31 - k / 8) + k % 8])
+        .collect::<Vec<Wire<F>>>();
+
+    let g = secp256k1_generator(cs);
+    let tweak = ec_mul(g, &i_l_bits, cs);
+    let derived_pubkey = ec_add_complete(tweak, parent_pubkey, cs);
+
+    cs.assert_equal(derived_pubkey.x, child_pubkey.x, "Invalid child public key");
+    cs.assert_equal(derived_pubkey.y, child_pubkey.y, "Invalid child public key");
+
+    for (bit, expected_bit) in i[256..].iter().zip(child_chain_code.iter()) {
+        cs.assert_equal(*bit, *expected_bit, "Invalid child chain code");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{bytes_to_le_bits, test_var_pub_input};
+    use std::panic;
+
+    type F = ark_secp256k1::Fq;
+
+    fn from_hex(x: &str) -> F {
+        F::from_be_bytes_mod_order(&hex::decode(x).unwrap())
+    }
+
+    fn ckd_circuit(cs: &mut ConstraintSystem<F>) {
+        let parent_pubkey = AffinePoint::new(cs.alloc_pub_input(), cs.alloc_pub_input());
+        let parent_chain_code = cs.alloc_pub_inputs(256);
+        let index = cs.alloc_pub_input();
+        let child_pubkey = AffinePoint::new(cs.alloc_pub_input(), cs.alloc_pub_input());
+        let child_chain_code = cs.alloc_pub_inputs(256);
+
+        verify_ckd(
+            cs,
+            parent_pubkey,
+            &parent_chain_code.try_into().unwrap(),
+            index,
+            child_pubkey,
+            &child_chain_code.try_into().unwrap(),
+        );
+    }
+
+    fn ckd_pub_input(index: u32) -> Vec<F> {
+        // m/0H -> m/0H/1 of test vector 1 of BIP-32.
+        // The child matches the published extended public key of m/0H/1
+        // (xpub6ASuArnXKPbfEwhqN6e3mwBcDTgzisQN1wXN9BJcM47sSikHjJf3UFHKkNAWbWMiGj7Wf5uMash7SyYq527Hqck2AxYysAA7xmALppuCkwQ).
+        let parent_chain_code =
+            hex::decode("47fdacbd0f1097043b78c63c20c34ef4ed9a111d980047ad16282c7ae6236141")
+                .unwrap();
+        let child_chain_code =
+            hex::decode("2a7857631386ba23dacac34180dd1983734e444fdbf774041578e9b6adb37c19")
+                .unwrap();
+
+        [
+            vec![
+                from_hex("5a784662a4a20a65bf6aab9ae98a6c068a81c52e4b032c0fb5400c706cfccc56"),
+                from_hex("7f717885be239daadce76b568958305183ad616ff74ed4dc219a74c26d35f839"),
+            ],
+            bytes_to_le_bits(&parent_chain_code),
+            vec![F::from(index)],
+            vec![
+                from_hex("501e454bf00751f24b1b489aa925215d66af2234e3891c3b21a52bedb3cd711c"),
+                from_hex("008794c1df8131b9ad1e1359965b3f3ee2feef0866be693729772be14be881ab"),
+            ],
+            bytes_to_le_bits(&child_chain_code),
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn test_verify_ckd() {
+        test_var_pub_input(ckd_circuit, &ckd_pub_input(1), &[]);
+
+        // Should fail for another index, or a hardened index
+        for index in [2, 1 << 31 | 1] {
+            let pub_input = ckd_pub_input(index);
+            let result = panic::catch_unwind(|| {
+                let mut cs = ConstraintSystem::new();
+                cs.set_constraints(&ckd_circuit);
+                cs.gen_witness(ckd_circuit, &pub_input, &[]);
+            });
+            assert!(result.is_err());
+        }
+    }
+}
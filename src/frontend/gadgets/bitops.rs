@@ -1,46 +1,99 @@
-use crate::frontend::constraint_system::Wire;
+use crate::frontend::constraint_system::{ConstraintSystem, Wire};
 use ark_ff::BigInteger;
 use ark_ff::Field;
 use ark_ff::PrimeField;
-
-pub fn xor_64<F: Field>(a: [Wire<F>; 64], b: [Wire<F>; 64]) -> [Wire<F>; 64] {
-    let cs = a[0].cs();
+use std::collections::BTreeMap;
+
+// Apply a two-input gate to each pair of bits of two words of the same width.
+// At witness generation, the outputs are assigned in one batch instead,
+// with `eval` computing the output of the gate from the values of its inputs.
+fn bitwise<F: Field>(
+    a: &[Wire<F>],
+    b: &[Wire<F>],
+    gate: impl Fn(Wire<F>, Wire<F>) -> Wire<F>,
+    eval: impl Fn(F, F) -> F + Sync,
+) -> Vec<Wire<F>> {
     assert_eq!(a.len(), b.len());
-    let mut out = [cs.one(); 64];
-    for i in 0..64 {
-        out[i] = bit_xor(a[i], b[i]);
+
+    if let Some(cs) = a.first().map(|a| a.cs()) {
+        if cs.is_assigning() {
+            let inputs = a.iter().copied().zip(b.iter().copied()).collect::<Vec<_>>();
+            return cs.assign_outputs(&inputs, eval);
+        }
     }
 
-    out
+    a.iter().zip(b).map(|(a, b)| gate(*a, *b)).collect()
 }
 
-// (!a) & b
-pub fn not_a_and_b<F: Field>(a: Wire<F>, b: Wire<F>) -> Wire<F> {
-    let cs = a.cs();
+// Element-wise XOR of two words of the same width
+pub fn xor<F: Field>(a: &[Wire<F>], b: &[Wire<F>]) -> Vec<Wire<F>> {
+    bitwise(a, b, bit_xor, |a, b| -(a + a) * b + a + b)
+}
 
-    let one = cs.one();
-    // (a * -1 + 1 * 1) * (b * 1) = c
-    cs.constrain(&[(a, -F::ONE), (one, F::ONE)], &[(b, F::ONE)], &[])
+// Element-wise AND of two words of the same width
+pub fn and<F: Field>(a: &[Wire<F>], b: &[Wire<F>]) -> Vec<Wire<F>> {
+    bitwise(a, b, bit_and, |a, b| a * b)
+}
+
+// Element-wise OR of two words of the same width
+pub fn or<F: Field>(a: &[Wire<F>], b: &[Wire<F>]) -> Vec<Wire<F>> {
+    bitwise(a, b, bit_or, |a, b| -a * b + a + b)
+}
+
+// Element-wise (!a) & b of two words of the same width
+pub fn not_a_and_b<F: Field>(a: &[Wire<F>], b: &[Wire<F>]) -> Vec<Wire<F>> {
+    bitwise(a, b, bit_not_a_and_b, |a, b| (F::ONE - a) * b)
+}
+
+pub fn xor_64<F: Field>(a: [Wire<F>; 64], b: [Wire<F>; 64]) -> [Wire<F>; 64] {
+    xor(&a, &b).try_into().unwrap()
 }
 
 pub fn not_a_and_b_64<F: Field>(a: [Wire<F>; 64], b: [Wire<F>; 64]) -> [Wire<F>; 64] {
+    not_a_and_b(&a, &b).try_into().unwrap()
+}
+
+pub fn and_64<F: Field>(a: [Wire<F>; 64], b: [Wire<F>; 64]) -> [Wire<F>; 64] {
+    and(&a, &b).try_into().unwrap()
+}
+
+pub fn or_64<F: Field>(a: [Wire<F>; 64], b: [Wire<F>; 64]) -> [Wire<F>; 64] {
+    or(&a, &b).try_into().unwrap()
+}
+
+pub fn rotate_left_64<F: Field>(a: [Wire<F>; 64], n: usize) -> [Wire<F>; 64] {
+    rotate_left(a, n)
+}
+
+// Rotate left by an amount given as 6 little-endian bits, which are constrained to be binary.
+// The i-th bit selects between the word and its rotation by 2^i,
+// so the output is `rotate_left_64(a, amount)` for the amount the bits encode.
+pub fn rotate_left_var_64<F: PrimeField>(
+    a: [Wire<F>; 64],
+    amount_bits: &[Wire<F>; 6],
+) -> [Wire<F>; 64] {
     let cs = a[0].cs();
-    assert_eq!(a.len(), b.len());
-    let mut out = [cs.one(); 64];
-    for i in 0..64 {
-        out[i] = not_a_and_b(a[i], b[i]);
+
+    let mut out = a;
+    for (i, bit) in amount_bits.iter().enumerate() {
+        cs.assert_equal(*bit * *bit, *bit, "rotation amount bit is not binary");
+
+        // out + bit * (rotated - out), as in `select`
+        let rotated = rotate_left_64(out, 1 << i);
+        for j in 0..64 {
+            out[j] = cs.mul_add(*bit, rotated[j] - out[j], out[j]);
+        }
     }
 
     out
 }
 
-pub fn rotate_left_64<F: Field>(a: [Wire<F>; 64], n: usize) -> [Wire<F>; 64] {
-    let mut out = Vec::with_capacity(64);
-    for i in 0..64 {
-        out.push(a[((i as usize).wrapping_sub(n)) % 64]);
-    }
+pub fn rotate_right_64<F: Field>(a: [Wire<F>; 64], n: usize) -> [Wire<F>; 64] {
+    rotate_right(a, n)
+}
 
-    out.try_into().unwrap()
+pub fn shift_right_64<F: Field>(a: [Wire<F>; 64], n: usize) -> [Wire<F>; 64] {
+    shift_right(a, n)
 }
 
 pub fn bit_xor<F: Field>(a: Wire<F>, b: Wire<F>) -> Wire<F> {
@@ -54,44 +107,250 @@ pub fn bit_xor<F: Field>(a: Wire<F>, b: Wire<F>) -> Wire<F> {
     )
 }
 
+// It does NOT constrain the input wires to be binary.
+pub fn bit_and<F: Field>(a: Wire<F>, b: Wire<F>) -> Wire<F> {
+    let cs = a.cs();
+
+    // a * b = c
+    cs.constrain(&[(a, F::ONE)], &[(b, F::ONE)], &[])
+}
+
+// (!a) & b
+pub fn bit_not_a_and_b<F: Field>(a: Wire<F>, b: Wire<F>) -> Wire<F> {
+    let cs = a.cs();
+
+    let one = cs.one();
+    // (a * -1 + 1 * 1) * (b * 1) = c
+    cs.constrain(&[(a, -F::ONE), (one, F::ONE)], &[(b, F::ONE)], &[])
+}
+
+// It does NOT constrain the input wires to be binary.
+pub fn bit_or<F: Field>(a: Wire<F>, b: Wire<F>) -> Wire<F> {
+    let cs = a.cs();
+
+    // -a * b + a + b = c
+    cs.constrain(&[(a, -F::ONE)], &[(b, F::ONE)], &[(a, F::ONE), (b, F::ONE)])
+}
+
 // Little-endian bits to value
 pub fn form_le_bits<F: PrimeField>(bits: &[Wire<F>]) -> Wire<F> {
     let cs = bits[0].cs();
 
     let mut terms = Vec::with_capacity(bits.len());
 
-    let mut pow = F::from(1u32);
-    for bit in bits.iter() {
+    for (i, bit) in bits.iter().enumerate() {
+        let pow = cs.pow_of_two(i);
         terms.push((cs.mul_const(*bit, pow), true));
-        pow *= F::from(2u32);
     }
 
     cs.sum(&terms)
 }
 
-// Value to little-endian bits
-pub fn to_le_bits<F: PrimeField>(x: Wire<F>) -> Vec<Wire<F>> {
-    let cs = x.cs();
+// Decompose a byte into little-endian bits.
+// The bits are constrained to be binary, and the byte to be less than 256.
+pub(crate) fn byte_to_le_bits<F: PrimeField>(
+    byte: Wire<F>,
+    cs: &mut ConstraintSystem<F>,
+) -> Vec<Wire<F>> {
+    to_le_bits_n(byte, 8, cs)
+}
 
-    let bits = (0..F::MODULUS_BIT_SIZE)
-        .map(|_| cs.alloc_var(F::ZERO))
-        .collect::<Vec<_>>();
+// Decompose `x` into `num_bits` little-endian bits,
+// which constrains `x` to be less than 2^num_bits.
+pub(crate) fn range_check<F: PrimeField>(
+    x: Wire<F>,
+    num_bits: usize,
+    cs: &mut ConstraintSystem<F>,
+) {
+    to_le_bits_n(x, num_bits, cs);
+}
 
-    if cs.is_witness_gen() {
-        let x_assigned = cs.wires[x.index];
-        let x_bits = x_assigned.into_bigint().to_bits_le();
+// Same as `range_check`, and returns the bits.
+// `num_bits` must be smaller than the bit size of the modulus,
+// otherwise the decomposition isn't unique.
+// A value that doesn't fit in `num_bits` isn't truncated,
+// and witness generation fails with "value is out of range".
+pub(crate) fn to_le_bits_n<F: PrimeField>(
+    x: Wire<F>,
+    num_bits: usize,
+    cs: &mut ConstraintSystem<F>,
+) -> Vec<Wire<F>> {
+    assert!(
+        num_bits < F::MODULUS_BIT_SIZE as usize,
+        "The number of bits must be smaller than the bit size of the modulus"
+    );
+
+    alloc_le_bits(x, num_bits, cs)
+}
 
-        for (i, b) in x_bits.iter().enumerate() {
-            cs.wires[bits[i].index] = F::from(*b);
-        }
+// Allocate `num_bits` little-endian bits of `x`,
+// constrained to be binary and to recompose to `x`.
+fn alloc_le_bits<F: PrimeField>(
+    x: Wire<F>,
+    num_bits: usize,
+    cs: &mut ConstraintSystem<F>,
+) -> Vec<Wire<F>> {
+    let x_bits = x.val(cs).map(|val| val.into_bigint().to_bits_le());
+
+    let bits = (0..num_bits)
+        .map(|i| {
+            let bit = x_bits.as_ref().map_or(false, |bits| bits[i]);
+            cs.alloc_var(F::from(bit))
+        })
+        .collect::<Vec<Wire<F>>>();
+
+    for bit in &bits {
+        cs.assert_equal(*bit * *bit, *bit, "bit is not binary");
     }
 
     let recovered_x = form_le_bits(&bits);
-    cs.assert_equal(x, recovered_x, "to_le_bits failed");
+    cs.assert_equal(x, recovered_x, "value is out of range");
 
     bits
 }
 
+// Sum of 32-bit words (as little-endian bits) modulo 2^32.
+pub(crate) fn sum_mod_32<F: PrimeField>(
+    words: &[[Wire<F>; 32]],
+    cs: &mut ConstraintSystem<F>,
+) -> [Wire<F>; 32] {
+    sum_mod(words, cs)
+}
+
+// Sum of 64-bit words (as little-endian bits) modulo 2^64.
+pub(crate) fn sum_mod_64<F: PrimeField>(
+    words: &[[Wire<F>; 64]],
+    cs: &mut ConstraintSystem<F>,
+) -> [Wire<F>; 64] {
+    sum_mod(words, cs)
+}
+
+// Sum of N-bit words (as little-endian bits) modulo 2^N.
+// The sum is decomposed into binary bits, and the carry bits are dropped.
+fn sum_mod<F: PrimeField, const N: usize>(
+    words: &[[Wire<F>; N]],
+    cs: &mut ConstraintSystem<F>,
+) -> [Wire<F>; N] {
+    let one = cs.one();
+
+    // The terms of the same wire (e.g. the bits of constants) are combined,
+    // since a linear combination has one coefficient per wire.
+    let mut terms = BTreeMap::<usize, (Wire<F>, F)>::new();
+    for word in words {
+        for (i, bit) in word.iter().enumerate() {
+            terms.entry(bit.id()).or_insert((*bit, F::ZERO)).1 += cs.pow_of_two(i);
+        }
+    }
+    let terms = terms.into_values().collect::<Vec<(Wire<F>, F)>>();
+    let sum = cs.constrain(&terms, &[(one, F::ONE)], &[]);
+
+    // The sum of n words is less than 2^(N + ceil(log2(n)))
+    let num_bits = N + (usize::BITS - (words.len() - 1).leading_zeros()) as usize;
+    let sum_bits = sum.val(cs).map(|val| val.into_bigint().to_bits_le());
+
+    let bits = (0..num_bits)
+        .map(|i| {
+            let bit = sum_bits.as_ref().map_or(false, |bits| bits[i]);
+            cs.alloc_var(F::from(bit))
+        })
+        .collect::<Vec<Wire<F>>>();
+
+    for bit in &bits {
+        cs.assert_equal(*bit * *bit, *bit, "sum bit is not binary");
+    }
+
+    let bit_terms = bits
+        .iter()
+        .enumerate()
+        .map(|(i, bit)| (*bit, cs.pow_of_two(i)))
+        .collect::<Vec<(Wire<F>, F)>>();
+    let recovered_sum = cs.constrain(&bit_terms, &[(one, F::ONE)], &[]);
+    cs.assert_equal(sum, recovered_sum, "sum_mod failed");
+
+    bits[..N].try_into().unwrap()
+}
+
+// Rotate an N-bit word (as little-endian bits) left by n bits
+pub fn rotate_left<F: Field, const N: usize>(a: [Wire<F>; N], n: usize) -> [Wire<F>; N] {
+    std::array::from_fn(|i| a[(i + N - n % N) % N])
+}
+
+// Rotate an N-bit word (as little-endian bits) right by n bits
+pub fn rotate_right<F: Field, const N: usize>(a: [Wire<F>; N], n: usize) -> [Wire<F>; N] {
+    rotate_left(a, N - n % N)
+}
+
+// Logical right shift of an N-bit word, which fills the high bits with zeros
+pub fn shift_right<F: Field, const N: usize>(a: [Wire<F>; N], n: usize) -> [Wire<F>; N] {
+    let zero = a[0].cs().zero();
+    std::array::from_fn(|i| if n < N - i { a[i + n] } else { zero })
+}
+
+// The N low bits of a constant, as little-endian bits
+pub(crate) fn const_word<F: Field, const N: usize>(
+    c: u64,
+    cs: &mut ConstraintSystem<F>,
+) -> [Wire<F>; N] {
+    let zero = cs.zero();
+    let one = cs.one();
+
+    std::array::from_fn(|i| if c >> i & 1 == 1 { one } else { zero })
+}
+
+// Choose: (e & f) ^ (!e & g) = e * (f - g) + g, bit by bit
+pub(crate) fn ch<F: Field, const N: usize>(
+    e: [Wire<F>; N],
+    f: [Wire<F>; N],
+    g: [Wire<F>; N],
+) -> [Wire<F>; N] {
+    std::array::from_fn(|i| e[i] * (f[i] - g[i]) + g[i])
+}
+
+// Majority: (a & b) ^ (a & c) ^ (b & c) = a * (b + c - 2 * b * c) + b * c, bit by bit
+pub(crate) fn maj<F: Field, const N: usize>(
+    a: [Wire<F>; N],
+    b: [Wire<F>; N],
+    c: [Wire<F>; N],
+) -> [Wire<F>; N] {
+    std::array::from_fn(|i| {
+        let bc = b[i] * c[i];
+        a[i] * (b[i] + c[i] - bc - bc) + bc
+    })
+}
+
+// XOR the bits of a key with a repeated constant byte
+pub(crate) fn xor_pad<F: Field>(
+    key: &[Wire<F>],
+    pad: u8,
+    cs: &mut ConstraintSystem<F>,
+) -> Vec<Wire<F>> {
+    let one = cs.one();
+
+    key.iter()
+        .enumerate()
+        .map(|(i, bit)| {
+            if pad >> (i % 8) & 1 == 1 {
+                one - *bit
+            } else {
+                *bit
+            }
+        })
+        .collect()
+}
+
+// (a + b) mod 2^32
+pub fn add_mod_32<F: PrimeField>(a: [Wire<F>; 32], b: [Wire<F>; 32]) -> [Wire<F>; 32] {
+    let cs = a[0].cs();
+    sum_mod_32(&[a, b], cs)
+}
+
+// Value to little-endian bits.
+// The bits are constrained to be binary.
+pub fn to_le_bits<F: PrimeField>(x: Wire<F>) -> Vec<Wire<F>> {
+    let cs = x.cs();
+    alloc_le_bits(x, F::MODULUS_BIT_SIZE as usize, cs)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -100,6 +359,39 @@ mod tests {
 
     type Fp = ark_secq256k1::Fr;
 
+    #[test]
+    fn test_find_redundant_constraints() {
+        let synthesizer = |cs: &mut ConstraintSystem<Fp>| {
+            let a = cs.alloc_priv_input();
+            let b = cs.alloc_priv_input();
+
+            let c = bit_xor(a, b);
+            // The same XOR constraint again
+            let d = bit_xor(a, b);
+
+            cs.expose_public(c);
+            cs.expose_public(d);
+        };
+
+        let mut cs = ConstraintSystem::<Fp>::new();
+        cs.set_constraints(&synthesizer);
+
+        assert_eq!(cs.find_redundant_constraints(), vec![2]);
+
+        let synthesizer = |cs: &mut ConstraintSystem<Fp>| {
+            let a = cs.alloc_priv_input();
+            let b = cs.alloc_priv_input();
+
+            let c = bit_xor(a, b);
+            cs.expose_public(c);
+        };
+
+        let mut cs = ConstraintSystem::<Fp>::new();
+        cs.set_constraints(&synthesizer);
+
+        assert!(cs.find_redundant_constraints().is_empty());
+    }
+
     #[test]
     pub fn test_from_le_bits() {
         let synthesizer = |cs: &mut ConstraintSystem<Fp>| {
@@ -119,6 +411,266 @@ mod tests {
         test_var_pub_input(synthesizer, &pub_input, &priv_input);
     }
 
+    #[test]
+    fn test_bit_xor_pretty_print() {
+        let synthesizer = |cs: &mut ConstraintSystem<Fp>| {
+            let a = cs.alloc_priv_input();
+            let b = cs.alloc_priv_input();
+            let c = bit_xor(a, b);
+
+            cs.expose_public(c);
+        };
+
+        let mut cs = ConstraintSystem::new();
+        cs.set_constraints(&synthesizer);
+
+        // The additions, and the single constraint of bit_xor
+        let printed = cs.pretty_print();
+        let lines = printed.lines().collect::<Vec<&str>>();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].contains("-2"));
+    }
+
+    fn to_bits_64(x: u64) -> Vec<Fp> {
+        (0..64).map(|i| Fp::from((x >> i) & 1)).collect()
+    }
+
+    fn bitwise_64_circuit(
+        op: fn([Wire<Fp>; 64], [Wire<Fp>; 64]) -> [Wire<Fp>; 64],
+    ) -> impl Fn(&mut ConstraintSystem<Fp>) {
+        move |cs: &mut ConstraintSystem<Fp>| {
+            let a = cs.alloc_priv_inputs_arr();
+            let b = cs.alloc_priv_inputs_arr();
+
+            let out = op(a, b);
+            for bit in out {
+                cs.expose_public(bit);
+            }
+        }
+    }
+
+    #[test]
+    fn test_and_64() {
+        let a = 0xdeadbeef01234567u64;
+        let b = 0x0f0f0f0ff0f0f0f0u64;
+
+        let synthesizer = bitwise_64_circuit(and_64);
+
+        let mut cs = ConstraintSystem::new();
+        cs.set_constraints(&synthesizer);
+
+        // One constraint per bit, after the additions row
+        assert_eq!(cs.num_constraints.unwrap(), 1 + 64);
+
+        let pub_input = to_bits_64(a & b);
+        let priv_input = [to_bits_64(a), to_bits_64(b)].concat();
+        let witness = cs.gen_witness(&synthesizer, &pub_input, &priv_input);
+        assert!(cs.is_sat(&witness, &pub_input));
+    }
+
+    #[test]
+    fn test_or_64() {
+        let a = 0xdeadbeef01234567u64;
+        let b = 0x0f0f0f0ff0f0f0f0u64;
+
+        let synthesizer = bitwise_64_circuit(or_64);
+
+        let mut cs = ConstraintSystem::new();
+        cs.set_constraints(&synthesizer);
+
+        assert_eq!(cs.num_constraints.unwrap(), 1 + 64);
+
+        let pub_input = to_bits_64(a | b);
+        let priv_input = [to_bits_64(a), to_bits_64(b)].concat();
+        let witness = cs.gen_witness(&synthesizer, &pub_input, &priv_input);
+        assert!(cs.is_sat(&witness, &pub_input));
+    }
+
+    fn to_bits(x: u64, width: usize) -> Vec<Fp> {
+        (0..width).map(|i| Fp::from((x >> i) & 1)).collect()
+    }
+
+    #[test]
+    fn test_xor() {
+        let a = 0xdeadbeef01234567u64;
+        let b = 0x0f0f0f0ff0f0f0f0u64;
+
+        for width in [8, 32, 64] {
+            let synthesizer = |cs: &mut ConstraintSystem<Fp>| {
+                let a = cs.alloc_priv_inputs(width);
+                let b = cs.alloc_priv_inputs(width);
+
+                for bit in xor(&a, &b) {
+                    cs.expose_public(bit);
+                }
+            };
+
+            let pub_input = to_bits(a ^ b, width);
+            let priv_input = [to_bits(a, width), to_bits(b, width)].concat();
+            test_var_pub_input(synthesizer, &pub_input, &priv_input);
+        }
+
+        // xor_64 is the 64-bit case
+        let synthesizer = bitwise_64_circuit(xor_64);
+        let pub_input = to_bits_64(a ^ b);
+        let priv_input = [to_bits_64(a), to_bits_64(b)].concat();
+        test_var_pub_input(synthesizer, &pub_input, &priv_input);
+    }
+
+    #[test]
+    fn test_not_a_and_b() {
+        let a = 0xdeadbeef01234567u64;
+        let b = 0x0f0f0f0ff0f0f0f0u64;
+
+        for width in [8, 32, 64] {
+            let synthesizer = |cs: &mut ConstraintSystem<Fp>| {
+                let a = cs.alloc_priv_inputs(width);
+                let b = cs.alloc_priv_inputs(width);
+
+                for bit in not_a_and_b(&a, &b) {
+                    cs.expose_public(bit);
+                }
+            };
+
+            let pub_input = to_bits(!a & b, width);
+            let priv_input = [to_bits(a, width), to_bits(b, width)].concat();
+            test_var_pub_input(synthesizer, &pub_input, &priv_input);
+        }
+    }
+
+    #[test]
+    fn test_rotate_and_shift_right_64() {
+        let a = 0xdeadbeef01234567u64;
+        let amounts = [0, 1, 7, 32, 63, 64];
+
+        let synthesizer = move |cs: &mut ConstraintSystem<Fp>| {
+            let a: [Wire<Fp>; 64] = cs.alloc_priv_inputs_arr();
+
+            for n in amounts {
+                let rotated = form_le_bits(&rotate_right_64(a, n));
+                let shifted = form_le_bits(&shift_right_64(a, n));
+
+                cs.expose_public(rotated);
+                cs.expose_public(shifted);
+            }
+        };
+
+        let pub_input = amounts
+            .iter()
+            .flat_map(|&n| {
+                let shifted = a.checked_shr(n as u32).unwrap_or(0);
+                [Fp::from(a.rotate_right(n as u32)), Fp::from(shifted)]
+            })
+            .collect::<Vec<Fp>>();
+
+        test_var_pub_input(synthesizer, &pub_input, &to_bits_64(a));
+    }
+
+    #[test]
+    fn test_rotate_and_shift_right_32() {
+        let a = 0xdeadbeefu32;
+        let amounts = [0, 1, 7, 31, 32];
+
+        let synthesizer = move |cs: &mut ConstraintSystem<Fp>| {
+            let a: [Wire<Fp>; 32] = cs.alloc_priv_inputs_arr();
+
+            for n in amounts {
+                let rotated = form_le_bits(&rotate_right(a, n));
+                let shifted = form_le_bits(&shift_right(a, n));
+
+                cs.expose_public(rotated);
+                cs.expose_public(shifted);
+            }
+        };
+
+        let pub_input = amounts
+            .iter()
+            .flat_map(|&n| {
+                let shifted = a.checked_shr(n as u32).unwrap_or(0);
+                [Fp::from(a.rotate_right(n as u32)), Fp::from(shifted)]
+            })
+            .collect::<Vec<Fp>>();
+
+        test_var_pub_input(synthesizer, &pub_input, &to_bits(a as u64, 32));
+    }
+
+    #[test]
+    fn test_rotate_left_var_64() {
+        let a = 0xdeadbeef01234567u64;
+
+        let synthesizer = |cs: &mut ConstraintSystem<Fp>| {
+            let a: [Wire<Fp>; 64] = cs.alloc_priv_inputs_arr();
+            let amount_bits = cs.alloc_priv_inputs_arr();
+
+            let rotated = rotate_left_var_64(a, &amount_bits);
+            cs.expose_public(form_le_bits(&rotated));
+        };
+
+        for amount in 0..64u64 {
+            let amount_bits = (0..6).map(|i| Fp::from((amount >> i) & 1));
+            let priv_input = to_bits_64(a)
+                .into_iter()
+                .chain(amount_bits)
+                .collect::<Vec<Fp>>();
+            let pub_input = [Fp::from(a.rotate_left(amount as u32))];
+
+            test_var_pub_input(synthesizer, &pub_input, &priv_input);
+        }
+
+        // An amount bit of 2 is rejected, even with its square assigned consistently
+        let synthesizer = |cs: &mut ConstraintSystem<Fp>| {
+            let a: [Wire<Fp>; 64] = cs.alloc_priv_inputs_arr();
+            let amount_bits = cs.alloc_priv_inputs_arr();
+
+            rotate_left_var_64(a, &amount_bits);
+        };
+
+        let mut cs = ConstraintSystem::new();
+        cs.set_constraints(&synthesizer);
+
+        let priv_input = [to_bits_64(a), vec![Fp::ZERO; 6]].concat();
+        let mut witness = cs.gen_witness(synthesizer, &[], &priv_input);
+        assert!(cs.is_sat(&witness, &[]));
+
+        // The witness starts with the bits of `a`, the amount bits,
+        // and the square of the first amount bit.
+        witness[64] = Fp::from(2u32);
+        witness[70] = Fp::from(4u32);
+
+        let unsat = cs.which_unsat(&witness, &[]).unwrap();
+        assert_eq!(
+            cs.constraint_label(unsat),
+            Some("rotation amount bit is not binary")
+        );
+    }
+
+    #[test]
+    fn test_add_mod_32() {
+        let cases = [
+            (0xffffffffu32, 1u32),
+            (0x89abcdef, 0xfedcba98),
+            (5, 7),
+            (0, 0),
+        ];
+
+        let synthesizer = |cs: &mut ConstraintSystem<Fp>| {
+            let a = cs.alloc_priv_inputs_arr();
+            let b = cs.alloc_priv_inputs_arr();
+
+            let sum = add_mod_32(a, b);
+            cs.expose_public(form_le_bits(&sum));
+        };
+
+        for (a, b) in cases {
+            let priv_input = [to_bits_64(a as u64), to_bits_64(b as u64)]
+                .map(|bits| bits[..32].to_vec())
+                .concat();
+            let pub_input = [Fp::from(a.wrapping_add(b))];
+
+            test_var_pub_input(synthesizer, &pub_input, &priv_input);
+        }
+    }
+
     #[test]
     fn test_to_le_bits() {
         let synthesizer = |cs: &mut ConstraintSystem<Fp>| {
@@ -143,4 +695,68 @@ mod tests {
 
         test_var_pub_input(synthesizer, &pub_input, &priv_input);
     }
+
+    #[test]
+    #[should_panic(
+        expected = "The number of bits must be smaller than the bit size of the modulus"
+    )]
+    fn test_to_le_bits_n_too_many_bits() {
+        let synthesizer = |cs: &mut ConstraintSystem<Fp>| {
+            let val = cs.alloc_priv_input();
+            to_le_bits_n(val, Fp::MODULUS_BIT_SIZE as usize, cs);
+        };
+
+        let mut cs = ConstraintSystem::new();
+        cs.set_constraints(&synthesizer);
+    }
+
+    #[test]
+    #[should_panic(expected = "value is out of range")]
+    fn test_to_le_bits_n_too_few_bits() {
+        let synthesizer = |cs: &mut ConstraintSystem<Fp>| {
+            let val = cs.alloc_priv_input();
+            to_le_bits_n(val, 8, cs);
+        };
+
+        let mut cs = ConstraintSystem::new();
+        cs.set_constraints(&synthesizer);
+        cs.gen_witness(synthesizer, &[], &[Fp::from(256u32)]);
+    }
+
+    #[test]
+    fn test_to_le_bits_non_binary() {
+        let synthesizer = |cs: &mut ConstraintSystem<Fp>| {
+            let val = cs.alloc_priv_input();
+            to_le_bits(val);
+        };
+
+        let mut cs = ConstraintSystem::new();
+        cs.set_constraints(&synthesizer);
+
+        let val = Fp::from(2);
+        let mut witness = cs.gen_witness(&synthesizer, &[], &[val]);
+        assert!(cs.is_sat(&witness, &[]));
+
+        // The witness consists of the input, the bits, the squared bits,
+        // the weighted bits, and their sum.
+        let num_bits = Fp::MODULUS_BIT_SIZE as usize;
+        let bit = |i: usize| 1 + i;
+        let squared_bit = |i: usize| 1 + num_bits + i;
+        let weighted_bit = |i: usize| 1 + 2 * num_bits + i;
+        assert_eq!(witness[bit(1)], Fp::ONE);
+        assert_eq!(witness[squared_bit(1)], Fp::ONE);
+        assert_eq!(witness[weighted_bit(1)], Fp::from(2));
+        assert_eq!(witness[1 + 3 * num_bits], val);
+
+        // Decompose 2 as 2 * 2^0 instead of 1 * 2^1.
+        // The bits still sum to the input, but aren't binary.
+        witness[bit(0)] = Fp::from(2);
+        witness[squared_bit(0)] = Fp::from(4);
+        witness[weighted_bit(0)] = Fp::from(2);
+        witness[bit(1)] = Fp::ZERO;
+        witness[squared_bit(1)] = Fp::ZERO;
+        witness[weighted_bit(1)] = Fp::ZERO;
+
+        assert!(!cs.is_sat(&witness, &[]));
+    }
 }
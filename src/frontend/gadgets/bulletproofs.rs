@@ -0,0 +1,71 @@
+use super::bitops::to_le_bits_n;
+use crate::frontend::constraint_system::{ConstraintSystem, Wire};
+use ark_ff::PrimeField;
+
+// Constrain `value` to be in [0, 2^n) in the form of the Bulletproofs range proof,
+// and return the vectors (a_L, a_R) of the proof:
+// - a_L are the little-endian bits of the value, i.e. <a_L, 2^n> = value
+// - a_R = a_L - 1^n, so a_L ∘ a_R = 0 as the bits are constrained to be binary
+pub fn range_proof_bits_bp<F: PrimeField>(
+    cs: &mut ConstraintSystem<F>,
+    value: Wire<F>,
+    n: usize,
+) -> (Vec<Wire<F>>, Vec<Wire<F>>) {
+    assert!(
+        n < F::MODULUS_BIT_SIZE as usize,
+        "The range must be smaller than the field"
+    );
+
+    let a_L = to_le_bits_n(value, n, cs);
+
+    let a_R = a_L
+        .iter()
+        .map(|a_L_i| cs.sub_const(*a_L_i, F::ONE))
+        .collect::<Vec<Wire<F>>>();
+
+    (a_L, a_R)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ff::Field;
+    use std::panic;
+
+    type F = ark_secq256k1::Fr;
+
+    const N: usize = 8;
+
+    fn range_circuit(cs: &mut ConstraintSystem<F>) {
+        let value = cs.alloc_priv_input();
+
+        let (a_L, a_R) = range_proof_bits_bp(cs, value, N);
+        for w in a_L.iter().chain(a_R.iter()) {
+            cs.expose_public(*w);
+        }
+    }
+
+    #[test]
+    fn test_range_proof_bits_bp() {
+        let value = F::from(0b1011_0010u32);
+
+        // The Bulletproofs vectors of the value
+        let a_L = [0, 1, 0, 0, 1, 1, 0, 1].map(|b: u32| F::from(b));
+        let a_R = a_L.map(|b| b - F::ONE);
+        let pub_input = [a_L, a_R].concat();
+
+        let mut cs = ConstraintSystem::new();
+        cs.set_constraints(&range_circuit);
+
+        let witness = cs.gen_witness(range_circuit, &pub_input, &[value]);
+        assert!(cs.is_sat(&witness, &pub_input));
+
+        // Should fail for a value out of the range
+        let result = panic::catch_unwind(|| {
+            let mut cs = ConstraintSystem::new();
+            cs.set_constraints(&range_circuit);
+            cs.gen_witness(range_circuit, &pub_input, &[F::from(1u32 << N)]);
+        });
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,120 @@
+use super::bitops::{byte_to_le_bits, form_le_bits, to_le_bits};
+use crate::frontend::constraint_system::{ConstraintSystem, Wire};
+use ark_ff::PrimeField;
+
+// Constrain the big-endian byte encoding of a field element to be less than the modulus,
+// so that every field element has exactly one accepted encoding.
+pub fn assert_canonical_field_bytes<F: PrimeField>(
+    cs: &mut ConstraintSystem<F>,
+    bytes: &[Wire<F>; 32],
+) {
+    assert!(
+        F::MODULUS_BIT_SIZE <= 256,
+        "The field modulus must fit in 32 bytes"
+    );
+
+    // Most significant bit first
+    let mut bits = Vec::with_capacity(256);
+    for byte in bytes {
+        let mut byte_bits = byte_to_le_bits(*byte, cs);
+        byte_bits.reverse();
+        bits.extend_from_slice(&byte_bits);
+    }
+
+    let mut modulus_bits = F::MODULUS.to_bits_le();
+    modulus_bits.resize(256, false);
+    modulus_bits.reverse();
+
+    assert_less_than_const(
+        &bits,
+        &modulus_bits,
+        "bytes are not a canonical encoding of a field element",
+        cs,
+    );
+}
+
+// Constrain the binary wires `bits` to encode a value less than the constant `bound`,
+// with both the wires and the bits of `bound` given most significant bit first.
+pub(crate) fn assert_less_than_const<F: PrimeField>(
+    bits: &[Wire<F>],
+    bound: &[bool],
+    msg: &str,
+    cs: &mut ConstraintSystem<F>,
+) {
+    assert_eq!(bits.len(), bound.len());
+
+    // Compare the bits against the bound from the most significant bit.
+    // `is_eq` is one while all the bits so far are equal to the bound bits.
+    let mut is_eq = cs.one();
+    let mut is_lt = cs.zero();
+    for (bit, bound_bit) in bits.iter().zip(bound.iter()) {
+        if *bound_bit {
+            // The value is less than the bound if this is the first differing bit
+            is_lt = cs.mul_add(is_eq, !*bit, is_lt);
+            is_eq = is_eq * *bit;
+        } else {
+            is_eq = is_eq * !*bit;
+        }
+    }
+
+    let one = cs.one();
+    cs.assert_equal(is_lt, one, msg);
+}
+
+// Little-endian bits of a field element, constrained to be its canonical representation,
+// so that the encoding of a point is unique.
+pub(crate) fn to_canonical_le_bits<F: PrimeField>(
+    x: Wire<F>,
+    cs: &mut ConstraintSystem<F>,
+) -> Vec<Wire<F>> {
+    let bits = to_le_bits(x);
+
+    // Big-endian bytes
+    let bytes: [Wire<F>; 32] =
+        std::array::from_fn(|j| form_le_bits(&bits[8 * (31 - j)..8 * (32 - j)]));
+    assert_canonical_field_bytes(cs, &bytes);
+
+    bits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_var_pub_input;
+    use ark_ff::{BigInteger, Field};
+    use std::panic;
+
+    type F = ark_secq256k1::Fr;
+
+    fn canonical_bytes_circuit<F: PrimeField>(cs: &mut ConstraintSystem<F>) {
+        let bytes = cs.alloc_priv_inputs_arr();
+        assert_canonical_field_bytes(cs, &bytes);
+    }
+
+    fn to_byte_felts(bytes: &[u8]) -> Vec<F> {
+        bytes.iter().map(|b| F::from(*b)).collect()
+    }
+
+    #[test]
+    fn test_assert_canonical_field_bytes() {
+        let synthesizer = |cs: &mut ConstraintSystem<F>| canonical_bytes_circuit(cs);
+
+        let cases = [F::ZERO, F::from(123u32), -F::ONE];
+
+        for case in cases {
+            let priv_input = to_byte_felts(&case.into_bigint().to_bytes_be());
+            let pub_input: [F; 0] = [];
+
+            test_var_pub_input(synthesizer, &pub_input, &priv_input);
+        }
+
+        // Should reject the encoding of the modulus itself
+        let modulus_bytes = F::MODULUS.to_bytes_be();
+        let result = panic::catch_unwind(|| {
+            let mut cs = ConstraintSystem::<F>::new();
+            cs.set_constraints(&synthesizer);
+            cs.gen_witness(synthesizer, &[], &to_byte_felts(&modulus_bytes));
+        });
+        assert!(result.is_err());
+    }
+}
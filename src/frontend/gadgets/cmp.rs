@@ -0,0 +1,220 @@
+use super::bitops;
+use crate::frontend::constraint_system::Wire;
+use ark_ff::{Field, PrimeField};
+
+// Assert that `a` is less than 2^n.
+// `a` is decomposed into n binary bits, which must recompose to `a`.
+// Unlike `to_le_bits`, which decomposes into as many bits as the modulus,
+// a value that doesn't fit in n bits can't be recomposed.
+pub fn range_check<F: PrimeField>(a: Wire<F>, n: usize) {
+    assert!(
+        n < F::MODULUS_BIT_SIZE as usize,
+        "The range must be smaller than the field"
+    );
+
+    let cs = a.cs();
+    bitops::range_check(a, n, cs);
+}
+
+// Returns 1 if a < b, and 0 otherwise.
+// a and b must be known to be less than 2^n_bits (e.g. with `range_check`),
+// otherwise the output is meaningless.
+// a - b + 2^n_bits is then in [1, 2^(n_bits + 1)), and its bit n_bits is set iff a >= b.
+pub fn less_than<F: PrimeField>(a: Wire<F>, b: Wire<F>, n_bits: usize) -> Wire<F> {
+    assert!(
+        n_bits + 1 < F::MODULUS_BIT_SIZE as usize,
+        "The range must be smaller than the field"
+    );
+
+    let cs = a.cs();
+    let offset = cs.alloc_const(F::from(2u32).pow([n_bits as u64]));
+
+    let bits = bitops::to_le_bits_n(a - b + offset, n_bits + 1, cs);
+
+    let one = cs.one();
+    one - bits[n_bits]
+}
+
+// Returns 1 if a <= b, and 0 otherwise, with the same precondition as `less_than`.
+pub fn less_than_or_equal<F: PrimeField>(a: Wire<F>, b: Wire<F>, n_bits: usize) -> Wire<F> {
+    let cs = a.cs();
+    let one = cs.one();
+
+    one - less_than(b, a, n_bits)
+}
+
+// Returns 1 if a is zero, and 0 otherwise.
+// The inverse of `a` (or 0) is a hint, and out = 1 - a * inv together with a * out = 0
+// leaves no choice of the hint that gives another output.
+pub fn is_zero<F: PrimeField>(a: Wire<F>) -> Wire<F> {
+    a.cs().is_zero(a)
+}
+
+// Returns 1 if a equals b, and 0 otherwise.
+pub fn is_equal<F: PrimeField>(a: Wire<F>, b: Wire<F>) -> Wire<F> {
+    is_zero(a - b)
+}
+
+// Returns `a` if cond is 1, and `b` if cond is 0, as b + cond * (a - b).
+// `cond` is constrained to be binary, since any other value would select
+// a combination of the two.
+pub fn select<F: PrimeField>(cond: Wire<F>, a: Wire<F>, b: Wire<F>) -> Wire<F> {
+    let cs = cond.cs();
+    cs.assert_equal(cond * cond, cond, "select condition is not binary");
+
+    cs.mul_add(cond, a - b, b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frontend::constraint_system::ConstraintSystem;
+    use crate::test_var_pub_input;
+    use std::panic;
+
+    type F = ark_secq256k1::Fr;
+
+    fn range_check_circuit(cs: &mut ConstraintSystem<F>) {
+        let a = cs.alloc_priv_input();
+        range_check(a, 8);
+    }
+
+    #[test]
+    fn test_range_check() {
+        for a in [0u32, 1, 128, 255] {
+            test_var_pub_input(range_check_circuit, &[], &[F::from(a)]);
+        }
+
+        // The witness can't be generated for a value out of range
+        let result = panic::catch_unwind(|| {
+            let mut cs = ConstraintSystem::new();
+            cs.set_constraints(&range_check_circuit);
+            cs.gen_witness(range_check_circuit, &[], &[F::from(256u32)]);
+        });
+        assert!(result.is_err());
+
+        // and a value out of range doesn't satisfy the constraints with the bits of another value
+        let mut cs = ConstraintSystem::new();
+        cs.set_constraints(&range_check_circuit);
+        let mut witness = cs.gen_witness(range_check_circuit, &[], &[F::from(255u32)]);
+        assert!(cs.is_sat(&witness, &[]));
+
+        witness[0] = F::from(256u32);
+        assert!(!cs.is_sat(&witness, &[]));
+    }
+
+    const N_BITS: usize = 8;
+
+    #[test]
+    fn test_less_than() {
+        let synthesizer = |cs: &mut ConstraintSystem<F>| {
+            let a = cs.alloc_priv_input();
+            let b = cs.alloc_priv_input();
+
+            let lt = less_than(a, b, N_BITS);
+            let le = less_than_or_equal(a, b, N_BITS);
+
+            cs.expose_public(lt);
+            cs.expose_public(le);
+        };
+
+        let max = (1u64 << N_BITS) - 1;
+        let cases = [
+            // Less
+            (3, 5),
+            (0, max),
+            (max - 1, max),
+            // Equal
+            (5, 5),
+            (0, 0),
+            (max, max),
+            // Greater
+            (7, 5),
+            (max, 0),
+            (max, max - 1),
+        ];
+
+        for (a, b) in cases {
+            let pub_input = [F::from(a < b), F::from(a <= b)];
+            test_var_pub_input(synthesizer, &pub_input, &[F::from(a), F::from(b)]);
+        }
+    }
+
+    fn is_zero_circuit(cs: &mut ConstraintSystem<F>) {
+        let a = cs.alloc_priv_input();
+        let out = is_zero(a);
+        cs.expose_public(out);
+    }
+
+    #[test]
+    fn test_is_zero() {
+        for a in [F::ZERO, F::ONE, F::from(3u32), -F::ONE] {
+            test_var_pub_input(is_zero_circuit, &[F::from(a == F::ZERO)], &[a]);
+        }
+
+        // The output doesn't depend on the inverse hint, which follows the input
+        for (a, out) in [(F::ZERO, F::ONE), (F::from(3u32), F::ZERO)] {
+            let mut cs = ConstraintSystem::new();
+            cs.set_constraints(&is_zero_circuit);
+            let mut witness = cs.gen_witness(is_zero_circuit, &[out], &[a]);
+            assert!(cs.is_sat(&witness, &[out]));
+
+            witness[1] = F::from(7u32);
+            assert_eq!(cs.is_sat(&witness, &[out]), a == F::ZERO);
+            assert!(!cs.is_sat(&witness, &[F::ONE - out]));
+        }
+    }
+
+    #[test]
+    fn test_is_equal() {
+        let synthesizer = |cs: &mut ConstraintSystem<F>| {
+            let a = cs.alloc_priv_input();
+            let b = cs.alloc_priv_input();
+            let out = is_equal(a, b);
+            cs.expose_public(out);
+        };
+
+        for (a, b) in [(3u32, 3u32), (3, 4), (0, 0), (0, 4)] {
+            let pub_input = [F::from(a == b)];
+            test_var_pub_input(synthesizer, &pub_input, &[F::from(a), F::from(b)]);
+        }
+    }
+
+    fn select_circuit(cs: &mut ConstraintSystem<F>) {
+        let cond = cs.alloc_priv_input();
+        let a = cs.alloc_priv_input();
+        let b = cs.alloc_priv_input();
+
+        let out = select(cond, a, b);
+        cs.expose_public(out);
+    }
+
+    #[test]
+    fn test_select() {
+        let a = F::from(3u32);
+        let b = F::from(4u32);
+
+        test_var_pub_input(select_circuit, &[a], &[F::ONE, a, b]);
+        test_var_pub_input(select_circuit, &[b], &[F::ZERO, a, b]);
+        test_var_pub_input(select_circuit, &[a], &[F::ONE, a, a]);
+
+        // The witness can't be generated for a non-binary condition
+        let result = panic::catch_unwind(|| {
+            let mut cs = ConstraintSystem::new();
+            cs.set_constraints(&select_circuit);
+            cs.gen_witness(select_circuit, &[b], &[F::from(2u32), a, b]);
+        });
+        assert!(result.is_err());
+
+        // and a non-binary condition doesn't satisfy the constraints,
+        // even with an output that matches b + cond * (a - b)
+        let mut cs = ConstraintSystem::new();
+        cs.set_constraints(&select_circuit);
+        let mut witness = cs.gen_witness(select_circuit, &[b], &[F::ZERO, a, b]);
+        assert!(cs.is_sat(&witness, &[b]));
+
+        witness[0] = F::from(2u32);
+        let forged_out = b + F::from(2u32) * (a - b);
+        assert!(!cs.is_sat(&witness, &[forged_out]));
+    }
+}
@@ -0,0 +1,51 @@
+use crate::frontend::constraint_system::{ConstraintSystem, Wire};
+use ark_ff::Field;
+
+// Assert that `new == old + 1`
+pub fn assert_increment<F: Field>(cs: &mut ConstraintSystem<F>, old: Wire<F>, new: Wire<F>) {
+    let incremented = cs.add_const(old, F::ONE);
+    cs.assert_equal(new, incremented, "Counter is not incremented by one");
+}
+
+// Assert that each value in the sequence is the previous value plus one
+pub fn assert_sequential<F: Field>(cs: &mut ConstraintSystem<F>, values: &[Wire<F>]) {
+    for pair in values.windows(2) {
+        assert_increment(cs, pair[0], pair[1]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_var_pub_input;
+    use std::panic;
+
+    type F = ark_secq256k1::Fr;
+
+    const SEQUENCE_LEN: usize = 5;
+
+    fn sequence_circuit(cs: &mut ConstraintSystem<F>) {
+        let values = cs.alloc_priv_inputs(SEQUENCE_LEN);
+        assert_sequential(cs, &values);
+
+        cs.expose_public(values[SEQUENCE_LEN - 1]);
+    }
+
+    #[test]
+    fn test_assert_sequential() {
+        let priv_input = [7u32, 8, 9, 10, 11].map(F::from);
+        let pub_input = [F::from(11u32)];
+
+        test_var_pub_input(sequence_circuit, &pub_input, &priv_input);
+
+        // Should fail if the sequence contains a gap
+        let result = panic::catch_unwind(|| {
+            let mut cs = ConstraintSystem::<F>::new();
+            cs.set_constraints(&sequence_circuit);
+
+            let priv_input = [7u32, 8, 10, 11, 12].map(F::from);
+            cs.gen_witness(sequence_circuit, &[F::from(12u32)], &priv_input);
+        });
+        assert!(result.is_err());
+    }
+}
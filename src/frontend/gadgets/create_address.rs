@@ -0,0 +1,201 @@
+use super::bitops::byte_to_le_bits;
+use super::to_addr::{keccak256_padded, pack_address, RATE};
+use crate::frontend::constraint_system::{ConstraintSystem, Wire};
+use ark_ff::{BigInteger, PrimeField};
+
+// Nonces are at most 2^64 - 1 (EIP-2681)
+const NONCE_BYTES: usize = 8;
+
+// Returns the address of a contract created with CREATE,
+// i.e. the last 20 bytes of keccak256(rlp([sender, nonce])).
+// `sender` is the 160 bits of the sender address in the same layout as the input of `to_addr`
+// (bytes in order, bits little-endian within each byte).
+// The nonce is constrained to be less than 2^64.
+pub fn create_address<F: PrimeField>(
+    cs: &mut ConstraintSystem<F>,
+    sender: &[Wire<F>],
+    nonce: Wire<F>,
+) -> Wire<F> {
+    assert_eq!(sender.len(), 160, "The sender must be 160 bits");
+
+    let zero = cs.zero();
+    let one = cs.one();
+
+    // Decompose the nonce into little-endian bytes
+    let nonce_bytes_val = nonce.val(cs).map(|val| val.into_bigint().to_bytes_le());
+    let nonce_bytes = (0..NONCE_BYTES)
+        .map(|i| {
+            let byte = nonce_bytes_val.as_ref().map_or(0, |bytes| bytes[i]);
+            cs.alloc_var(F::from(byte))
+        })
+        .collect::<Vec<Wire<F>>>();
+
+    let nonce_bits = nonce_bytes
+        .iter()
+        .map(|byte| byte_to_le_bits(*byte, cs))
+        .collect::<Vec<Vec<Wire<F>>>>();
+
+    let mut terms = Vec::with_capacity(NONCE_BYTES);
+    let mut pow = F::ONE;
+    for byte in &nonce_bytes {
+        terms.push((cs.mul_const(*byte, pow), true));
+        pow *= F::from(256u32);
+    }
+    let recovered_nonce = cs.sum(&terms);
+    cs.assert_equal(nonce, recovered_nonce, "The nonce must be less than 2^64");
+
+    // is_significant[k] is 1 if any of the bytes k, k + 1, ... of the nonce is non-zero
+    let mut is_significant = vec![zero; NONCE_BYTES];
+    for k in (0..NONCE_BYTES).rev() {
+        let is_nonzero = !nonce_bytes[k].is_zero();
+        is_significant[k] = if k == NONCE_BYTES - 1 {
+            is_nonzero
+        } else {
+            is_nonzero | is_significant[k + 1]
+        };
+    }
+
+    // The length of the big-endian encoding of the nonce (without leading zeros),
+    // and is_len[l] that is 1 if the length is l.
+    let nonce_len = cs.sum(
+        &is_significant
+            .iter()
+            .map(|w| (*w, true))
+            .collect::<Vec<(Wire<F>, bool)>>(),
+    );
+
+    let mut is_len = Vec::with_capacity(NONCE_BYTES + 1);
+    is_len.push(!is_significant[0]);
+    for l in 1..NONCE_BYTES {
+        is_len.push(is_significant[l - 1] - is_significant[l]);
+    }
+    is_len.push(is_significant[NONCE_BYTES - 1]);
+
+    // Nonces less than 0x80 are encoded as a single byte (and zero as 0x80).
+    // Larger nonces are encoded as 0x80 + length followed by the big-endian bytes.
+    let is_small = !is_significant[1] & !nonce_bits[0][7];
+
+    // 0xc0 + the length of the list payload, which is 21 bytes for the sender
+    // and 1 or 1 + length bytes for the nonce.
+    let large_payload_len = !is_small * nonce_len;
+    let list_prefix = cs.add_const(large_payload_len, F::from(0xd6u32));
+
+    let zero_nonce_encoding = cs.mul_const(is_len[0], F::from(0x80u32));
+    let small_encoding = zero_nonce_encoding + nonce_bytes[0];
+    let large_prefix = cs.add_const(nonce_len, F::from(0x80u32));
+    let encoding_first = cs.if_then(is_small, small_encoding).else_then(large_prefix);
+
+    // The bytes after the first byte of the nonce encoding,
+    // including the first byte of the padding (0x01) right after the message.
+    let mut encoding_rest = Vec::with_capacity(NONCE_BYTES + 1);
+    for j in 1..=(NONCE_BYTES + 1) {
+        let mut terms = vec![];
+
+        // The j-th big-endian byte, if the length is at least j
+        for l in j..=NONCE_BYTES {
+            terms.push((is_len[l] * nonce_bytes[l - j], true));
+        }
+
+        // The padding, if the length is j - 1
+        terms.push((is_len[j - 1], true));
+
+        let large_byte = cs.sum(&terms);
+        let small_byte = if j == 1 { one } else { zero };
+
+        encoding_rest.push(cs.if_then(is_small, small_byte).else_then(large_byte));
+    }
+
+    let const_byte_bits = |byte: u8| {
+        (0..8)
+            .map(|i| if (byte >> i) & 1 == 1 { one } else { zero })
+            .collect::<Vec<Wire<F>>>()
+    };
+
+    // The message with its padding fits in a single block
+    let mut padded_input = Vec::with_capacity(RATE);
+    padded_input.extend(byte_to_le_bits(list_prefix, cs));
+    padded_input.extend(const_byte_bits(0x94));
+    padded_input.extend_from_slice(sender);
+    padded_input.extend(byte_to_le_bits(encoding_first, cs));
+    for byte in encoding_rest {
+        padded_input.extend(byte_to_le_bits(byte, cs));
+    }
+
+    padded_input.resize(RATE, zero);
+    padded_input[RATE - 1] = one;
+
+    let digest = keccak256_padded(&padded_input);
+    pack_address(&digest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bytes_to_le_bits;
+    use ark_ff::Field;
+    use num_bigint::BigUint;
+    use std::panic;
+
+    type F = ark_secq256k1::Fr;
+
+    const SENDER: &str = "6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0";
+
+    fn create_address_circuit(cs: &mut ConstraintSystem<F>) {
+        let sender = cs.alloc_priv_inputs(160);
+        let nonce = cs.alloc_priv_input();
+
+        let address = create_address(cs, &sender, nonce);
+        cs.expose_public(address);
+    }
+
+    fn priv_input(nonce: F) -> Vec<F> {
+        let mut priv_input = bytes_to_le_bits::<F>(&hex::decode(SENDER).unwrap());
+        priv_input.push(nonce);
+
+        priv_input
+    }
+
+    fn address(address_str: &str) -> F {
+        F::from(BigUint::from_bytes_be(&hex::decode(address_str).unwrap()))
+    }
+
+    #[test]
+    fn test_create_address() {
+        // Contract addresses of the sender computed off-circuit
+        let cases = [
+            (0u64, "cd234a471b72ba2f1ccf0a70fcaba648a5eecd8d"),
+            (1, "343c43a37d37dff08ae8c4a11544c718abb4fcf8"),
+            (0x7f, "06d9a77f5e4b311bae8d559db9cdb4df94104aa0"),
+            (0x80, "08e190dcb7b73f5fcdabb43e102215c83659a76d"),
+            (0x1234, "e57c87ba715dd75f735ebb2644c07375f4c4f0e1"),
+            (u64::MAX, "9bc924993b60399df164c3763a964301d3db95ca"),
+        ];
+
+        let mut cs = ConstraintSystem::new();
+        cs.set_constraints(&create_address_circuit);
+
+        for (nonce, expected) in cases {
+            let pub_input = [address(expected)];
+            let witness = cs.gen_witness(
+                create_address_circuit,
+                &pub_input,
+                &priv_input(F::from(nonce)),
+            );
+
+            assert!(cs.is_sat(&witness, &pub_input));
+
+            // Should fail for a different address
+            assert!(!cs.is_sat(&witness, &[pub_input[0] + F::ONE]));
+        }
+
+        // Should reject nonces of 2^64 and above
+        let result = panic::catch_unwind(|| {
+            let mut cs = ConstraintSystem::new();
+            cs.set_constraints(&create_address_circuit);
+
+            let nonce = F::from(u64::MAX) + F::ONE;
+            cs.gen_witness(create_address_circuit, &[F::ZERO], &priv_input(nonce));
+        });
+        assert!(result.is_err());
+    }
+}
@@ -32,6 +32,8 @@ pub fn ec_add_incomplete<F: Field>(p: AffinePoint<F>, q: AffinePoint<F>) -> Affi
 // Complete addition for short-Weierstrass curves.
 // We follow the specification from the halo2 book.
 // https://zcash.github.io/halo2/design/gadgets/ecc/addition.html#complete-addition
+// The identity is represented as (0, 0). P + P takes the slope of the tangent,
+// and P + (-P) is the identity.
 pub fn ec_add_complete<F: Field>(
     p: AffinePoint<F>,
     q: AffinePoint<F>,
@@ -47,21 +49,41 @@ pub fn ec_add_complete<F: Field>(
     let is_sym = is_x_equal & (p.y.is_equal(-q.y));
     let is_out_zero = both_zeros.or(is_sym, cs);
 
+    let is_double = is_x_equal & p.y.is_equal(q.y);
+
     let zero = cs.zero();
 
-    let inc_add = ec_add_incomplete(p, q);
+    // lambda = (3 * x^2) / (2 * y) if P = Q, and (p.y - q.y) / (p.x - q.x) otherwise
+    let three = cs.alloc_const(F::from(3u32));
+    let two = cs.alloc_const(F::from(2u32));
+    let lambda_double = (three * (p.x * p.x)).div_or_zero(two * p.y);
+    let lambda_add = (p.y - q.y).div_or_zero(p.x - q.x);
+    let lambda = cs.if_then(is_double, lambda_double).else_then(lambda_add);
+
+    // out_x = (lambda * lambda) - p.x - q.x;
+    let sum_x = cs.constrain(
+        &[(lambda, F::ONE)],
+        &[(lambda, F::ONE)],
+        &[(p.x, -F::ONE), (q.x, -F::ONE)],
+    );
+    // out_y = lambda * (p.x - out_x) - p.y;
+    let sum_y = cs.constrain(
+        &[(lambda, F::ONE)],
+        &[(p.x, F::ONE), (sum_x, -F::ONE)],
+        &[(p.y, -F::ONE)],
+    );
 
     let out_x = cs
         .if_then(is_out_zero, zero)
         .elif(p_is_zero, q.x, cs)
         .elif(q_is_zero, p.x, cs)
-        .else_then(inc_add.x);
+        .else_then(sum_x);
 
     let out_y = cs
         .if_then(is_out_zero, zero)
         .elif(p_is_zero, q.y, cs)
         .elif(q_is_zero, p.y, cs)
-        .else_then(inc_add.y);
+        .else_then(sum_y);
 
     AffinePoint::new(out_x, out_y)
 }
@@ -139,6 +161,10 @@ mod tests {
             (p_nonzero, zero),
             (p_nonzero, -q_nonzero),
             (p_nonzero, q_nonzero),
+            // Doubling
+            (p_nonzero, p_nonzero),
+            // Identity
+            (p_nonzero, -p_nonzero),
         ];
 
         for (p, q) in cases {
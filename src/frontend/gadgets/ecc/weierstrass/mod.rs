@@ -1,7 +1,9 @@
 mod add;
 mod double;
 mod mul;
+mod pubkey;
 
 pub use add::{ec_add_complete, ec_add_incomplete};
 pub use double::ec_double;
-pub use mul::ec_mul;
+pub use mul::{ec_mul, scalar_mul, WINDOW_BITS};
+pub use pubkey::{assert_valid_pubkey, secp256k1_generator, SECP256K1_B, SECP256K1_N};
@@ -1,7 +1,13 @@
 use super::super::AffinePoint;
-use super::{add::ec_add_complete, double::ec_double};
+use super::{
+    add::{ec_add_complete, ec_add_incomplete},
+    double::ec_double,
+};
 use crate::frontend::constraint_system::{ConstraintSystem, Wire};
-use ark_ff::Field;
+use ark_ff::{Field, PrimeField};
+
+// The number of scalar bits processed at once by `scalar_mul`
+pub const WINDOW_BITS: usize = 4;
 
 // Naive double-and-add algorithm
 pub fn ec_mul<F: Field>(
@@ -25,6 +31,83 @@ pub fn ec_mul<F: Field>(
     result
 }
 
+// Doubling that maps the identity (0, 0) to itself.
+// On a curve of odd order, 2 * y is zero only for the identity.
+fn ec_double_or_identity<F: Field>(
+    p: AffinePoint<F>,
+    cs: &mut ConstraintSystem<F>,
+) -> AffinePoint<F> {
+    // lambda = (3 * x^2) / (2 * y)
+    let three = cs.alloc_const(F::from(3u32));
+    let two = cs.alloc_const(F::from(2u32));
+    let lambda = (three * (p.x * p.x)).div_or_zero(two * p.y);
+
+    // x = lambda^2 - 2 * x
+    let out_x = (lambda * lambda) - (p.x * two);
+    // y = lambda * (x - out_x) - y
+    let out_y = lambda * (p.x - out_x) - p.y;
+
+    AffinePoint::new(out_x, out_y)
+}
+
+// Select table[index], where index is given by its little-endian bits,
+// by halving the table with each bit.
+// Each bit is constrained to be binary once, rather than by every `select` it drives.
+fn lookup<F: PrimeField>(table: &[AffinePoint<F>], index_bits: &[Wire<F>]) -> AffinePoint<F> {
+    let cs = index_bits[0].cs();
+
+    let mut level = table[..(1 << index_bits.len())].to_vec();
+    for bit in index_bits {
+        cs.assert_equal(*bit * *bit, *bit, "window bit is not binary");
+
+        // pair[0] + bit * (pair[1] - pair[0]), as in `select`
+        level = level
+            .chunks(2)
+            .map(|pair| {
+                AffinePoint::new(
+                    cs.mul_add(*bit, pair[1].x - pair[0].x, pair[0].x),
+                    cs.mul_add(*bit, pair[1].y - pair[0].y, pair[0].y),
+                )
+            })
+            .collect();
+    }
+
+    level[0]
+}
+
+// Fixed-window scalar multiplication, with the little-endian bits of the scalar.
+// The multiples 0, base, ..., (2^WINDOW_BITS - 1) * base are precomputed,
+// and the windows are processed from the most significant one,
+// doubling the result WINDOW_BITS times and adding the multiple selected by the window.
+// The bits are constrained to be binary by `lookup`.
+// The base must not be the identity.
+pub fn scalar_mul<F: PrimeField>(scalar_bits: &[Wire<F>], base: &AffinePoint<F>) -> AffinePoint<F> {
+    let cs = base.x.cs();
+
+    // j * base for j < 2^WINDOW_BITS.
+    // j * base is never +-base for j > 2, so the incomplete addition suffices.
+    let infinity = AffinePoint::new(cs.zero(), cs.zero());
+    let mut table = vec![infinity, *base, ec_double(*base, cs)];
+    for j in 3..(1 << WINDOW_BITS) {
+        table.push(ec_add_incomplete(table[j - 1], *base));
+    }
+
+    let mut result = infinity;
+    for (i, window) in scalar_bits.chunks(WINDOW_BITS).rev().enumerate() {
+        // The first window may be shorter, and nothing needs to be doubled before it
+        if i > 0 {
+            for _ in 0..window.len() {
+                result = ec_double_or_identity(result, cs);
+            }
+        }
+
+        let multiple = lookup(&table, window);
+        result = ec_add_complete(multiple, result, cs);
+    }
+
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use crate::test_var_pub_input;
@@ -72,4 +155,113 @@ mod tests {
 
         test_var_pub_input(synthesizer, &pub_input, &priv_input);
     }
+
+    fn scalar_input(s: Fr) -> Vec<F> {
+        s.into_bigint()
+            .to_bits_le()
+            .iter()
+            .map(|b| F::from(*b))
+            .collect()
+    }
+
+    fn scalar_mul_circuit(cs: &mut ConstraintSystem<F>) {
+        let p_x = cs.alloc_priv_input();
+        let p_y = cs.alloc_priv_input();
+
+        let s_bits = cs.alloc_priv_inputs(256);
+
+        let p = AffinePoint::new(p_x, p_y);
+
+        let out = scalar_mul(&s_bits, &p);
+
+        cs.expose_public(out.x);
+        cs.expose_public(out.y);
+    }
+
+    #[test]
+    pub fn test_scalar_mul() {
+        let p = (Secp256k1Affine::generator() * Fr::from(7u32)).into_affine();
+
+        let scalars = [
+            Fr::from(0u32),
+            Fr::from(1u32),
+            Fr::from(3u32),
+            // Zero windows after the first non-zero one
+            Fr::from(0x100010u32),
+            Fr::from(124221521521u64),
+            -Fr::from(1u32),
+        ];
+
+        for s in scalars {
+            let out = (p * s).into_affine();
+
+            let pub_input = [out.x, out.y];
+            let mut priv_input = vec![p.x, p.y];
+            priv_input.extend(scalar_input(s));
+
+            test_var_pub_input(scalar_mul_circuit, &pub_input, &priv_input);
+        }
+
+        // The bits must be binary
+        let mut cs = ConstraintSystem::new();
+        cs.set_constraints(&scalar_mul_circuit);
+
+        let s = Fr::from(6u32);
+        let out = (p * s).into_affine();
+        let mut priv_input = vec![p.x, p.y];
+        priv_input.extend(scalar_input(s));
+
+        let mut witness = cs.gen_witness(scalar_mul_circuit, &[out.x, out.y], &priv_input);
+        assert!(cs.is_sat(&witness, &[out.x, out.y]));
+
+        // 6 = 2 * 1 + 4, with bit 0 set to 2 instead of setting bit 1
+        witness[2] = F::from(2u32);
+        witness[3] = F::ZERO;
+        assert!(!cs.is_sat(&witness, &[out.x, out.y]));
+    }
+
+    #[test]
+    pub fn test_scalar_mul_num_constraints() {
+        let naive_circuit = |cs: &mut ConstraintSystem<F>| {
+            let p_x = cs.alloc_priv_input();
+            let p_y = cs.alloc_priv_input();
+            let s_bits = cs.alloc_priv_inputs(256);
+
+            let out = ec_mul(AffinePoint::new(p_x, p_y), &s_bits, cs);
+
+            cs.expose_public(out.x);
+            cs.expose_public(out.y);
+        };
+
+        let mut naive_cs = ConstraintSystem::new();
+        naive_cs.set_constraints(&naive_circuit);
+
+        let mut windowed_cs = ConstraintSystem::new();
+        windowed_cs.set_constraints(&scalar_mul_circuit);
+
+        assert!(windowed_cs.num_constraints() < naive_cs.num_constraints());
+    }
+
+    #[test]
+    pub fn test_lookup_num_constraints() {
+        let lookup_circuit = |cs: &mut ConstraintSystem<F>| {
+            let table = (0..(1 << WINDOW_BITS))
+                .map(|_| AffinePoint::new(cs.alloc_priv_input(), cs.alloc_priv_input()))
+                .collect::<Vec<AffinePoint<F>>>();
+            let index_bits = cs.alloc_priv_inputs(WINDOW_BITS);
+
+            let out = lookup(&table, &index_bits);
+
+            cs.expose_public(out.x);
+            cs.expose_public(out.y);
+        };
+
+        let mut cs = ConstraintSystem::new();
+        cs.set_constraints(&lookup_circuit);
+
+        // The first constraint, two constraints to check that each bit is binary,
+        // and one constraint per coordinate of each pair that is selected from
+        let num_pairs = (1 << WINDOW_BITS) - 1;
+        assert_eq!(cs.num_constraints(), 1 + 2 * WINDOW_BITS + 2 * num_pairs);
+    }
 }
@@ -0,0 +1,71 @@
+use super::super::AffinePoint;
+use crate::frontend::constraint_system::ConstraintSystem;
+use ark_ff::{Field, PrimeField};
+
+// The coefficient b of secp256k1: y^2 = x^3 + 7
+pub const SECP256K1_B: u32 = 7;
+
+// The generator of secp256k1
+const SECP256K1_GX: &str = "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+const SECP256K1_GY: &str = "483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8";
+
+// The order n of the group of secp256k1, as a big-endian hex string
+pub const SECP256K1_N: &str = "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141";
+
+// The generator of secp256k1 as constant wires.
+// The circuit must be over the base field of secp256k1.
+pub fn secp256k1_generator<F: PrimeField>(cs: &mut ConstraintSystem<F>) -> AffinePoint<F> {
+    AffinePoint::new(
+        cs.alloc_const(F::from_be_bytes_mod_order(
+            &hex::decode(SECP256K1_GX).unwrap(),
+        )),
+        cs.alloc_const(F::from_be_bytes_mod_order(
+            &hex::decode(SECP256K1_GY).unwrap(),
+        )),
+    )
+}
+
+// Assert that `pubkey` is a point on secp256k1.
+// secp256k1 has cofactor 1, so every point on the curve is in the prime-order subgroup.
+// The point at infinity has no affine coordinates and is rejected as well.
+// The circuit must be over the base field of secp256k1.
+pub fn assert_valid_pubkey<F: Field>(cs: &mut ConstraintSystem<F>, pubkey: &AffinePoint<F>) {
+    let y_sq = pubkey.y * pubkey.y;
+    let x_cube = pubkey.x * pubkey.x * pubkey.x;
+    let rhs = cs.add_const(x_cube, F::from(SECP256K1_B));
+
+    cs.assert_equal(y_sq, rhs, "The public key is not on secp256k1");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_var_pub_input;
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_secp256k1::Affine as Secp256k1Affine;
+    use ark_secp256k1::Fr;
+    use std::panic;
+
+    type F = ark_secp256k1::Fq;
+
+    fn pubkey_circuit(cs: &mut ConstraintSystem<F>) {
+        let x = cs.alloc_pub_input();
+        let y = cs.alloc_pub_input();
+
+        assert_valid_pubkey(cs, &AffinePoint::new(x, y));
+    }
+
+    #[test]
+    fn test_assert_valid_pubkey() {
+        let pubkey = (Secp256k1Affine::generator() * Fr::from(12345u32)).into_affine();
+        test_var_pub_input(pubkey_circuit, &[pubkey.x, pubkey.y], &[]);
+
+        // Should fail for a point off the curve
+        let result = panic::catch_unwind(|| {
+            let mut cs = ConstraintSystem::<F>::new();
+            cs.set_constraints(&pubkey_circuit);
+            cs.gen_witness(pubkey_circuit, &[pubkey.x, pubkey.y + F::from(1u32)], &[]);
+        });
+        assert!(result.is_err());
+    }
+}
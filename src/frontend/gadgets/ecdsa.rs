@@ -0,0 +1,223 @@
+use super::canonical::{assert_less_than_const, to_canonical_le_bits};
+use super::ecc::weierstrass::{
+    assert_valid_pubkey, ec_add_complete, scalar_mul, secp256k1_generator, SECP256K1_B, SECP256K1_N,
+};
+use super::ecc::AffinePoint;
+use super::to_addr::to_addr;
+use crate::frontend::constraint_system::{ConstraintSystem, Wire};
+use ark_ff::PrimeField;
+
+// Verify the ECDSA signature (r, s) of `msg_hash` by `pubkey` over secp256k1,
+// and return the Ethereum address of `pubkey`.
+// `msg_hash` is the hash of the message reduced modulo the order n of secp256k1.
+// r and s must be in [1, n - 1], so that each signature has a single encoding.
+// Instead of computing z / s and r / s modulo n, which isn't the native field,
+// the point R with x-coordinate r is a hint, and s * R = z * G + r * Q is checked.
+// The signature only fixes the x-coordinate of R, so s * R = -(z * G + r * Q) is accepted as well.
+// A signature where the x-coordinate of R is at least n is rejected,
+// which happens with probability below 2^-127.
+// The circuit must be over the base field of secp256k1.
+pub fn verify_ecdsa<F: PrimeField>(
+    cs: &mut ConstraintSystem<F>,
+    msg_hash: Wire<F>,
+    r: Wire<F>,
+    s: Wire<F>,
+    pubkey: AffinePoint<F>,
+) -> Wire<F> {
+    assert_eq!(
+        F::MODULUS_BIT_SIZE,
+        256,
+        "The circuit must be over secp256k1"
+    );
+    assert_valid_pubkey(cs, &pubkey);
+
+    let z_bits = to_canonical_le_bits(msg_hash, cs);
+    let r_bits = to_canonical_le_bits(r, cs);
+    let s_bits = to_canonical_le_bits(s, cs);
+
+    assert_scalar_in_range(r, &r_bits, "r is out of range", cs);
+    assert_scalar_in_range(s, &s_bits, "s is out of range", cs);
+
+    // R = (r, sqrt(r^3 + 7)), with either of the square roots
+    let r_cube_b = cs.add_const(r * r * r, F::from(SECP256K1_B));
+    let r_y_val = r_cube_b.val(cs).and_then(|val| val.sqrt());
+    let r_y = cs.alloc_var(r_y_val.unwrap_or(F::ZERO));
+    cs.assert_equal(
+        r_y * r_y,
+        r_cube_b,
+        "r is not the x-coordinate of a point on secp256k1",
+    );
+    let big_r = AffinePoint::new(r, r_y);
+
+    let g = secp256k1_generator(cs);
+    let s_r = scalar_mul(&s_bits, &big_r);
+    let z_g = scalar_mul(&z_bits, &g);
+    let r_q = scalar_mul(&r_bits, &pubkey);
+    let expected = ec_add_complete(z_g, r_q, cs);
+
+    let zero = cs.zero();
+
+    // s * R = +-(z * G + r * Q).
+    // s isn't zero modulo n, so s * R isn't the identity.
+    cs.assert_equal(s_r.x, expected.x, "Invalid signature");
+    cs.assert_equal(
+        (s_r.y - expected.y) * (s_r.y + expected.y),
+        zero,
+        "Invalid signature",
+    );
+
+    // The public key as 64 big-endian bytes, with the bits of each byte little-endian
+    let x_bits = to_canonical_le_bits(pubkey.x, cs);
+    let y_bits = to_canonical_le_bits(pubkey.y, cs);
+
+    let mut pubkey_bits = Vec::with_capacity(512);
+    for bits in [&x_bits, &y_bits] {
+        for j in 0..32 {
+            pubkey_bits.extend_from_slice(&bits[8 * (31 - j)..8 * (32 - j)]);
+        }
+    }
+
+    to_addr(pubkey_bits.try_into().unwrap())
+}
+
+// Constrain a scalar, given with its little-endian bits, to be in [1, n - 1]
+fn assert_scalar_in_range<F: PrimeField>(
+    x: Wire<F>,
+    bits: &[Wire<F>],
+    msg: &str,
+    cs: &mut ConstraintSystem<F>,
+) {
+    // Most significant bit first
+    let bits = bits.iter().rev().copied().collect::<Vec<Wire<F>>>();
+    let n_bits = hex::decode(SECP256K1_N)
+        .unwrap()
+        .iter()
+        .flat_map(|byte| (0..8).rev().map(move |i| byte >> i & 1 == 1))
+        .collect::<Vec<bool>>();
+    assert_less_than_const(&bits, &n_bits, msg, cs);
+
+    let zero = cs.zero();
+    let x_is_zero = x.is_zero();
+    cs.assert_equal(x_is_zero, zero, msg);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eth_utils::address_from_digest;
+    use crate::test_var_pub_input;
+
+    type F = ark_secp256k1::Fq;
+
+    fn from_hex(x: &str) -> F {
+        F::from_be_bytes_mod_order(&hex::decode(x).unwrap())
+    }
+
+    fn ecdsa_circuit(cs: &mut ConstraintSystem<F>) {
+        let msg_hash = cs.alloc_priv_input();
+        let r = cs.alloc_priv_input();
+        let s = cs.alloc_priv_input();
+        let pubkey = AffinePoint::new(cs.alloc_priv_input(), cs.alloc_priv_input());
+
+        let address = verify_ecdsa(cs, msg_hash, r, s, pubkey);
+        cs.expose_public(address);
+    }
+
+    // A signature of keccak256("hello sapir") by the private key
+    // 0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318,
+    // which has the address 0x2c7536e3605d9c16a7a3d7b1898e529396a65c23
+    fn signature_input() -> (Vec<F>, Vec<F>) {
+        let digest: [u8; 32] =
+            hex::decode("2d0711265872909a648495892c7536e3605d9c16a7a3d7b1898e529396a65c23")
+                .unwrap()
+                .try_into()
+                .unwrap();
+        let address = address_from_digest::<F>(&digest);
+
+        let priv_input = vec![
+            // msg_hash
+            from_hex("a1d24d1fe9a95968c71679cfbdf038a758fd53bf81128d6ec97baf19f3a97816"),
+            // r
+            from_hex("8c6d087890e413e671409a94b68a9f8c92af277e63c808415860cbcc97b53221"),
+            // s
+            from_hex("c05a3449efe4a539795f22504591ec2d22f682822d60984c8cf3fc34a0c8d79e"),
+            // The public key
+            from_hex("4e3b81af9c2234cad09d679ce6035ed1392347ce64ce405f5dcd36228a25de6e"),
+            from_hex("47fd35c4215d1edf53e6f83de344615ce719bdb0fd878f6ed76f06dd277956de"),
+        ];
+
+        (vec![address], priv_input)
+    }
+
+    // A signature by the same key with a small s, over a digest chosen for it,
+    // so that s + n is less than the modulus
+    fn small_s_signature_input() -> (Vec<F>, Vec<F>) {
+        let (pub_input, mut priv_input) = signature_input();
+
+        // msg_hash
+        priv_input[0] =
+            from_hex("00dfde4d0cbaf0b0cf1b1ec0310fd3c36f10c216bfd0f0268b6ed314db8932de");
+        // r
+        priv_input[1] =
+            from_hex("fe006629a91f9a032cadb31ddeabe4cecec5648f9a774e660c03d0bc43198cf5");
+        // s
+        priv_input[2] = F::from(0x1234567u32);
+
+        (pub_input, priv_input)
+    }
+
+    fn gen_witness(pub_input: &[F], priv_input: &[F]) {
+        let mut cs = ConstraintSystem::new();
+        cs.set_constraints(&ecdsa_circuit);
+        cs.gen_witness(ecdsa_circuit, pub_input, priv_input);
+    }
+
+    #[test]
+    fn test_verify_ecdsa() {
+        let (pub_input, priv_input) = signature_input();
+        test_var_pub_input(ecdsa_circuit, &pub_input, &priv_input);
+
+        let (pub_input, priv_input) = small_s_signature_input();
+        test_var_pub_input(ecdsa_circuit, &pub_input, &priv_input);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid signature")]
+    fn test_verify_ecdsa_wrong_message() {
+        let (pub_input, mut priv_input) = signature_input();
+        priv_input[0] += F::from(1u32);
+        gen_witness(&pub_input, &priv_input);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid signature")]
+    fn test_verify_ecdsa_wrong_s() {
+        let (pub_input, mut priv_input) = signature_input();
+        priv_input[2] += F::from(1u32);
+        gen_witness(&pub_input, &priv_input);
+    }
+
+    #[test]
+    #[should_panic(expected = "s is out of range")]
+    fn test_verify_ecdsa_s_plus_n() {
+        let (pub_input, mut priv_input) = small_s_signature_input();
+        priv_input[2] += from_hex(SECP256K1_N);
+        gen_witness(&pub_input, &priv_input);
+    }
+
+    #[test]
+    #[should_panic(expected = "r is out of range")]
+    fn test_verify_ecdsa_zero_r() {
+        let (pub_input, mut priv_input) = signature_input();
+        priv_input[1] = F::from(0u32);
+        gen_witness(&pub_input, &priv_input);
+    }
+
+    #[test]
+    #[should_panic(expected = "r is out of range")]
+    fn test_verify_ecdsa_r_at_least_n() {
+        let (pub_input, mut priv_input) = signature_input();
+        priv_input[1] = from_hex(SECP256K1_N);
+        gen_witness(&pub_input, &priv_input);
+    }
+}
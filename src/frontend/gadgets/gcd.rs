@@ -0,0 +1,119 @@
+use super::bitops::range_check;
+use crate::frontend::constraint_system::{ConstraintSystem, Wire};
+use ark_ff::PrimeField;
+
+// The inputs of `verify_gcd` are less than 2^GCD_BITS
+pub const GCD_BITS: usize = 64;
+
+// Returns (g, x, y) such that a * x + b * y = g = gcd(a, b)
+fn ext_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a, 1, 0)
+    } else {
+        let (g, x, y) = ext_gcd(b, a % b);
+        (g, y, x - (a / b) * y)
+    }
+}
+
+// Assert that g = gcd(a, b) for integers a, b and g less than 2^GCD_BITS.
+// The prover provides the quotients a / g and b / g, which show that g divides a and b,
+// and Bezout coefficients x, y >= 0 with a * x - b * y = +-g,
+// which show that every common divisor of a and b divides g.
+pub fn verify_gcd<F: PrimeField>(cs: &mut ConstraintSystem<F>, a: Wire<F>, b: Wire<F>, g: Wire<F>) {
+    // The products below are less than 2^(2 * GCD_BITS), so they don't wrap around
+    assert!(
+        2 * GCD_BITS + 1 < F::MODULUS_BIT_SIZE as usize,
+        "The field is too small"
+    );
+
+    range_check(a, GCD_BITS, cs);
+    range_check(b, GCD_BITS, cs);
+    range_check(g, GCD_BITS, cs);
+
+    // Compute the hints from the values, which are in range if the range checks pass
+    let to_u64 = |w: Wire<F>, cs: &mut ConstraintSystem<F>| {
+        w.val(cs).map(|val| val.into_bigint().as_ref()[0])
+    };
+    let hints = match (to_u64(a, cs), to_u64(b, cs), to_u64(g, cs)) {
+        (Some(a), Some(b), Some(g)) => {
+            let (a_quot, b_quot) = if g == 0 { (0, 0) } else { (a / g, b / g) };
+
+            // The coefficients have opposite signs (or one is zero),
+            // and their absolute values are at most max(a, b).
+            let (_, x, y) = ext_gcd(a as i128, b as i128);
+            let (x, y, is_neg) = if x >= 0 && y <= 0 {
+                (x, -y, false)
+            } else {
+                (-x, y, true)
+            };
+
+            [a_quot, b_quot, x as u64, y as u64, is_neg as u64]
+        }
+        _ => [0; 5],
+    };
+
+    let [a_quot, b_quot, x, y, is_neg] = hints.map(|hint| cs.alloc_var(F::from(hint)));
+
+    // g divides a and b
+    range_check(a_quot, GCD_BITS, cs);
+    range_check(b_quot, GCD_BITS, cs);
+    cs.assert_equal(g * a_quot, a, "g doesn't divide a");
+    cs.assert_equal(g * b_quot, b, "g doesn't divide b");
+
+    // a * x - b * y = g if is_neg is 0, and -g if it's 1
+    range_check(x, GCD_BITS, cs);
+    range_check(y, GCD_BITS, cs);
+    cs.assert_equal(is_neg * is_neg, is_neg, "sign is not binary");
+
+    let signed_g = g - cs.mul_const(is_neg * g, F::from(2u32));
+    let bezout = a * x - b * y;
+    cs.assert_equal(bezout, signed_g, "g is not the gcd of a and b");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_var_pub_input;
+    use std::panic;
+
+    type F = ark_secq256k1::Fr;
+
+    fn gcd_circuit(cs: &mut ConstraintSystem<F>) {
+        let a = cs.alloc_pub_input();
+        let b = cs.alloc_pub_input();
+        let g = cs.alloc_pub_input();
+
+        verify_gcd(cs, a, b, g);
+    }
+
+    #[test]
+    fn test_verify_gcd() {
+        let cases = [
+            // Coprime pairs
+            (35u64, 64u64, 1u64),
+            (64, 35, 1),
+            (1, u64::MAX, 1),
+            // Non-coprime pairs
+            (12, 18, 6),
+            (18, 12, 6),
+            (u64::MAX, 3 * 5 * 17, 3 * 5 * 17),
+            (0, 7, 7),
+            (7, 0, 7),
+            (0, 0, 0),
+        ];
+
+        for (a, b, g) in cases {
+            test_var_pub_input(gcd_circuit, &[a, b, g].map(F::from), &[]);
+        }
+
+        // Should fail for common divisors other than the gcd, and for non-divisors
+        for (a, b, g) in [(12u64, 18u64, 3u64), (12, 18, 1), (12, 18, 12), (35, 64, 0)] {
+            let result = panic::catch_unwind(|| {
+                let mut cs = ConstraintSystem::new();
+                cs.set_constraints(&gcd_circuit);
+                cs.gen_witness(gcd_circuit, &[a, b, g].map(F::from), &[]);
+            });
+            assert!(result.is_err());
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use super::bitops::{form_le_bits, to_le_bits_n};
+use super::cmp::less_than;
+use super::sha256::hmac_sha256;
+use crate::frontend::constraint_system::{ConstraintSystem, Wire};
+use ark_ff::PrimeField;
+
+// The base64url encoding (RFC 4648, without padding) of a byte string,
+// with the input and output as bits that are little-endian within each byte.
+// The input bits must be binary.
+fn base64url_encode<F: PrimeField>(
+    cs: &mut ConstraintSystem<F>,
+    bytes: &[Wire<F>],
+) -> Vec<Wire<F>> {
+    assert_eq!(bytes.len() % 8, 0, "Input must be a whole number of bytes");
+
+    let zero = cs.zero();
+
+    // The input bits from the most significant bit of the first byte
+    let be_bits = bytes
+        .chunks(8)
+        .flat_map(|byte| byte.iter().rev().copied())
+        .collect::<Vec<Wire<F>>>();
+
+    let mut encoded = vec![];
+    for group in be_bits.chunks(6) {
+        // The last group is padded with zero bits
+        let mut le_bits = vec![zero; 6 - group.len()];
+        le_bits.extend(group.iter().rev());
+        let value = form_le_bits(&le_bits);
+
+        let mut is_at_least = |c: u32| {
+            let c = cs.alloc_const(F::from(c));
+            cs.one() - less_than(value, c, 6)
+        };
+        let at_least_26 = is_at_least(26);
+        let at_least_52 = is_at_least(52);
+        let at_least_62 = is_at_least(62);
+        let is_63 = is_at_least(63);
+
+        // 'A' to 'Z', 'a' to 'z', '0' to '9', '-' and '_',
+        // as offsets from the value that change at 26, 52, 62 and 63
+        let offset_26 = cs.mul_const(at_least_26, F::from(6u32));
+        let offset_52 = cs.mul_const(at_least_52, F::from(75u32));
+        let offset_62 = cs.mul_const(at_least_62, F::from(13u32));
+        let offset_63 = cs.mul_const(is_63, F::from(49u32));
+        let value = cs.add_const(value, F::from(b'A'));
+        let c = cs.sum(&[
+            (value, true),
+            (offset_26, true),
+            (offset_52, false),
+            (offset_62, false),
+            (offset_63, true),
+        ]);
+
+        encoded.extend(to_le_bits_n(c, 8, cs));
+    }
+
+    encoded
+}
+
+// Verify the HS256 signature of a JWT (RFC 7519), i.e. that `signature`
+// is the HMAC-SHA256 with `key` of the base64url-encoded header and payload joined by a dot.
+// The key, header, payload and signature are byte strings of bits that are little-endian
+// within each byte, like `hmac_sha256`, where the header and the payload are decoded.
+// All the bits must be binary.
+pub fn verify_jwt_hs256<F: PrimeField>(
+    cs: &mut ConstraintSystem<F>,
+    key: &[Wire<F>],
+    header: &[Wire<F>],
+    payload: &[Wire<F>],
+    signature: &[Wire<F>; 256],
+) {
+    let zero = cs.zero();
+    let one = cs.one();
+    let dot = (0..8).map(|i| if b'.' >> i & 1 == 1 { one } else { zero });
+
+    let mut signing_input = base64url_encode(cs, header);
+    signing_input.extend(dot);
+    signing_input.extend(base64url_encode(cs, payload));
+
+    let mac = hmac_sha256(cs, key, &signing_input);
+    for (bit, expected_bit) in mac.iter().zip(signature.iter()) {
+        cs.assert_equal(*bit, *expected_bit, "Invalid JWT signature");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{bytes_to_le_bits, test_var_pub_input};
+
+    type F = ark_secq256k1::Fr;
+
+    // The example token of jwt.io, signed with the key "your-256-bit-secret"
+    // and the signature recomputed with Python's hmac module
+    const KEY: &[u8] = b"your-256-bit-secret";
+    const HEADER: &[u8] = br#"{"alg":"HS256","typ":"JWT"}"#;
+    const PAYLOAD: &[u8] = br#"{"sub":"1234567890","name":"John Doe","iat":1516239022}"#;
+    const SIGNATURE: &str = "49f94ac7044948c78a285d904f87f0a4c7897f7e8f3a4eb2255fda750b2cc397";
+
+    fn jwt_circuit(cs: &mut ConstraintSystem<F>) {
+        let signature = cs.alloc_pub_inputs_arr();
+        let key = cs.alloc_priv_inputs(KEY.len() * 8);
+        let header = cs.alloc_priv_inputs(HEADER.len() * 8);
+        let payload = cs.alloc_priv_inputs(PAYLOAD.len() * 8);
+
+        verify_jwt_hs256(cs, &key, &header, &payload, &signature);
+    }
+
+    fn priv_input(payload: &[u8]) -> Vec<F> {
+        [
+            bytes_to_le_bits(KEY),
+            bytes_to_le_bits(HEADER),
+            bytes_to_le_bits(payload),
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn test_verify_jwt_hs256() {
+        let pub_input = bytes_to_le_bits(&hex::decode(SIGNATURE).unwrap());
+        test_var_pub_input(jwt_circuit, &pub_input, &priv_input(PAYLOAD));
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid JWT signature")]
+    fn test_verify_jwt_hs256_tampered_payload() {
+        let tampered_payload = String::from_utf8(PAYLOAD.to_vec())
+            .unwrap()
+            .replace("John Doe", "Jane Doe");
+
+        let pub_input = bytes_to_le_bits(&hex::decode(SIGNATURE).unwrap());
+
+        let mut cs = ConstraintSystem::new();
+        cs.gen_witness(
+            jwt_circuit,
+            &pub_input,
+            &priv_input(tampered_payload.as_bytes()),
+        );
+    }
+}
@@ -0,0 +1,205 @@
+use super::to_addr::keccak256;
+use crate::frontend::constraint_system::{ConstraintSystem, Wire};
+use ark_ff::PrimeField;
+
+// A node of a keccak Merkle tree: the 256 bits of a digest,
+// little-endian within each byte as in to_addr.
+pub type KeccakNode<F> = [Wire<F>; 256];
+
+// keccak256(left . right)
+fn hash_pair<F: PrimeField>(left: &KeccakNode<F>, right: &KeccakNode<F>) -> KeccakNode<F> {
+    keccak256(&[left.as_slice(), right.as_slice()].concat())
+}
+
+// Returns the (level, index) of the nodes that a batch update of the leaves at `indices`
+// must provide, in the order `verify_batch_update` consumes them.
+// These are the siblings of the updated paths that aren't on an updated path themselves.
+pub fn batch_update_siblings(depth: usize, indices: &[usize]) -> Vec<(usize, usize)> {
+    let mut nodes = indices.to_vec();
+    nodes.sort();
+    nodes.dedup();
+
+    let mut siblings = vec![];
+    for level in 0..depth {
+        for i in &nodes {
+            if nodes.binary_search(&(i ^ 1)).is_err() {
+                siblings.push((level, i ^ 1));
+            }
+        }
+
+        nodes = nodes.iter().map(|i| i >> 1).collect();
+        nodes.dedup();
+    }
+
+    siblings
+}
+
+// Verify that replacing the leaves at `indices` (sorted and distinct) from `old_leaves` to `new_leaves`
+// updates the root of the keccak Merkle tree of the given depth from `old_root` to `new_root`.
+// The paths of the updated leaves are recomputed together, so each node that is shared
+// by several paths is only hashed once per root.
+// `siblings` are the nodes listed by `batch_update_siblings`, which are the same in both trees.
+pub fn verify_batch_update<F: PrimeField>(
+    cs: &mut ConstraintSystem<F>,
+    depth: usize,
+    indices: &[usize],
+    old_leaves: &[KeccakNode<F>],
+    new_leaves: &[KeccakNode<F>],
+    siblings: &[KeccakNode<F>],
+    old_root: &KeccakNode<F>,
+    new_root: &KeccakNode<F>,
+) {
+    assert!(!indices.is_empty(), "At least one leaf must be updated");
+    assert_eq!(indices.len(), old_leaves.len());
+    assert_eq!(indices.len(), new_leaves.len());
+    assert!(
+        indices.windows(2).all(|w| w[0] < w[1]),
+        "The indices must be sorted and distinct"
+    );
+    assert!(
+        indices[indices.len() - 1] < 1 << depth,
+        "Index out of the tree"
+    );
+    assert_eq!(
+        siblings.len(),
+        batch_update_siblings(depth, indices).len(),
+        "Invalid number of siblings"
+    );
+
+    // The updated nodes of the current level: (index, old node, new node)
+    let mut nodes = indices
+        .iter()
+        .zip(old_leaves.iter().zip(new_leaves.iter()))
+        .map(|(i, (old, new))| (*i, *old, *new))
+        .collect::<Vec<(usize, KeccakNode<F>, KeccakNode<F>)>>();
+
+    let mut siblings = siblings.iter();
+
+    for _ in 0..depth {
+        let mut parents = Vec::with_capacity(nodes.len());
+
+        let mut k = 0;
+        while k < nodes.len() {
+            let (i, old, new) = &nodes[k];
+
+            let ((old_left, new_left), (old_right, new_right)) =
+                if i % 2 == 0 && k + 1 < nodes.len() && nodes[k + 1].0 == i + 1 {
+                    // Both children are updated
+                    let (_, old_right, new_right) = &nodes[k + 1];
+                    k += 2;
+                    ((old, new), (old_right, new_right))
+                } else {
+                    let sibling = siblings.next().unwrap();
+                    k += 1;
+                    if i % 2 == 0 {
+                        ((old, new), (sibling, sibling))
+                    } else {
+                        ((sibling, sibling), (old, new))
+                    }
+                };
+
+            parents.push((
+                i >> 1,
+                hash_pair(old_left, old_right),
+                hash_pair(new_left, new_right),
+            ));
+        }
+
+        nodes = parents;
+    }
+
+    let (_, computed_old_root, computed_new_root) = &nodes[0];
+    for (computed, root) in computed_old_root.iter().zip(old_root.iter()) {
+        cs.assert_equal(*computed, *root, "Invalid old root");
+    }
+    for (computed, root) in computed_new_root.iter().zip(new_root.iter()) {
+        cs.assert_equal(*computed, *root, "Invalid new root");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bytes_to_le_bits;
+
+    type F = ark_secq256k1::Fr;
+
+    const DEPTH: usize = 8;
+    const INDICES: [usize; 3] = [4, 5, 6];
+
+    fn alloc_nodes(cs: &mut ConstraintSystem<F>, n: usize) -> Vec<KeccakNode<F>> {
+        (0..n).map(|_| cs.alloc_priv_inputs_arr()).collect()
+    }
+
+    fn batch_update_circuit(cs: &mut ConstraintSystem<F>) {
+        let old_root: KeccakNode<F> = cs.alloc_pub_inputs_arr();
+        let new_root: KeccakNode<F> = cs.alloc_pub_inputs_arr();
+
+        let old_leaves = alloc_nodes(cs, INDICES.len());
+        let new_leaves = alloc_nodes(cs, INDICES.len());
+        let siblings = alloc_nodes(cs, batch_update_siblings(DEPTH, &INDICES).len());
+
+        verify_batch_update(
+            cs,
+            DEPTH,
+            &INDICES,
+            &old_leaves,
+            &new_leaves,
+            &siblings,
+            &old_root,
+            &new_root,
+        );
+    }
+
+    #[test]
+    fn test_batch_update_siblings() {
+        let expected = vec![(0, 7), (2, 0), (3, 1), (4, 1), (5, 1), (6, 1), (7, 1)];
+        assert_eq!(batch_update_siblings(DEPTH, &INDICES), expected);
+    }
+
+    #[test]
+    fn test_verify_batch_update() {
+        // Leaf i of the old tree is 32 bytes of i, and the leaves 4, 5 and 6
+        // are updated to 32 bytes of 0x80 + i.
+        let old_leaves = INDICES.map(|i| vec![i as u8; 32]);
+        let new_leaves = INDICES.map(|i| vec![0x80 + i as u8; 32]);
+
+        // The siblings and the roots computed off-circuit
+        let mut siblings = vec![vec![7u8; 32]];
+        for sibling in [
+            "57ad090642e288d3069bf5bcf54be1be00e27ee594cfd22c204c9fbece2bf006",
+            "cac0b3c6c5d94a3820ef3debc236edb7c9c69afc69f133ab817c6c7c95a798da",
+            "cde566a54583eeb4a3508220f47726c0aa2a34eae6f16a398087e458504e2a4b",
+            "a5a1bb77a25d41124f022fe3a08ed11d7da7f7ecd37c040315d5fa42cf536feb",
+            "020362de7a4a6bf9a5258cd182d0708e989dc940c112e53427025cbb53a51bac",
+            "59d392664b9d23065406872b94b3bce4c7771730494d2421618ac7a388012de0",
+        ] {
+            siblings.push(hex::decode(sibling).unwrap());
+        }
+
+        let old_root =
+            hex::decode("17bf1211dc02a9c62b11c1019f82c7c5cfafed124a680adaf2a3cb20fabc45be")
+                .unwrap();
+        let new_root =
+            hex::decode("7427d51cab44fb34730b77bb0f34a716a68a18a1b1281131b746b00daadbea31")
+                .unwrap();
+
+        let priv_input = old_leaves
+            .iter()
+            .chain(new_leaves.iter())
+            .chain(siblings.iter())
+            .flat_map(|node| bytes_to_le_bits(node))
+            .collect::<Vec<F>>();
+
+        let mut pub_input = [bytes_to_le_bits(&old_root), bytes_to_le_bits(&new_root)].concat();
+
+        let mut cs = ConstraintSystem::new();
+        cs.set_constraints(&batch_update_circuit);
+        let witness = cs.gen_witness(batch_update_circuit, &pub_input, &priv_input);
+        assert!(cs.is_sat(&witness, &pub_input));
+
+        // Should fail for a different new root
+        pub_input[256] = F::from(1u32) - pub_input[256];
+        assert!(!cs.is_sat(&witness, &pub_input));
+    }
+}
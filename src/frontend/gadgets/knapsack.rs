@@ -0,0 +1,78 @@
+use crate::frontend::constraint_system::{ConstraintSystem, Wire};
+use ark_ff::Field;
+
+// Assert that the selectors are binary and that sum_i selectors[i] * weights[i] == target,
+// i.e. the selected weights sum to the target.
+pub fn assert_weighted_sum<F: Field>(
+    cs: &mut ConstraintSystem<F>,
+    selectors: &[Wire<F>],
+    weights: &[F],
+    target: Wire<F>,
+) {
+    assert_eq!(
+        selectors.len(),
+        weights.len(),
+        "Each selector must have a weight"
+    );
+
+    let mut terms = Vec::with_capacity(selectors.len());
+    for (selector, weight) in selectors.iter().zip(weights.iter()) {
+        cs.assert_equal(*selector * *selector, *selector, "selector is not binary");
+        terms.push((cs.mul_const(*selector, *weight), true));
+    }
+
+    let weighted_sum = cs.sum(&terms);
+    cs.assert_equal(
+        weighted_sum,
+        target,
+        "Weighted sum doesn't match the target",
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_var_pub_input;
+    use std::panic;
+
+    type F = ark_secq256k1::Fr;
+
+    const WEIGHTS: [u32; 6] = [3, 34, 4, 12, 5, 2];
+
+    fn knapsack_circuit(cs: &mut ConstraintSystem<F>) {
+        let target = cs.alloc_pub_input();
+        let selectors = cs.alloc_priv_inputs(WEIGHTS.len());
+
+        assert_weighted_sum(cs, &selectors, &WEIGHTS.map(F::from), target);
+    }
+
+    #[test]
+    fn test_assert_weighted_sum() {
+        // 4 + 5 = 9
+        let target = F::from(9u32);
+        let selection = [0u32, 0, 1, 0, 1, 0].map(F::from);
+
+        test_var_pub_input(knapsack_circuit, &[target], &selection);
+
+        // Should fail for a selection that doesn't sum to the target
+        let result = panic::catch_unwind(|| {
+            let mut cs = ConstraintSystem::new();
+            cs.set_constraints(&knapsack_circuit);
+
+            let selection = [1u32, 0, 1, 0, 1, 0].map(F::from);
+            cs.gen_witness(knapsack_circuit, &[target], &selection);
+        });
+        assert!(result.is_err());
+
+        // Should fail for a non-binary selector, even if the sum matches
+        let result = panic::catch_unwind(|| {
+            let mut cs = ConstraintSystem::new();
+            cs.set_constraints(&knapsack_circuit);
+
+            // 3 * 3 = 9
+            let selection = [3u32, 0, 0, 0, 0, 0].map(F::from);
+            cs.gen_witness(knapsack_circuit, &[target], &selection);
+        });
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,121 @@
+use super::cmp::select;
+use crate::frontend::constraint_system::Wire;
+use ark_ff::PrimeField;
+
+// Verify that `leaf` is in the Merkle tree of `root`, given the siblings along the path (`path`)
+// and the position of the node at each level (`index_bits`, from the leaf up).
+// An index bit of 1 means the current node is the right child, and the bits are constrained to be binary.
+// `hash` hashes a (left, right) pair of children into their parent,
+// so any two-to-one hash gadget (e.g. Poseidon or a Keccak wrapper) can be plugged in.
+pub fn verify_merkle_path<F: PrimeField, H: Fn([Wire<F>; 2]) -> Wire<F>>(
+    leaf: Wire<F>,
+    path: &[Wire<F>],
+    index_bits: &[Wire<F>],
+    root: Wire<F>,
+    hash: H,
+) {
+    assert_eq!(
+        path.len(),
+        index_bits.len(),
+        "There must be an index bit for each sibling"
+    );
+
+    let cs = leaf.cs();
+
+    let mut node = leaf;
+    for (sibling, bit) in path.iter().zip(index_bits) {
+        // Swap the pair if the current node is the right child.
+        // The first select constrains the bit to be binary,
+        // so the second one is computed as in `select` without checking it again.
+        let left = select(*bit, *sibling, node);
+        let right = cs.mul_add(*bit, node - *sibling, *sibling);
+
+        node = hash([left, right]);
+    }
+
+    cs.assert_equal(node, root, "Merkle root mismatch");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frontend::constraint_system::ConstraintSystem;
+    use crate::frontend::gadgets::poseidon::poseidon::PoseidonChip;
+    use crate::frontend::gadgets::tree::{hash, SPONGE_WIDTH};
+    use crate::test_var_pub_input;
+    use merkle_tree::MerkleTree;
+    use poseidon::constants::secp256k1_w3;
+    use std::panic;
+
+    type F = ark_secp256k1::Fq;
+    const TREE_DEPTH: usize = 4;
+
+    fn merkle_path_circuit(cs: &mut ConstraintSystem<F>) {
+        let leaf = cs.alloc_priv_input();
+        let path = cs.alloc_priv_inputs(TREE_DEPTH);
+        let index_bits = cs.alloc_priv_inputs(TREE_DEPTH);
+        let root = cs.alloc_pub_input();
+
+        let poseidon_chip = PoseidonChip::<F, SPONGE_WIDTH>::new(cs, secp256k1_w3());
+        let hash_pair = |[left, right]: [Wire<F>; 2]| hash(left, right, poseidon_chip.clone());
+
+        verify_merkle_path(leaf, &path, &index_bits, root, hash_pair);
+    }
+
+    // Build a depth-4 tree off-circuit, and return the root
+    // and the private input of the circuit to prove the membership of `leaves[index]`.
+    fn tree_and_proof(index: usize) -> (F, Vec<F>) {
+        let leaves = (0..(1 << TREE_DEPTH))
+            .map(|i| F::from(i as u32 * 7 + 1))
+            .collect::<Vec<F>>();
+
+        let mut tree = MerkleTree::<F, SPONGE_WIDTH>::new(secp256k1_w3());
+        for leaf in &leaves {
+            tree.insert(*leaf);
+        }
+        tree.finish();
+
+        let proof = tree.create_proof(leaves[index]);
+
+        let mut priv_input = vec![leaves[index]];
+        priv_input.extend_from_slice(&proof.siblings);
+        priv_input.extend(proof.path_indices.iter().map(|x| F::from(*x as u64)));
+
+        (tree.root.unwrap(), priv_input)
+    }
+
+    #[test]
+    fn test_verify_merkle_path() {
+        for index in [0, 5, 10, 15] {
+            let (root, priv_input) = tree_and_proof(index);
+            test_var_pub_input(merkle_path_circuit, &[root], &priv_input);
+        }
+    }
+
+    #[test]
+    fn test_verify_merkle_path_wrong_sibling() {
+        let (root, mut priv_input) = tree_and_proof(5);
+
+        // The first sibling follows the leaf
+        priv_input[1] += F::from(1u32);
+
+        let result = panic::catch_unwind(|| {
+            let mut cs = ConstraintSystem::new();
+            cs.set_constraints(&merkle_path_circuit);
+            cs.gen_witness(merkle_path_circuit, &[root], &priv_input);
+        });
+        assert!(result.is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "select condition is not binary")]
+    fn test_verify_merkle_path_non_binary_index() {
+        let (root, mut priv_input) = tree_and_proof(5);
+
+        // The first index bit follows the leaf and the siblings
+        priv_input[1 + TREE_DEPTH] = F::from(2u32);
+
+        let mut cs = ConstraintSystem::new();
+        cs.gen_witness(merkle_path_circuit, &[root], &priv_input);
+    }
+}
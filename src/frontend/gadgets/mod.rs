@@ -1,13 +1,85 @@
 #![allow(non_snake_case)]
+mod auction;
+mod base58;
+mod bip32;
 mod bitops;
+mod bulletproofs;
+mod canonical;
+mod cmp;
+mod counter;
+mod create_address;
 mod ecc;
+mod ecdsa;
+mod gcd;
+mod jwt;
+mod keccak_tree;
+mod knapsack;
+mod merkle;
+mod mpt;
+mod nonnative;
+mod ope;
+mod pack;
+mod password;
+mod pedersen;
+mod permutation;
 pub mod poseidon;
+mod prf;
+mod reed_solomon;
+mod rollup;
+mod salt;
+mod schnorr;
+mod sha256;
+mod sha512;
+mod shamir;
+mod shuffle;
+mod sqrt;
+mod timestamp;
 mod to_addr;
 mod tree;
+mod utf8;
+mod vdf;
 
-pub use bitops::{form_le_bits, to_le_bits};
+pub use self::poseidon::poseidon_hash;
+pub use auction::verify_sealed_bid;
+pub use base58::verify_base58check;
+pub use bip32::verify_ckd;
+pub use bitops::{
+    add_mod_32, and, and_64, form_le_bits, not_a_and_b, or, or_64, rotate_left, rotate_left_var_64,
+    rotate_right, rotate_right_64, shift_right, shift_right_64, to_le_bits, xor,
+};
+pub use bulletproofs::range_proof_bits_bp;
+pub use canonical::assert_canonical_field_bytes;
+pub use cmp::{is_equal, is_zero, less_than, less_than_or_equal, range_check, select};
+pub use counter::{assert_increment, assert_sequential};
+pub use create_address::create_address;
 pub use ecc::twisted_edwards;
 pub use ecc::weierstrass;
 pub use ecc::AffinePoint;
-pub use to_addr::to_addr;
+pub use ecdsa::verify_ecdsa;
+pub use gcd::verify_gcd;
+pub use jwt::verify_jwt_hs256;
+pub use keccak_tree::{batch_update_siblings, verify_batch_update, KeccakNode};
+pub use knapsack::assert_weighted_sum;
+pub use merkle::verify_merkle_path;
+pub use mpt::{verify_mpt_proof, MptNode, MAX_NODE_LEN};
+pub use nonnative::{mul_mod, FpElement, LIMB_BITS};
+pub use ope::assert_ope_order;
+pub use pack::{from_bytes_be, from_bytes_le, pack, to_bytes, unpack};
+pub use password::verify_password_hash;
+pub use pedersen::{assert_bit_field_commitment_consistent, verify_pedersen_opening};
+pub use permutation::assert_permutation;
+pub use prf::keccak_prf;
+pub use reed_solomon::assert_rs_codeword;
+pub use rollup::{verify_rollup_batch, AccountProof, RollupTransfer};
+pub use salt::derive_salt;
+pub use schnorr::{verify_poseidon_schnorr, verify_threshold_schnorr, SchnorrSignature};
+pub use sha256::{hmac_sha256, sha256};
+pub use sha512::{hmac_sha512, sha512};
+pub use shamir::verify_shamir_reconstruct;
+pub use shuffle::verify_shuffle;
+pub use sqrt::sqrt;
+pub use timestamp::assert_timestamp_in_range;
+pub use to_addr::{keccak256, keccak256_var, to_addr};
 pub use tree::verify_merkle_proof;
+pub use utf8::assert_valid_utf8;
+pub use vdf::verify_vdf_segment;
@@ -0,0 +1,336 @@
+use super::bitops::{byte_to_le_bits, form_le_bits, range_check};
+use super::to_addr::keccak256_prefix;
+use crate::frontend::constraint_system::{ConstraintSystem, Wire};
+use ark_ff::{Field, PrimeField};
+
+// The length of the largest node: a branch node with 16 children, i.e.
+// a 3-byte list header, 16 hashes with their 1-byte headers, and an empty value
+pub const MAX_NODE_LEN: usize = 3 + 16 * 33 + 1;
+
+// RLP prefixes of the empty string, of a 32-byte string,
+// of a string with a 1-byte length, and of lists with a 1 and 2-byte length
+const EMPTY_STRING: u8 = 0x80;
+const HASH_STRING: u8 = 0xa0;
+const LONG_STRING_1: u8 = 0xb8;
+const LONG_LIST_1: u8 = 0xf8;
+const LONG_LIST_2: u8 = 0xf9;
+
+// A node of a Merkle-Patricia proof: its RLP encoding as bytes padded with zeros
+// to MAX_NODE_LEN bytes, and its length in bytes
+#[derive(Clone)]
+pub struct MptNode<F: Field> {
+    pub bytes: Vec<Wire<F>>,
+    pub len: Wire<F>,
+}
+
+// The `count` bytes of `bytes` from `index`, which is only known at proving time.
+// The bytes past the end of `bytes` are zero.
+fn bytes_at<F: PrimeField>(
+    cs: &mut ConstraintSystem<F>,
+    bytes: &[Wire<F>],
+    index: Wire<F>,
+    count: usize,
+) -> Vec<Wire<F>> {
+    let is_index = (0..bytes.len())
+        .map(|i| {
+            let i = cs.alloc_const(F::from(i as u64));
+            cs.is_equal(index, i)
+        })
+        .collect::<Vec<Wire<F>>>();
+
+    (0..count)
+        .map(|k| {
+            let terms = is_index
+                .iter()
+                .zip(bytes.iter().skip(k))
+                .map(|(is_index, byte)| (*is_index * *byte, true))
+                .collect::<Vec<(Wire<F>, bool)>>();
+            cs.sum(&terms)
+        })
+        .collect()
+}
+
+// Check that `node` is a branch node whose children are hashes or empty, with an empty value,
+// and return the hash of its child at `nibble`, as 32 bytes.
+fn branch_child<F: PrimeField>(
+    cs: &mut ConstraintSystem<F>,
+    node: &MptNode<F>,
+    nibble: Wire<F>,
+) -> Vec<Wire<F>> {
+    let zero = cs.zero();
+    let one = cs.one();
+    let is_byte = |cs: &mut ConstraintSystem<F>, byte: Wire<F>, c: u8| {
+        let c = cs.alloc_const(F::from(c));
+        cs.is_equal(byte, c)
+    };
+
+    // A branch node has at least two children, so its payload is at least 56 bytes
+    // and the length of the payload follows the list prefix in 1 or 2 bytes.
+    let is_long_1 = is_byte(cs, node.bytes[0], LONG_LIST_1);
+    let is_long_2 = is_byte(cs, node.bytes[0], LONG_LIST_2);
+    cs.assert_equal(is_long_1 + is_long_2, one, "Invalid branch node");
+
+    let len_1 = node.bytes[1];
+    let len_2 = cs.mul_const(node.bytes[1], F::from(256u32)) + node.bytes[2];
+    let payload_len = cs.mul_add(is_long_2, len_2 - len_1, len_1);
+    let header_len = cs.add_const(is_long_2, F::from(2u32));
+    cs.assert_equal(
+        header_len + payload_len,
+        node.len,
+        "Invalid branch node length",
+    );
+
+    // Walk the 16 children and the value, which are 1 byte if empty and 33 bytes if a hash
+    let mut offset = header_len;
+    let mut child_offset = zero;
+    let mut is_child_hash = zero;
+    for i in 0..16 {
+        let prefix = bytes_at(cs, &node.bytes, offset, 1)[0];
+        let is_empty = is_byte(cs, prefix, EMPTY_STRING);
+        let is_hash = is_byte(cs, prefix, HASH_STRING);
+        cs.assert_equal(is_empty + is_hash, one, "Invalid branch node child");
+
+        let is_nibble = is_byte(cs, nibble, i);
+        child_offset = cs.mul_add(is_nibble, offset, child_offset);
+        is_child_hash = cs.mul_add(is_nibble, is_hash, is_child_hash);
+
+        let item_len = cs.mul_const(is_hash, F::from(32u32));
+        offset = offset + item_len + one;
+    }
+
+    let value_prefix = bytes_at(cs, &node.bytes, offset, 1)[0];
+    let is_empty = is_byte(cs, value_prefix, EMPTY_STRING);
+    cs.assert_equal(is_empty, one, "A branch node of the path has a value");
+    cs.assert_equal(offset + one, node.len, "Invalid branch node length");
+
+    cs.assert_equal(is_child_hash, one, "The key isn't in the trie");
+
+    // The hash follows the prefix of the child
+    bytes_at(cs, &node.bytes, child_offset + one, 32)
+}
+
+// Check that `node` is a leaf node with the path `nibbles` and the value `value`,
+// which first `value_len` bytes are the value and the rest are zero.
+// The value is at least 56 bytes, and the payload of the node less than 256 bytes,
+// so both have a 1-byte length after their prefix.
+fn check_leaf<F: PrimeField>(
+    cs: &mut ConstraintSystem<F>,
+    node: &MptNode<F>,
+    nibbles: &[Wire<F>],
+    value: &[Wire<F>],
+    value_len: Wire<F>,
+) {
+    let byte = |cs: &mut ConstraintSystem<F>, c: u8| cs.alloc_const(F::from(c));
+
+    // The hex-prefix encoding of the path, with the leaf flag
+    let mut path = if nibbles.len() % 2 == 1 {
+        vec![cs.add_const(nibbles[0], F::from(0x30u32))]
+    } else {
+        vec![byte(cs, 0x20)]
+    };
+    for pair in nibbles[(nibbles.len() % 2)..].chunks(2) {
+        path.push(cs.mul_const(pair[0], F::from(16u32)) + pair[1]);
+    }
+
+    // A single path byte is less than 0x80, so it is its own encoding
+    if path.len() > 1 {
+        path.insert(0, byte(cs, EMPTY_STRING + path.len() as u8));
+    }
+
+    let value_start = 2 + path.len() + 2;
+    assert!(
+        value_start + value.len() <= MAX_NODE_LEN,
+        "The value doesn't fit in a node"
+    );
+
+    let mut expected = vec![byte(cs, LONG_LIST_1), node.len - byte(cs, 2)];
+    expected.extend(path);
+    expected.extend([byte(cs, LONG_STRING_1), value_len]);
+    expected.extend(value);
+
+    for (node_byte, expected_byte) in node.bytes.iter().zip(expected.iter()) {
+        cs.assert_equal(
+            *node_byte,
+            *expected_byte,
+            "The leaf doesn't hold the value",
+        );
+    }
+
+    // The value length byte is less than 256, and the value is at least 56 bytes,
+    // and at most the number of value bytes, so none of the node is left unchecked.
+    range_check(value_len - byte(cs, 56), 8, cs);
+    let max_value_len = cs.alloc_const(F::from(value.len() as u64));
+    range_check(max_value_len - value_len, 8, cs);
+
+    let leaf_len = cs.add_const(value_len, F::from(value_start as u64));
+    cs.assert_equal(leaf_len, node.len, "Invalid leaf node length");
+}
+
+// Verify a Merkle-Patricia inclusion proof of `value` at `key` in the trie of `root`,
+// such as the account proof of eth_getProof.
+// `nodes` are the nodes from the root to the leaf: one branch node per nibble of the key
+// followed by the leaf, where the children of the branch nodes are hashes.
+// Extension nodes, and nodes short enough to be embedded in their parent, aren't supported.
+// `key`, `value` and `root` are bytes, and `value` is padded with zeros,
+// with `value_len` bytes from 56 to 255 as for the RLP of an account.
+// The bytes are constrained to be bytes.
+pub fn verify_mpt_proof<F: PrimeField>(
+    cs: &mut ConstraintSystem<F>,
+    key: &[Wire<F>; 32],
+    value: &[Wire<F>],
+    value_len: Wire<F>,
+    nodes: &[MptNode<F>],
+    root: &[Wire<F>; 32],
+) {
+    assert!(
+        !nodes.is_empty() && nodes.len() <= 64,
+        "There must be a node per nibble of the path"
+    );
+    assert!(value.len() < 256, "The value must be less than 256 bytes");
+
+    // The nibbles of the key, from the high nibble of the first byte
+    let mut nibbles = Vec::with_capacity(64);
+    for byte in key {
+        let bits = byte_to_le_bits(*byte, cs);
+        nibbles.push(form_le_bits(&bits[4..]));
+        nibbles.push(form_le_bits(&bits[..4]));
+    }
+
+    let mut expected_hash = root.to_vec();
+    for (depth, node) in nodes.iter().enumerate() {
+        assert_eq!(node.bytes.len(), MAX_NODE_LEN, "The node must be padded");
+
+        let bits = node
+            .bytes
+            .iter()
+            .flat_map(|byte| byte_to_le_bits(*byte, cs))
+            .collect::<Vec<Wire<F>>>();
+
+        let hash = keccak256_prefix(cs, &bits, node.len);
+        for (hash_byte, expected_byte) in hash.chunks(8).zip(expected_hash.iter()) {
+            cs.assert_equal(
+                form_le_bits(hash_byte),
+                *expected_byte,
+                "The node doesn't match its hash",
+            );
+        }
+
+        if depth + 1 < nodes.len() {
+            expected_hash = branch_child(cs, node, nibbles[depth]);
+        } else {
+            check_leaf(cs, node, &nibbles[depth..], value, value_len);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_var_pub_input;
+
+    type F = ark_secq256k1::Fr;
+
+    // An account proof in a trie of 3 accounts, computed off-circuit:
+    // two branch nodes and the leaf of the account
+    const ROOT: &str = "6bdaa6e111248a3f00c334f58774370b4afa9165e57d08fad7c7c89616ebfdb7";
+    // The keccak256 of the address
+    const KEY: &str = "d52688a8f926c816ca1e079067caba944f158e764817b83fc43594370ca9cf62";
+    // The RLP of the nonce, the balance, the storage root and the code hash
+    const VALUE: &str = concat!(
+        "f84c02881bc16d674ec80000a056e81f171bcc55a6ff8345e692c0f86e5b48e0",
+        "1b996cadc001622fb5e363b421a0c5d2460186f7233c927e7db2dcc703c0e500",
+        "b653ca82273b7bfad8045d85a470",
+    );
+
+    const NODE_0: &str = concat!(
+        "f90191a09ae5548189d01a563ad04b78122f4f230015c2450b93a6e4aa75a9d3",
+        "fd29d74ea07759dbc485a2c62b9ffab3181389238c306d2748d9bcead3c32d89",
+        "28f48201528080a047817a9f1eb91bb9615468ef158f88c6c5eacc9f4cd6bf82",
+        "7cf0a0333331bbf2a09fa3097ed0d05cc1366f4279edb56e0ada8caf398e7c2f",
+        "23068d047cdfe6226ca08f19658cf6560e5e9e2590e49dec24c7bc7df0dd3352",
+        "e730702d5ada8c4c2c9180a0434a38e01a19c00f209b7256ec510645ca376727",
+        "8f656e26bf67c23237ed6fffa052d7b079f907527ba62645b17f91df7afa6d74",
+        "e1b54ac7021a13194d9b41e81da06ef7407b32c9d033ea3dddf4cc148de3d0b7",
+        "2899f7dba5fe37727641c5858f2480a0a4d506e3d00408d3a6ff81acaa244d10",
+        "3a20a7abc2e414e202274cde73946a66a025f20def36db44264bffa5f12e2a53",
+        "13010dacab1f43e69470f7ebdb6999dd35a036092d7f4309c2478ad76fca0d8a",
+        "2a965f1ae7f966baaf651a612652bf08e710a07efcd87935729e9968a29c96ae",
+        "25d8779e8b0c39a021a1ca805f1d8d6eddbd1780",
+    );
+
+    const NODE_1: &str = concat!(
+        "f8718080808080a00cc43046db8f9054ab0ea616d973c2bb6e7e1166785d2934",
+        "76995509620f4f1d808080a0a7272d51b4a7c6ac6644b9dbc8c4bfe586bbc9c4",
+        "338b3525df650d4122d54ec880808080a03b2ceecdbd5d49a7880c15f6abe937",
+        "9bf4f473a1194a788d3e183852d633bb578080",
+    );
+
+    const NODE_2: &str = concat!(
+        "f871a0202688a8f926c816ca1e079067caba944f158e764817b83fc43594370c",
+        "a9cf62b84ef84c02881bc16d674ec80000a056e81f171bcc55a6ff8345e692c0",
+        "f86e5b48e01b996cadc001622fb5e363b421a0c5d2460186f7233c927e7db2dc",
+        "c703c0e500b653ca82273b7bfad8045d85a470",
+    );
+
+    // The value is padded with zeros up to MAX_VALUE_LEN bytes
+    const MAX_VALUE_LEN: usize = 128;
+    const NUM_NODES: usize = 3;
+
+    fn mpt_circuit(cs: &mut ConstraintSystem<F>) {
+        let root = cs.alloc_pub_inputs_arr();
+        let key = cs.alloc_priv_inputs_arr();
+        let value = cs.alloc_priv_inputs(MAX_VALUE_LEN);
+        let value_len = cs.alloc_priv_input();
+        let nodes = (0..NUM_NODES)
+            .map(|_| MptNode {
+                bytes: cs.alloc_priv_inputs(MAX_NODE_LEN),
+                len: cs.alloc_priv_input(),
+            })
+            .collect::<Vec<MptNode<F>>>();
+
+        verify_mpt_proof(cs, &key, &value, value_len, &nodes, &root);
+    }
+
+    fn to_felts(bytes: &[u8], len: usize) -> Vec<F> {
+        let mut felts = bytes.iter().map(|b| F::from(*b)).collect::<Vec<F>>();
+        felts.resize(len, F::from(0u32));
+        felts
+    }
+
+    fn priv_input(value: &[u8]) -> Vec<F> {
+        let mut priv_input = to_felts(&hex::decode(KEY).unwrap(), 32);
+        priv_input.extend(to_felts(value, MAX_VALUE_LEN));
+        priv_input.push(F::from(value.len() as u64));
+
+        for node in [NODE_0, NODE_1, NODE_2] {
+            let node = hex::decode(node).unwrap();
+            priv_input.extend(to_felts(&node, MAX_NODE_LEN));
+            priv_input.push(F::from(node.len() as u64));
+        }
+
+        priv_input
+    }
+
+    #[test]
+    fn test_verify_mpt_proof() {
+        let pub_input = to_felts(&hex::decode(ROOT).unwrap(), 32);
+        test_var_pub_input(
+            mpt_circuit,
+            &pub_input,
+            &priv_input(&hex::decode(VALUE).unwrap()),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "The leaf doesn't hold the value")]
+    fn test_verify_mpt_proof_wrong_value() {
+        // Change the last byte of the balance
+        let mut value = hex::decode(VALUE).unwrap();
+        value[11] += 1;
+
+        let pub_input = to_felts(&hex::decode(ROOT).unwrap(), 32);
+
+        let mut cs = ConstraintSystem::new();
+        cs.gen_witness(mpt_circuit, &pub_input, &priv_input(&value));
+    }
+}
@@ -0,0 +1,362 @@
+use super::bitops::range_check;
+use crate::frontend::constraint_system::{ConstraintSystem, Wire};
+use ark_ff::{Field, PrimeField};
+use num_bigint::BigUint;
+
+// The number of bits of each limb of a foreign field element
+pub const LIMB_BITS: usize = 64;
+
+// An integer represented as little-endian limbs of LIMB_BITS bits,
+// e.g. an element of a field that doesn't fit in the native field.
+#[derive(Clone)]
+pub struct FpElement<F: Field> {
+    pub limbs: Vec<Wire<F>>,
+}
+
+impl<F: PrimeField> FpElement<F> {
+    // The limbs must be known to be less than 2^LIMB_BITS (e.g. with `range_check`)
+    pub fn new(limbs: Vec<Wire<F>>) -> Self {
+        Self { limbs }
+    }
+
+    // Allocate `num_limbs` limbs of `val`, each constrained to be less than 2^LIMB_BITS
+    pub fn alloc(cs: &mut ConstraintSystem<F>, val: Option<&BigUint>, num_limbs: usize) -> Self {
+        let limb_vals = val.map(|val| to_limbs::<F>(val, num_limbs));
+
+        let limbs = (0..num_limbs)
+            .map(|i| cs.alloc_var(limb_vals.as_ref().map_or(F::ZERO, |limbs| limbs[i])))
+            .collect::<Vec<Wire<F>>>();
+
+        let element = Self::new(limbs);
+        element.range_check(cs);
+
+        element
+    }
+
+    // Constrain each limb to be less than 2^LIMB_BITS
+    pub fn range_check(&self, cs: &mut ConstraintSystem<F>) {
+        for limb in &self.limbs {
+            range_check(*limb, LIMB_BITS, cs);
+        }
+    }
+
+    // The integer represented by the limbs, at witness generation
+    pub fn value(&self, cs: &mut ConstraintSystem<F>) -> Option<BigUint> {
+        let mut val = BigUint::from(0u32);
+        for limb in self.limbs.iter().rev() {
+            let limb_val: BigUint = limb.val(cs)?.into();
+            val = (val << LIMB_BITS) + limb_val;
+        }
+
+        Some(val)
+    }
+}
+
+// Split `x` into `num_limbs` little-endian limbs of LIMB_BITS bits
+fn to_limbs<F: PrimeField>(x: &BigUint, num_limbs: usize) -> Vec<F> {
+    let mask = (BigUint::from(1u32) << LIMB_BITS) - 1u32;
+
+    let limbs = (0..num_limbs)
+        .map(|i| F::from((x >> (LIMB_BITS * i)) & &mask))
+        .collect::<Vec<F>>();
+    assert!(
+        x >> (LIMB_BITS * num_limbs) == BigUint::from(0u32),
+        "The value doesn't fit in {} limbs",
+        num_limbs
+    );
+
+    limbs
+}
+
+// Assert that sum_i columns[i] * 2^(LIMB_BITS * i) is zero over the integers,
+// where each column is a signed integer.
+// The carry out of each column is (column + carry in) / 2^LIMB_BITS,
+// which is a linear combination rather than a hint.
+// Range-checking the carries to `carry_bits` signed bits then enforces
+// that each division is exact, as an inexact one is a large field element.
+// The columns must be small enough that no column equation wraps around the native modulus.
+fn assert_columns_zero<F: PrimeField>(
+    cs: &mut ConstraintSystem<F>,
+    columns: &[Wire<F>],
+    carry_bits: usize,
+) {
+    assert!(
+        LIMB_BITS + carry_bits + 1 < F::MODULUS_BIT_SIZE as usize,
+        "The carries must not wrap around the native modulus"
+    );
+
+    let shift_inv = F::from(2u32).pow([LIMB_BITS as u64]).inverse().unwrap();
+    let offset = F::from(2u32).pow([(carry_bits - 1) as u64]);
+
+    let mut carry = cs.zero();
+    for column in columns {
+        carry = cs.mul_const(*column + carry, shift_inv);
+        range_check(cs.add_const(carry, offset), carry_bits, cs);
+    }
+
+    let zero = cs.zero();
+    cs.assert_equal(carry, zero, "The limbs don't sum to zero");
+}
+
+// Assert that r < modulus, by decomposing modulus - 1 - r into limbs
+fn assert_less_than_modulus<F: PrimeField>(
+    cs: &mut ConstraintSystem<F>,
+    r: &FpElement<F>,
+    modulus: &BigUint,
+) {
+    let num_limbs = r.limbs.len();
+    let max = modulus - 1u32;
+
+    let diff_val = r.value(cs).map(|r| {
+        // Any value that fits in the limbs if r is out of range, which fails the constraints
+        if r <= max {
+            &max - r
+        } else {
+            BigUint::from(0u32)
+        }
+    });
+    let diff = FpElement::alloc(cs, diff_val.as_ref(), num_limbs);
+
+    // (modulus - 1) - r - diff, limb by limb.
+    // Each column is in (-2^(LIMB_BITS + 1), 2^LIMB_BITS), so the carries are in [-2, 0].
+    let max_limbs = to_limbs::<F>(&max, num_limbs);
+    let columns = (0..num_limbs)
+        .map(|i| {
+            let neg_sum = cs.sum(&[(r.limbs[i], true), (diff.limbs[i], true)]);
+            let max_limb = cs.alloc_const(max_limbs[i]);
+            max_limb - neg_sum
+        })
+        .collect::<Vec<Wire<F>>>();
+
+    assert_columns_zero(cs, &columns, 3);
+}
+
+// Assert that a * b = q * modulus + r over the integers, with 0 <= r < modulus.
+// a, b and r have as many limbs as the modulus, and q has one more.
+// All the limbs must be range-checked by the caller.
+fn assert_mul_mod<F: PrimeField>(
+    cs: &mut ConstraintSystem<F>,
+    a: &FpElement<F>,
+    b: &FpElement<F>,
+    q: &FpElement<F>,
+    r: &FpElement<F>,
+    modulus: &BigUint,
+) {
+    let num_limbs = a.limbs.len();
+    assert_eq!(b.limbs.len(), num_limbs);
+    assert_eq!(q.limbs.len(), num_limbs + 1);
+    assert_eq!(r.limbs.len(), num_limbs);
+
+    assert_less_than_modulus(cs, r, modulus);
+
+    let modulus_limbs = to_limbs::<F>(modulus, num_limbs);
+
+    // Column i of a * b - q * modulus - r
+    let num_columns = 2 * num_limbs;
+    let mut terms = vec![vec![]; num_columns];
+    for i in 0..num_limbs {
+        for j in 0..num_limbs {
+            terms[i + j].push((a.limbs[i] * b.limbs[j], true));
+        }
+    }
+    for i in 0..(num_limbs + 1) {
+        for j in 0..num_limbs {
+            terms[i + j].push((cs.mul_const(q.limbs[i], modulus_limbs[j]), false));
+        }
+    }
+    for i in 0..num_limbs {
+        terms[i].push((r.limbs[i], false));
+    }
+
+    let columns = terms
+        .iter()
+        .map(|column_terms| cs.sum(column_terms))
+        .collect::<Vec<Wire<F>>>();
+
+    // Each column is the sum of at most 2 * num_limbs + 2 terms less than 2^(2 * LIMB_BITS),
+    // so the carries are less than (2 * num_limbs + 2) * 2^LIMB_BITS in magnitude.
+    let carry_bits =
+        LIMB_BITS + (2 * num_limbs + 2).next_power_of_two().trailing_zeros() as usize + 2;
+    assert_columns_zero(cs, &columns, carry_bits);
+}
+
+// a * b mod modulus, for a foreign modulus of any size.
+// a and b must have as many limbs as the modulus, each less than 2^LIMB_BITS
+// (e.g. allocated with `FpElement::alloc`), but don't need to be reduced.
+// The quotient and the remainder are hints, constrained by a * b = q * modulus + r
+// limb by limb with carries, and the remainder is constrained to be less than the modulus.
+pub fn mul_mod<F: PrimeField>(
+    a: &FpElement<F>,
+    b: &FpElement<F>,
+    modulus: &BigUint,
+) -> FpElement<F> {
+    assert!(
+        *modulus > BigUint::from(1u32),
+        "The modulus must be greater than one"
+    );
+
+    let num_limbs = ((modulus.bits() as usize) + LIMB_BITS - 1) / LIMB_BITS;
+    assert_eq!(
+        a.limbs.len(),
+        num_limbs,
+        "The operands must have as many limbs as the modulus"
+    );
+
+    let cs = a.limbs[0].cs();
+
+    let product = a.value(cs).and_then(|a| b.value(cs).map(|b| a * b));
+    let q_val = product.as_ref().map(|product| product / modulus);
+    let r_val = product.as_ref().map(|product| product % modulus);
+
+    let q = FpElement::alloc(cs, q_val.as_ref(), num_limbs + 1);
+    let r = FpElement::alloc(cs, r_val.as_ref(), num_limbs);
+
+    assert_mul_mod(cs, a, b, &q, &r, modulus);
+
+    r
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_var_pub_input;
+    use std::panic;
+
+    type F = ark_secq256k1::Fr;
+
+    // The base field modulus of BLS12-381, which doesn't fit in F
+    const MODULUS: &str = "1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaaab";
+    const NUM_LIMBS: usize = 6;
+
+    fn modulus() -> BigUint {
+        BigUint::parse_bytes(MODULUS.as_bytes(), 16).unwrap()
+    }
+
+    fn alloc_element(cs: &mut ConstraintSystem<F>, num_limbs: usize) -> FpElement<F> {
+        let element = FpElement::new(cs.alloc_priv_inputs(num_limbs));
+        element.range_check(cs);
+        element
+    }
+
+    fn mul_mod_circuit(cs: &mut ConstraintSystem<F>) {
+        let a = alloc_element(cs, NUM_LIMBS);
+        let b = alloc_element(cs, NUM_LIMBS);
+
+        let r = mul_mod(&a, &b, &modulus());
+        for limb in r.limbs {
+            cs.expose_public(limb);
+        }
+    }
+
+    // a * b = q * modulus + r with the quotient and the remainder as inputs
+    fn assert_mul_mod_circuit(cs: &mut ConstraintSystem<F>) {
+        let a = alloc_element(cs, NUM_LIMBS);
+        let b = alloc_element(cs, NUM_LIMBS);
+        let q = alloc_element(cs, NUM_LIMBS + 1);
+        let r = alloc_element(cs, NUM_LIMBS);
+
+        assert_mul_mod(cs, &a, &b, &q, &r, &modulus());
+    }
+
+    fn test_operands() -> (BigUint, BigUint) {
+        let p = modulus();
+        let a = &p - 12345u32;
+        let b = (&p >> 3) + 987654321u32;
+        (a, b)
+    }
+
+    fn should_fail(priv_input: Vec<F>) {
+        let result = panic::catch_unwind(|| {
+            let mut cs = ConstraintSystem::new();
+            cs.set_constraints(&assert_mul_mod_circuit);
+            cs.gen_witness(assert_mul_mod_circuit, &[], &priv_input);
+        });
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_mul_mod() {
+        let p = modulus();
+        let (a, b) = test_operands();
+
+        let cases = [
+            (a.clone(), b.clone()),
+            (BigUint::from(0u32), b.clone()),
+            (BigUint::from(1u32), a.clone()),
+            // Unreduced operands
+            (
+                (BigUint::from(1u32) << (LIMB_BITS * NUM_LIMBS)) - 1u32,
+                a.clone(),
+            ),
+        ];
+
+        for (a, b) in cases {
+            let r = (&a * &b) % &p;
+
+            let pub_input = to_limbs::<F>(&r, NUM_LIMBS);
+            let priv_input = [to_limbs(&a, NUM_LIMBS), to_limbs(&b, NUM_LIMBS)].concat();
+            test_var_pub_input(mul_mod_circuit, &pub_input, &priv_input);
+        }
+    }
+
+    #[test]
+    fn test_mul_mod_malicious_witness() {
+        let p = modulus();
+        let (a, b) = test_operands();
+        let product = &a * &b;
+        let q = &product / &p;
+        let r = &product % &p;
+
+        let input = |q_limbs: Vec<F>, r_limbs: Vec<F>| {
+            [
+                to_limbs(&a, NUM_LIMBS),
+                to_limbs(&b, NUM_LIMBS),
+                q_limbs,
+                r_limbs,
+            ]
+            .concat()
+        };
+
+        let q_limbs = to_limbs::<F>(&q, NUM_LIMBS + 1);
+        let r_limbs = to_limbs::<F>(&r, NUM_LIMBS);
+        test_var_pub_input(
+            assert_mul_mod_circuit,
+            &[],
+            &input(q_limbs.clone(), r_limbs.clone()),
+        );
+
+        // The same remainder, with a limb overflowing into the next one
+        let shift = F::from(2u32).pow([LIMB_BITS as u64]);
+        let mut overflowed_r = r_limbs.clone();
+        overflowed_r[0] += shift;
+        overflowed_r[1] -= F::ONE;
+        should_fail(input(q_limbs.clone(), overflowed_r));
+
+        // The same quotient, with a negative limb borrowing from the next one
+        let mut overflowed_q = q_limbs.clone();
+        overflowed_q[0] -= shift;
+        overflowed_q[1] += F::ONE;
+        should_fail(input(overflowed_q, r_limbs.clone()));
+
+        // An unreduced remainder: a * b = (q - 1) * p + (r + p)
+        let unreduced_r = to_limbs::<F>(&(&r + &p), NUM_LIMBS);
+        let smaller_q = to_limbs::<F>(&(&q - 1u32), NUM_LIMBS + 1);
+        should_fail(input(smaller_q, unreduced_r));
+
+        // A remainder off by one
+        let mut wrong_r = r_limbs.clone();
+        wrong_r[0] += F::ONE;
+        should_fail(input(q_limbs.clone(), wrong_r));
+
+        // Tampering with the remainder of an honest witness doesn't satisfy the constraints
+        let mut cs = ConstraintSystem::new();
+        cs.set_constraints(&assert_mul_mod_circuit);
+        let mut witness = cs.gen_witness(assert_mul_mod_circuit, &[], &input(q_limbs, r_limbs));
+        assert!(cs.is_sat(&witness, &[]));
+
+        let r_offset = 3 * NUM_LIMBS + 1;
+        witness[r_offset] += shift;
+        witness[r_offset + 1] -= F::ONE;
+        assert!(!cs.is_sat(&witness, &[]));
+    }
+}
@@ -0,0 +1,74 @@
+use super::bitops::range_check;
+use crate::frontend::constraint_system::{ConstraintSystem, Wire};
+use ark_ff::PrimeField;
+
+// Assert that the plaintext of `ct_lo` is less than the plaintext of `ct_hi`,
+// where both are ciphertexts of an order-preserving encryption scheme
+// with ciphertexts in [0, 2^num_bits).
+// Since the encryption preserves the order, this is the comparison ct_lo < ct_hi.
+pub fn assert_ope_order<F: PrimeField>(
+    cs: &mut ConstraintSystem<F>,
+    ct_lo: Wire<F>,
+    ct_hi: Wire<F>,
+    num_bits: usize,
+) {
+    // The difference below must not wrap around the modulus
+    assert!(
+        num_bits < F::MODULUS_BIT_SIZE as usize - 1,
+        "The ciphertexts must be smaller than the field"
+    );
+
+    range_check(ct_lo, num_bits, cs);
+    range_check(ct_hi, num_bits, cs);
+
+    // ct_hi - ct_lo - 1 is in [0, 2^num_bits) iff ct_lo < ct_hi
+    let diff = ct_hi - ct_lo;
+    let gap = cs.sub_const(diff, F::ONE);
+    range_check(gap, num_bits, cs);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_var_pub_input;
+    use std::panic;
+
+    type F = ark_secq256k1::Fr;
+
+    const CT_BITS: usize = 16;
+
+    // A toy order-preserving encryption of the plaintexts 0..16,
+    // which maps each plaintext to a strictly increasing ciphertext.
+    fn ope_encrypt(m: usize) -> F {
+        let gaps = [
+            13u64, 7, 201, 3, 54, 1, 999, 42, 8, 77, 310, 5, 16, 2, 650, 29,
+        ];
+        F::from(gaps[..=m].iter().sum::<u64>())
+    }
+
+    fn ope_circuit(cs: &mut ConstraintSystem<F>) {
+        let ct_lo = cs.alloc_pub_input();
+        let ct_hi = cs.alloc_pub_input();
+
+        assert_ope_order(cs, ct_lo, ct_hi, CT_BITS);
+    }
+
+    #[test]
+    fn test_assert_ope_order() {
+        let pub_input = [ope_encrypt(3), ope_encrypt(9)];
+        test_var_pub_input(ope_circuit, &pub_input, &[]);
+
+        // Should fail for the reversed and the equal orderings
+        for pub_input in [
+            [ope_encrypt(9), ope_encrypt(3)],
+            [ope_encrypt(5), ope_encrypt(5)],
+        ] {
+            let result = panic::catch_unwind(|| {
+                let mut cs = ConstraintSystem::<F>::new();
+                cs.set_constraints(&ope_circuit);
+                cs.gen_witness(ope_circuit, &pub_input, &[]);
+            });
+            assert!(result.is_err());
+        }
+    }
+}
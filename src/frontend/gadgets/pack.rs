@@ -0,0 +1,186 @@
+use super::bitops::range_check;
+use crate::frontend::constraint_system::{ConstraintSystem, Wire};
+use ark_ff::{BigInteger, PrimeField};
+
+fn check_bit_widths<F: PrimeField>(bit_widths: &[usize]) {
+    assert!(
+        bit_widths.iter().all(|width| *width > 0),
+        "Bit widths must be positive"
+    );
+    assert!(
+        bit_widths.iter().sum::<usize>() < F::MODULUS_BIT_SIZE as usize,
+        "The packed values must fit in a field element"
+    );
+}
+
+// Pack the values into a single field element,
+// placing values[i] at the bit offset bit_widths[0] + ... + bit_widths[i - 1].
+// Each value is constrained to fit in its bit width, so the packing is injective.
+pub fn pack<F: PrimeField>(
+    cs: &mut ConstraintSystem<F>,
+    values: &[Wire<F>],
+    bit_widths: &[usize],
+) -> Wire<F> {
+    assert_eq!(
+        values.len(),
+        bit_widths.len(),
+        "Each value needs a bit width"
+    );
+    check_bit_widths::<F>(bit_widths);
+
+    let mut terms = Vec::with_capacity(values.len());
+    let mut shift = F::ONE;
+    for (value, width) in values.iter().zip(bit_widths.iter()) {
+        range_check(*value, *width, cs);
+        terms.push((cs.mul_const(*value, shift), true));
+        shift *= F::from(2u32).pow([*width as u64]);
+    }
+
+    cs.sum(&terms)
+}
+
+// The inverse of `pack`: split the packed field element into values of the given bit widths.
+pub fn unpack<F: PrimeField>(
+    cs: &mut ConstraintSystem<F>,
+    packed: Wire<F>,
+    bit_widths: &[usize],
+) -> Vec<Wire<F>> {
+    check_bit_widths::<F>(bit_widths);
+
+    let packed_bits = packed.val(cs).map(|val| val.into_bigint().to_bits_le());
+
+    let mut offset = 0;
+    let mut values = Vec::with_capacity(bit_widths.len());
+    for width in bit_widths {
+        let value = packed_bits.as_ref().map_or(F::ZERO, |bits| {
+            F::from_bigint(F::BigInt::from_bits_le(&bits[offset..(offset + width)])).unwrap()
+        });
+        values.push(cs.alloc_var(value));
+        offset += width;
+    }
+
+    let repacked = pack(cs, &values, bit_widths);
+    cs.assert_equal(packed, repacked, "The packed value is out of range");
+
+    values
+}
+
+// Pack little-endian bytes into a single field element.
+// Each byte must be known to be in 0..256 (e.g. with `range_check`),
+// otherwise the packing isn't injective.
+pub fn from_bytes_le<F: PrimeField>(bytes: &[Wire<F>]) -> Wire<F> {
+    assert!(!bytes.is_empty(), "No bytes to pack");
+    assert!(
+        8 * bytes.len() < F::MODULUS_BIT_SIZE as usize,
+        "The packed bytes must fit in a field element"
+    );
+
+    let cs = bytes[0].cs();
+    let mut terms = Vec::with_capacity(bytes.len());
+    let mut shift = F::ONE;
+    for byte in bytes {
+        terms.push((cs.mul_const(*byte, shift), true));
+        shift *= F::from(256u32);
+    }
+
+    cs.sum(&terms)
+}
+
+// Pack big-endian bytes into a single field element, with the same precondition as `from_bytes_le`.
+pub fn from_bytes_be<F: PrimeField>(bytes: &[Wire<F>]) -> Wire<F> {
+    let le_bytes = bytes.iter().rev().copied().collect::<Vec<Wire<F>>>();
+    from_bytes_le(&le_bytes)
+}
+
+// Decompose `a` into `n_bytes` range-checked little-endian bytes.
+// The witness can't be generated if `a` doesn't fit in `n_bytes`.
+pub fn to_bytes<F: PrimeField>(a: Wire<F>, n_bytes: usize) -> Vec<Wire<F>> {
+    let cs = a.cs();
+    unpack(cs, a, &vec![8; n_bytes])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_var_pub_input;
+    use std::panic;
+
+    type F = ark_secq256k1::Fr;
+
+    const BIT_WIDTHS: [usize; 3] = [1, 8, 16];
+
+    fn pack_circuit(cs: &mut ConstraintSystem<F>) {
+        let values = cs.alloc_priv_inputs(BIT_WIDTHS.len());
+
+        let packed = pack(cs, &values, &BIT_WIDTHS);
+        let unpacked = unpack(cs, packed, &BIT_WIDTHS);
+
+        for (value, unpacked) in values.iter().zip(unpacked.iter()) {
+            cs.assert_equal(*value, *unpacked, "Round trip failed");
+        }
+
+        cs.expose_public(packed);
+    }
+
+    #[test]
+    fn test_pack_unpack() {
+        let values = [1u32, 0xab, 0x1234].map(F::from);
+        // 1 | 0xab << 1 | 0x1234 << 9
+        let packed = F::from(1u32 | (0xab << 1) | (0x1234 << 9));
+
+        test_var_pub_input(pack_circuit, &[packed], &values);
+
+        // Should fail if a value doesn't fit in its bit width
+        let result = panic::catch_unwind(|| {
+            let mut cs = ConstraintSystem::new();
+            cs.set_constraints(&pack_circuit);
+
+            let values = [2u32, 0xab, 0x1234].map(F::from);
+            cs.gen_witness(pack_circuit, &[packed], &values);
+        });
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_from_bytes() {
+        let synthesizer = |cs: &mut ConstraintSystem<F>| {
+            let bytes = cs.alloc_priv_inputs(2);
+
+            let be = from_bytes_be(&bytes);
+            let le = from_bytes_le(&bytes);
+
+            cs.expose_public(be);
+            cs.expose_public(le);
+        };
+
+        let bytes = [0x12u32, 0x34].map(F::from);
+        let pub_input = [0x1234u32, 0x3412].map(F::from);
+        test_var_pub_input(synthesizer, &pub_input, &bytes);
+    }
+
+    fn to_bytes_circuit(cs: &mut ConstraintSystem<F>) {
+        let a = cs.alloc_priv_input();
+        let bytes = to_bytes(a, 2);
+
+        for byte in bytes.iter().rev() {
+            cs.expose_public(*byte);
+        }
+
+        let repacked = from_bytes_le(&bytes);
+        cs.assert_equal(a, repacked, "Round trip failed");
+    }
+
+    #[test]
+    fn test_to_bytes() {
+        let pub_input = [0x12u32, 0x34].map(F::from);
+        test_var_pub_input(to_bytes_circuit, &pub_input, &[F::from(0x1234u32)]);
+
+        // Should fail if the value doesn't fit in the bytes
+        let result = panic::catch_unwind(|| {
+            let mut cs = ConstraintSystem::new();
+            cs.set_constraints(&to_bytes_circuit);
+            cs.gen_witness(to_bytes_circuit, &pub_input, &[F::from(0x11234u32)]);
+        });
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,76 @@
+use super::to_addr::keccak256;
+use crate::frontend::constraint_system::{ConstraintSystem, Wire};
+use ark_ff::PrimeField;
+
+// Assert that keccak256(salt . password) is equal to `stored_hash`.
+// All bits are little-endian within each byte, as in to_addr.
+// Keccak is fast to compute and not a memory-hard KDF (like scrypt or Argon2),
+// so a stored hash of a low-entropy password can be brute-forced off-circuit.
+// Only use this with high-entropy passwords, or when the stored hash isn't public.
+pub fn verify_password_hash<F: PrimeField>(
+    cs: &mut ConstraintSystem<F>,
+    password_bits: &[Wire<F>],
+    salt_bits: &[Wire<F>],
+    stored_hash: &[Wire<F>; 256],
+) {
+    let mut preimage = salt_bits.to_vec();
+    preimage.extend_from_slice(password_bits);
+
+    let hash = keccak256(&preimage);
+    for (bit, stored_bit) in hash.iter().zip(stored_hash.iter()) {
+        cs.assert_equal(*bit, *stored_bit, "Invalid password");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bytes_to_le_bits;
+    use std::panic;
+
+    type F = ark_secq256k1::Fr;
+
+    const SALT_LEN: usize = 16;
+    const PASSWORD: &[u8] = b"correct horse";
+
+    fn password_circuit(cs: &mut ConstraintSystem<F>) {
+        let stored_hash = cs.alloc_pub_inputs(256);
+        let password_bits = cs.alloc_priv_inputs(PASSWORD.len() * 8);
+        let salt_bits = cs.alloc_priv_inputs(SALT_LEN * 8);
+
+        verify_password_hash(
+            cs,
+            &password_bits,
+            &salt_bits,
+            &stored_hash.try_into().unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_verify_password_hash() {
+        let salt = (1..=SALT_LEN as u8).collect::<Vec<u8>>();
+
+        // keccak256(salt . b"correct horse") computed off-circuit
+        let stored_hash = bytes_to_le_bits(
+            &hex::decode("a1826d20dc3e73e13e6a5e57a78eba7b8610e8c3491e4bf86eeb6e30695d0c81")
+                .unwrap(),
+        );
+
+        let mut cs = ConstraintSystem::new();
+        cs.set_constraints(&password_circuit);
+
+        let priv_input = [bytes_to_le_bits(PASSWORD), bytes_to_le_bits(&salt)].concat();
+        let witness = cs.gen_witness(password_circuit, &stored_hash, &priv_input);
+        assert!(cs.is_sat(&witness, &stored_hash));
+
+        // Should fail for a wrong password of the same length
+        let result = panic::catch_unwind(|| {
+            let mut cs = ConstraintSystem::new();
+            cs.set_constraints(&password_circuit);
+
+            let priv_input = [bytes_to_le_bits(b"correct horsf"), bytes_to_le_bits(&salt)].concat();
+            cs.gen_witness(password_circuit, &stored_hash, &priv_input);
+        });
+        assert!(result.is_err());
+    }
+}
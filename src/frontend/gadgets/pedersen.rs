@@ -0,0 +1,182 @@
+use super::bitops::to_le_bits;
+use super::ecc::weierstrass::{ec_add_complete, ec_mul};
+use super::ecc::AffinePoint;
+use crate::frontend::constraint_system::{ConstraintSystem, Wire};
+use ark_ff::PrimeField;
+
+// Assert that `commitment` opens to `value` with `blinding`,
+// i.e. commitment = value * g + blinding * h on a short-Weierstrass curve
+// which base field is the native field.
+// The discrete log of `h` with respect to `g` must be unknown for the commitment to be binding.
+pub fn verify_pedersen_opening<F: PrimeField>(
+    cs: &mut ConstraintSystem<F>,
+    commitment: AffinePoint<F>,
+    value: Wire<F>,
+    blinding: Wire<F>,
+    g: AffinePoint<F>,
+    h: AffinePoint<F>,
+) {
+    let value_bits = to_le_bits(value);
+    let blinding_bits = to_le_bits(blinding);
+
+    let value_g = ec_mul(g, &value_bits, cs);
+    let blinding_h = ec_mul(h, &blinding_bits, cs);
+    let expected = ec_add_complete(value_g, blinding_h, cs);
+
+    cs.assert_equal(expected.x, commitment.x, "Invalid Pedersen opening");
+    cs.assert_equal(expected.y, commitment.y, "Invalid Pedersen opening");
+}
+
+// Assert that `commitment`, a Pedersen commitment to a field element value * g + blinding * h,
+// equals the commitment reconstructed from the bits of the value,
+// i.e. sum_i bits[i] * (2^i * g) + blinding * h.
+// The bits are constrained to be binary, so this bridges a bit-level representation
+// of the committed value to the field-level commitment.
+pub fn assert_bit_field_commitment_consistent<F: PrimeField>(
+    cs: &mut ConstraintSystem<F>,
+    commitment: AffinePoint<F>,
+    bits: &[Wire<F>],
+    blinding: Wire<F>,
+    g: AffinePoint<F>,
+    h: AffinePoint<F>,
+) {
+    for bit in bits {
+        cs.assert_equal(*bit * *bit, *bit, "bit is not binary");
+    }
+
+    let blinding_bits = to_le_bits(blinding);
+
+    // ec_mul adds up bits[i] * 2^i * g
+    let bits_g = ec_mul(g, bits, cs);
+    let blinding_h = ec_mul(h, &blinding_bits, cs);
+    let reconstructed = ec_add_complete(bits_g, blinding_h, cs);
+
+    cs.assert_equal(
+        reconstructed.x,
+        commitment.x,
+        "Bit decomposition doesn't match the commitment",
+    );
+    cs.assert_equal(
+        reconstructed.y,
+        commitment.y,
+        "Bit decomposition doesn't match the commitment",
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_var_pub_input;
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_ff::Field;
+    use ark_secp256k1::Affine as Secp256k1Affine;
+    use ark_secp256k1::Fr;
+    use std::panic;
+
+    type F = ark_secp256k1::Fq;
+
+    fn pedersen_circuit(cs: &mut ConstraintSystem<F>) {
+        let commitment_x = cs.alloc_pub_input();
+        let commitment_y = cs.alloc_pub_input();
+
+        let value = cs.alloc_priv_input();
+        let blinding = cs.alloc_priv_input();
+
+        let g = Secp256k1Affine::generator();
+        let h = (g * Fr::from(7u32)).into_affine();
+
+        let g = AffinePoint::new(cs.alloc_const(g.x), cs.alloc_const(g.y));
+        let h = AffinePoint::new(cs.alloc_const(h.x), cs.alloc_const(h.y));
+
+        let commitment = AffinePoint::new(commitment_x, commitment_y);
+        verify_pedersen_opening(cs, commitment, value, blinding, g, h);
+    }
+
+    #[test]
+    fn test_verify_pedersen_opening() {
+        let g = Secp256k1Affine::generator();
+        let h = (g * Fr::from(7u32)).into_affine();
+
+        let value = 1234u64;
+        let blinding = 987654321u64;
+
+        let commitment = (g * Fr::from(value) + h * Fr::from(blinding)).into_affine();
+
+        let pub_input = [commitment.x, commitment.y];
+        let priv_input = [F::from(value), F::from(blinding)];
+
+        test_var_pub_input(pedersen_circuit, &pub_input, &priv_input);
+
+        // Should fail if the value is tampered
+        let result = panic::catch_unwind(|| {
+            let mut cs = ConstraintSystem::<F>::new();
+            cs.set_constraints(&pedersen_circuit);
+
+            let tampered_priv_input = [F::from(value + 1), F::from(blinding)];
+            cs.gen_witness(pedersen_circuit, &pub_input, &tampered_priv_input);
+        });
+        assert!(result.is_err());
+    }
+
+    const VALUE_BITS: usize = 32;
+
+    fn bit_field_commitment_circuit(cs: &mut ConstraintSystem<F>) {
+        let commitment_x = cs.alloc_pub_input();
+        let commitment_y = cs.alloc_pub_input();
+
+        let bits = cs.alloc_priv_inputs(VALUE_BITS);
+        let blinding = cs.alloc_priv_input();
+
+        let g = Secp256k1Affine::generator();
+        let h = (g * Fr::from(7u32)).into_affine();
+
+        let g = AffinePoint::new(cs.alloc_const(g.x), cs.alloc_const(g.y));
+        let h = AffinePoint::new(cs.alloc_const(h.x), cs.alloc_const(h.y));
+
+        let commitment = AffinePoint::new(commitment_x, commitment_y);
+        assert_bit_field_commitment_consistent(cs, commitment, &bits, blinding, g, h);
+    }
+
+    #[test]
+    fn test_assert_bit_field_commitment_consistent() {
+        let g = Secp256k1Affine::generator();
+        let h = (g * Fr::from(7u32)).into_affine();
+
+        let value = 0xdeadbeefu64;
+        let blinding = 987654321u64;
+
+        let commitment = (g * Fr::from(value) + h * Fr::from(blinding)).into_affine();
+        let pub_input = [commitment.x, commitment.y];
+
+        let bits = (0..VALUE_BITS)
+            .map(|i| F::from((value >> i) & 1))
+            .collect::<Vec<F>>();
+        let priv_input = [bits.clone(), vec![F::from(blinding)]].concat();
+
+        test_var_pub_input(bit_field_commitment_circuit, &pub_input, &priv_input);
+
+        // Should fail if a bit is flipped
+        let mut tampered_bits = bits.clone();
+        tampered_bits[3] = F::ONE - tampered_bits[3];
+
+        // Should fail if the bits aren't binary, even if they sum up to the value
+        let mut non_binary_bits = bits.clone();
+        non_binary_bits[0] = F::from(3u32);
+        non_binary_bits[1] = F::ZERO;
+
+        for tampered in [tampered_bits, non_binary_bits] {
+            let result = panic::catch_unwind(|| {
+                let mut cs = ConstraintSystem::<F>::new();
+                cs.set_constraints(&bit_field_commitment_circuit);
+
+                let tampered_priv_input = [tampered, vec![F::from(blinding)]].concat();
+                cs.gen_witness(
+                    bit_field_commitment_circuit,
+                    &pub_input,
+                    &tampered_priv_input,
+                );
+            });
+            assert!(result.is_err());
+        }
+    }
+}
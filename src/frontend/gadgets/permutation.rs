@@ -0,0 +1,68 @@
+use crate::frontend::constraint_system::Wire;
+use ark_ff::Field;
+
+// Assert that `b` is a permutation of `a` with the grand product check
+// prod_i (challenge - a_i) == prod_i (challenge - b_i).
+// The check is sound only if `challenge` is chosen at random after `a` and `b` are fixed
+// (e.g. derived from a commitment to them), since a non-permutation passes
+// for at most `a.len()` values of the challenge.
+pub fn assert_permutation<F: Field>(a: &[Wire<F>], b: &[Wire<F>], challenge: Wire<F>) {
+    assert_eq!(a.len(), b.len(), "The arrays must have the same length");
+
+    let cs = challenge.cs();
+
+    let mut a_prod = cs.one();
+    let mut b_prod = cs.one();
+    for (a_i, b_i) in a.iter().zip(b.iter()) {
+        a_prod = a_prod * (challenge - *a_i);
+        b_prod = b_prod * (challenge - *b_i);
+    }
+
+    cs.assert_equal(
+        a_prod,
+        b_prod,
+        "The arrays are not permutations of each other",
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frontend::constraint_system::ConstraintSystem;
+    use crate::test_var_pub_input;
+    use std::panic;
+
+    type F = ark_secq256k1::Fr;
+
+    const LEN: usize = 4;
+
+    fn permutation_circuit(cs: &mut ConstraintSystem<F>) {
+        let challenge = cs.alloc_pub_input();
+        let a = cs.alloc_priv_inputs(LEN);
+        let b = cs.alloc_priv_inputs(LEN);
+
+        assert_permutation(&a, &b, challenge);
+    }
+
+    #[test]
+    fn test_assert_permutation() {
+        let challenge = F::from(123456789u64);
+        let a = [3u32, 1, 4, 1].map(F::from);
+        let b = [1u32, 4, 3, 1].map(F::from);
+
+        let pub_input = [challenge];
+        let priv_input = [a, b].concat();
+        test_var_pub_input(permutation_circuit, &pub_input, &priv_input);
+
+        // Should fail if the multiplicities differ
+        let result = panic::catch_unwind(|| {
+            let mut cs = ConstraintSystem::<F>::new();
+            cs.set_constraints(&permutation_circuit);
+
+            let not_permuted = [1u32, 4, 3, 3].map(F::from);
+            let priv_input = [a, not_permuted].concat();
+            cs.gen_witness(permutation_circuit, &pub_input, &priv_input);
+        });
+        assert!(result.is_err());
+    }
+}
@@ -1 +1,87 @@
 pub mod poseidon;
+
+use self::poseidon::PoseidonChip;
+use crate::frontend::constraint_system::Wire;
+use ::poseidon::constants::secp256k1_w3;
+use ark_ff::PrimeField;
+
+// The width of the permutation used by `poseidon_hash`,
+// which absorbs two elements per permutation with a capacity of one.
+pub const POSEIDON_HASH_WIDTH: usize = 3;
+
+// Poseidon hash of a non-empty number of field elements, with the t = 3 constants
+// for the base field of secp256k1 (i.e. the scalar field of secq256k1).
+// The capacity element is initialized to the number of inputs to separate the lengths,
+// and the inputs are added to the rate elements two at a time, permuting after each pair.
+pub fn poseidon_hash<F: PrimeField>(inputs: &[Wire<F>]) -> Wire<F> {
+    assert!(!inputs.is_empty(), "No inputs to hash");
+
+    let cs = inputs[0].cs();
+    let mut chip = PoseidonChip::<F, POSEIDON_HASH_WIDTH>::new(cs, secp256k1_w3());
+    chip.state[0] = cs.alloc_const(F::from(inputs.len() as u64));
+
+    for chunk in inputs.chunks(POSEIDON_HASH_WIDTH - 1) {
+        for (i, input) in chunk.iter().enumerate() {
+            chip.state[i + 1] = chip.state[i + 1] + *input;
+        }
+
+        chip.permute();
+        chip.pos = 0;
+    }
+
+    chip.state[1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frontend::constraint_system::ConstraintSystem;
+    use crate::test_var_pub_input;
+    use ::poseidon::Poseidon;
+    use ark_ff::Field;
+
+    type F = ark_secq256k1::Fr;
+
+    fn native_poseidon_hash(inputs: &[F]) -> F {
+        let mut state = [F::from(inputs.len() as u64), F::ZERO, F::ZERO];
+
+        for chunk in inputs.chunks(POSEIDON_HASH_WIDTH - 1) {
+            for (i, input) in chunk.iter().enumerate() {
+                state[i + 1] += input;
+            }
+
+            // A fresh instance starts again from the first round constants
+            let mut poseidon = Poseidon::<F, POSEIDON_HASH_WIDTH>::new(secp256k1_w3());
+            poseidon.state[..].copy_from_slice(&state);
+            poseidon.permute();
+            state.copy_from_slice(&poseidon.state[..]);
+        }
+
+        state[1]
+    }
+
+    #[test]
+    fn test_poseidon_hash() {
+        for n in [1, 2, 3, 5] {
+            let synthesizer = |cs: &mut ConstraintSystem<F>| {
+                let inputs = cs.alloc_priv_inputs(n);
+                let out = poseidon_hash(&inputs);
+                cs.expose_public(out);
+            };
+
+            let inputs = (0..n).map(|i| F::from(i as u64 + 3)).collect::<Vec<F>>();
+            let expected = native_poseidon_hash(&inputs);
+            test_var_pub_input(synthesizer, &[expected], &inputs);
+        }
+    }
+
+    #[test]
+    fn test_poseidon_hash_length_separation() {
+        // [a] and [a, 0] absorb the same rate elements, but hash differently
+        let a = F::from(7u32);
+        assert_ne!(
+            native_poseidon_hash(&[a]),
+            native_poseidon_hash(&[a, F::from(0u32)])
+        );
+    }
+}
@@ -0,0 +1,83 @@
+use super::to_addr::keccak256;
+use crate::frontend::constraint_system::{ConstraintSystem, Wire};
+use ark_ff::PrimeField;
+
+// Domain tag prepended to the preimage, so that the outputs of the PRF
+// never coincide with the Keccak256 digest of the same key and input.
+pub const KECCAK_PRF_TAG: &[u8] = b"sapir.keccak_prf";
+
+// Computes keccak256(tag . key . input), which is a pseudorandom function of
+// the input keyed by the key. This is a PRF and not a VRF: there is no public key
+// against which the output can be checked without proving this circuit.
+// All bits are little-endian within each byte, as in to_addr.
+pub fn keccak_prf<F: PrimeField>(
+    cs: &mut ConstraintSystem<F>,
+    key_bits: &[Wire<F>],
+    input_bits: &[Wire<F>],
+) -> [Wire<F>; 256] {
+    let zero = cs.zero();
+    let one = cs.one();
+
+    let mut preimage = KECCAK_PRF_TAG
+        .iter()
+        .flat_map(|byte| (0..8).map(move |i| (byte >> i) & 1 == 1))
+        .map(|bit| if bit { one } else { zero })
+        .collect::<Vec<Wire<F>>>();
+
+    preimage.extend_from_slice(key_bits);
+    preimage.extend_from_slice(input_bits);
+
+    keccak256(&preimage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bytes_to_le_bits;
+
+    type F = ark_secq256k1::Fr;
+
+    const KEY_LEN: usize = 32;
+    const INPUT: &[u8] = b"sapir-input";
+
+    fn prf_circuit(cs: &mut ConstraintSystem<F>) {
+        let key_bits = cs.alloc_priv_inputs(KEY_LEN * 8);
+        let input_bits = cs.alloc_priv_inputs(INPUT.len() * 8);
+
+        let out = keccak_prf(cs, &key_bits, &input_bits);
+        for bit in out {
+            cs.expose_public(bit);
+        }
+    }
+
+    #[test]
+    fn test_keccak_prf() {
+        let key_1 = (1..=KEY_LEN as u8).collect::<Vec<u8>>();
+        let key_2 = (2..=(KEY_LEN + 1) as u8).collect::<Vec<u8>>();
+
+        // keccak256(b"sapir.keccak_prf" . key . b"sapir-input") computed off-circuit
+        let expected_1 = bytes_to_le_bits(
+            &hex::decode("224e309bb9d7e0ff58a4eafe7b2b72fa3bfefccd33a1ca7c4843bf4948c41c85")
+                .unwrap(),
+        );
+        let expected_2 = bytes_to_le_bits(
+            &hex::decode("fe2efdd7969c43f3a6d0062f90142b84c25b2e48036bf3c09a3b60dd5fff5507")
+                .unwrap(),
+        );
+
+        let mut cs = ConstraintSystem::new();
+        cs.set_constraints(&prf_circuit);
+
+        let priv_input_1 = [bytes_to_le_bits(&key_1), bytes_to_le_bits(INPUT)].concat();
+        let witness_1 = cs.gen_witness(prf_circuit, &expected_1, &priv_input_1);
+        assert!(cs.is_sat(&witness_1, &expected_1));
+
+        let priv_input_2 = [bytes_to_le_bits(&key_2), bytes_to_le_bits(INPUT)].concat();
+        let witness_2 = cs.gen_witness(prf_circuit, &expected_2, &priv_input_2);
+        assert!(cs.is_sat(&witness_2, &expected_2));
+
+        // Different keys yield different outputs
+        assert_ne!(expected_1, expected_2);
+        assert!(!cs.is_sat(&witness_2, &expected_1));
+    }
+}
@@ -0,0 +1,73 @@
+use crate::frontend::constraint_system::{ConstraintSystem, Wire};
+use ark_ff::PrimeField;
+
+// Assert that the evaluations `(x_i, y_i)` are symbols of the Reed-Solomon codeword
+// of the polynomial with the coefficients `coeffs` (lowest degree first),
+// i.e. y_i = sum_j coeffs[j] * x_i^j.
+// The degree of the polynomial is bounded by the number of coefficients.
+pub fn assert_rs_codeword<F: PrimeField>(
+    cs: &mut ConstraintSystem<F>,
+    coeffs: &[Wire<F>],
+    evals: &[(Wire<F>, Wire<F>)],
+) {
+    assert!(!coeffs.is_empty(), "At least one coefficient is required");
+
+    for (x, y) in evals {
+        // Horner's method
+        let mut acc = coeffs[coeffs.len() - 1];
+        for coeff in coeffs.iter().rev().skip(1) {
+            acc = acc * *x + *coeff;
+        }
+
+        cs.assert_equal(acc, *y, "Evaluation is not on the codeword");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_var_pub_input;
+    use std::panic;
+
+    type F = ark_secq256k1::Fr;
+
+    // p(x) = 5 + 3x + 2x^2 + x^3
+    const COEFFS: [u64; 4] = [5, 3, 2, 1];
+    const POINTS: [u64; 5] = [0, 1, 2, 7, 100];
+
+    fn eval(x: u64) -> u64 {
+        COEFFS.iter().rev().fold(0, |acc, c| acc * x + c)
+    }
+
+    fn rs_codeword_circuit(cs: &mut ConstraintSystem<F>) {
+        let evals = POINTS
+            .iter()
+            .map(|_| (cs.alloc_pub_input(), cs.alloc_pub_input()))
+            .collect::<Vec<(Wire<F>, Wire<F>)>>();
+        let coeffs = cs.alloc_priv_inputs(COEFFS.len());
+
+        assert_rs_codeword(cs, &coeffs, &evals);
+    }
+
+    #[test]
+    fn test_assert_rs_codeword() {
+        let pub_input = POINTS
+            .iter()
+            .flat_map(|x| [F::from(*x), F::from(eval(*x))])
+            .collect::<Vec<F>>();
+        let priv_input = COEFFS.map(F::from);
+
+        test_var_pub_input(rs_codeword_circuit, &pub_input, &priv_input);
+
+        // Should fail if a coefficient doesn't match the evaluations
+        let result = panic::catch_unwind(|| {
+            let mut cs = ConstraintSystem::new();
+            cs.set_constraints(&rs_codeword_circuit);
+
+            let mut tampered_coeffs = priv_input;
+            tampered_coeffs[3] += F::from(1u32);
+            cs.gen_witness(rs_codeword_circuit, &pub_input, &tampered_coeffs);
+        });
+        assert!(result.is_err());
+    }
+}
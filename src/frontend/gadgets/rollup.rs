@@ -0,0 +1,254 @@
+use super::bitops::range_check;
+use super::poseidon::poseidon::PoseidonChip;
+use super::tree::{hash, verify_merkle_proof, SPONGE_WIDTH};
+use crate::frontend::constraint_system::{ConstraintSystem, Wire};
+use ark_ff::{Field, PrimeField};
+
+// Balances and amounts are less than 2^BALANCE_BITS
+pub const BALANCE_BITS: usize = 64;
+
+// An account, which leaf in the state tree is hash(balance, nonce),
+// and its Merkle proof.
+#[derive(Clone)]
+pub struct AccountProof<F: Field> {
+    pub balance: Wire<F>,
+    pub nonce: Wire<F>,
+    pub siblings: Vec<Wire<F>>,
+    pub path_indices: Vec<Wire<F>>,
+}
+
+// A transfer of `amount` from the sender to the receiver.
+// `nonce` must be the current nonce of the sender, which is incremented by the transfer.
+#[derive(Clone)]
+pub struct RollupTransfer<F: Field> {
+    pub sender: AccountProof<F>,
+    pub receiver: AccountProof<F>,
+    pub amount: Wire<F>,
+    pub nonce: Wire<F>,
+}
+
+// Assert that applying the transfers in order takes the state tree from `old_root` to `new_root`.
+// Each transfer debits the sender and increments its nonce, and then credits the receiver,
+// so the Merkle proof of the receiver is against the state after debiting the sender.
+// The transfers must not overdraw the senders or overflow the balances of the receivers.
+pub fn verify_rollup_batch<F: PrimeField>(
+    cs: &mut ConstraintSystem<F>,
+    old_root: Wire<F>,
+    new_root: Wire<F>,
+    transfers: &[RollupTransfer<F>],
+    poseidon: PoseidonChip<F, SPONGE_WIDTH>,
+) {
+    let one = cs.one();
+
+    let mut root = old_root;
+    for transfer in transfers {
+        let sender = &transfer.sender;
+        let receiver = &transfer.receiver;
+
+        // The sender's account is in the current state
+        let sender_leaf = hash(sender.balance, sender.nonce, poseidon.clone());
+        let sender_root = verify_merkle_proof(
+            sender_leaf,
+            &sender.siblings,
+            &sender.path_indices,
+            poseidon.clone(),
+            cs,
+        );
+        cs.assert_equal(sender_root, root, "Sender account is not in the state");
+        cs.assert_equal(transfer.nonce, sender.nonce, "Invalid nonce");
+
+        // The balance of the sender doesn't underflow
+        range_check(transfer.amount, BALANCE_BITS, cs);
+        let sender_balance = sender.balance - transfer.amount;
+        range_check(sender_balance, BALANCE_BITS, cs);
+
+        let sender_leaf = hash(sender_balance, sender.nonce + one, poseidon.clone());
+        root = verify_merkle_proof(
+            sender_leaf,
+            &sender.siblings,
+            &sender.path_indices,
+            poseidon.clone(),
+            cs,
+        );
+
+        // The receiver's account is in the state after debiting the sender
+        let receiver_leaf = hash(receiver.balance, receiver.nonce, poseidon.clone());
+        let receiver_root = verify_merkle_proof(
+            receiver_leaf,
+            &receiver.siblings,
+            &receiver.path_indices,
+            poseidon.clone(),
+            cs,
+        );
+        cs.assert_equal(receiver_root, root, "Receiver account is not in the state");
+
+        // The balance of the receiver doesn't overflow
+        let receiver_balance = receiver.balance + transfer.amount;
+        range_check(receiver_balance, BALANCE_BITS, cs);
+
+        let receiver_leaf = hash(receiver_balance, receiver.nonce, poseidon.clone());
+        root = verify_merkle_proof(
+            receiver_leaf,
+            &receiver.siblings,
+            &receiver.path_indices,
+            poseidon.clone(),
+            cs,
+        );
+    }
+
+    cs.assert_equal(root, new_root, "Invalid new state root");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_var_pub_input;
+    use poseidon::{constants::secp256k1_w3, Poseidon};
+
+    type F = ark_secp256k1::Fq;
+
+    const TREE_DEPTH: usize = 8;
+    const NUM_TRANSFERS: usize = 2;
+
+    fn native_hash(left: F, right: F) -> F {
+        let mut poseidon = Poseidon::<F, SPONGE_WIDTH>::new(secp256k1_w3());
+        poseidon.state[0] = F::from(3u32);
+        poseidon.state[1] = left;
+        poseidon.state[2] = right;
+        poseidon.permute();
+
+        poseidon.state[1]
+    }
+
+    // The state tree, with the accounts as (balance, nonce)
+    struct State {
+        accounts: Vec<(u64, u64)>,
+    }
+
+    impl State {
+        fn levels(&self) -> Vec<Vec<F>> {
+            let leaves = self
+                .accounts
+                .iter()
+                .map(|(balance, nonce)| native_hash(F::from(*balance), F::from(*nonce)))
+                .collect::<Vec<F>>();
+
+            let mut levels = vec![leaves];
+            for _ in 0..TREE_DEPTH {
+                let level = levels
+                    .last()
+                    .unwrap()
+                    .chunks(2)
+                    .map(|pair| native_hash(pair[0], pair[1]))
+                    .collect();
+                levels.push(level);
+            }
+
+            levels
+        }
+
+        fn root(&self) -> F {
+            self.levels()[TREE_DEPTH][0]
+        }
+
+        // The account, its siblings, and its path indices
+        fn account_input(&self, index: usize) -> Vec<F> {
+            let levels = self.levels();
+            let (balance, nonce) = self.accounts[index];
+
+            let siblings = (0..TREE_DEPTH).map(|i| levels[i][(index >> i) ^ 1]);
+            let path_indices = (0..TREE_DEPTH).map(|i| F::from(((index >> i) & 1) as u64));
+
+            [F::from(balance), F::from(nonce)]
+                .into_iter()
+                .chain(siblings)
+                .chain(path_indices)
+                .collect()
+        }
+
+        // Apply the transfer, and return the private input of the circuit for it
+        fn transfer(&mut self, from: usize, to: usize, amount: u64) -> Vec<F> {
+            let nonce = self.accounts[from].1;
+
+            let mut input = vec![F::from(amount), F::from(nonce)];
+            input.extend(self.account_input(from));
+
+            // The amount may overdraw the sender for the negative tests
+            self.accounts[from].0 = self.accounts[from].0.wrapping_sub(amount);
+            self.accounts[from].1 += 1;
+
+            input.extend(self.account_input(to));
+            self.accounts[to].0 += amount;
+
+            input
+        }
+    }
+
+    fn alloc_account(cs: &mut ConstraintSystem<F>) -> AccountProof<F> {
+        AccountProof {
+            balance: cs.alloc_priv_input(),
+            nonce: cs.alloc_priv_input(),
+            siblings: cs.alloc_priv_inputs(TREE_DEPTH),
+            path_indices: cs.alloc_priv_inputs(TREE_DEPTH),
+        }
+    }
+
+    fn rollup_circuit(cs: &mut ConstraintSystem<F>) {
+        let old_root = cs.alloc_pub_input();
+        let new_root = cs.alloc_pub_input();
+
+        let transfers = (0..NUM_TRANSFERS)
+            .map(|_| {
+                let amount = cs.alloc_priv_input();
+                let nonce = cs.alloc_priv_input();
+                let sender = alloc_account(cs);
+                let receiver = alloc_account(cs);
+
+                RollupTransfer {
+                    sender,
+                    receiver,
+                    amount,
+                    nonce,
+                }
+            })
+            .collect::<Vec<RollupTransfer<F>>>();
+
+        let poseidon = PoseidonChip::<F, SPONGE_WIDTH>::new(cs, secp256k1_w3());
+        verify_rollup_batch(cs, old_root, new_root, &transfers, poseidon);
+    }
+
+    // Run the transfers from the initial state, and return the public and private inputs
+    fn batch_input(transfers: [(usize, usize, u64); NUM_TRANSFERS]) -> (Vec<F>, Vec<F>) {
+        let mut accounts = vec![(0, 0); 1 << TREE_DEPTH];
+        accounts[3] = (100, 5);
+        accounts[42] = (40, 0);
+        accounts[200] = (7, 1);
+
+        let mut state = State { accounts };
+        let old_root = state.root();
+
+        let priv_input = transfers
+            .iter()
+            .flat_map(|(from, to, amount)| state.transfer(*from, *to, *amount))
+            .collect::<Vec<F>>();
+
+        (vec![old_root, state.root()], priv_input)
+    }
+
+    #[test]
+    fn test_verify_rollup_batch() {
+        // The second transfer spends funds received in the first one
+        let (pub_input, priv_input) = batch_input([(3, 42, 30), (42, 200, 50)]);
+        test_var_pub_input(rollup_circuit, &pub_input, &priv_input);
+    }
+
+    #[test]
+    #[should_panic(expected = "value is out of range")]
+    fn test_verify_rollup_batch_overdraw() {
+        // The second transfer overdraws the sender
+        let (pub_input, priv_input) = batch_input([(3, 42, 30), (42, 200, 80)]);
+
+        let mut cs = ConstraintSystem::new();
+        cs.gen_witness(rollup_circuit, &pub_input, &priv_input);
+    }
+}
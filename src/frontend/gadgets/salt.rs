@@ -0,0 +1,111 @@
+use super::bitops::{byte_to_le_bits, form_le_bits};
+use super::canonical::assert_canonical_field_bytes;
+use super::to_addr::keccak256;
+use crate::frontend::constraint_system::{ConstraintSystem, Wire};
+use ark_ff::{BigInteger, PrimeField};
+
+// Computes the salt keccak256(seed . counter),
+// where the counter is encoded as 32 big-endian bytes (i.e. as a uint256).
+// The encoding of the counter is constrained to be canonical,
+// so each counter yields exactly one salt.
+// All bits are little-endian within each byte, as in to_addr.
+pub fn derive_salt<F: PrimeField>(
+    cs: &mut ConstraintSystem<F>,
+    seed_bits: &[Wire<F>],
+    counter: Wire<F>,
+) -> [Wire<F>; 256] {
+    // Big-endian bytes of the counter
+    let counter_bytes_val = counter.val(cs).map(|val| {
+        let mut bytes = val.into_bigint().to_bytes_le();
+        bytes.resize(32, 0);
+        bytes.reverse();
+        bytes
+    });
+
+    let counter_bytes: [Wire<F>; 32] = (0..32)
+        .map(|i| {
+            let byte = counter_bytes_val.as_ref().map_or(0, |bytes| bytes[i]);
+            cs.alloc_var(F::from(byte))
+        })
+        .collect::<Vec<Wire<F>>>()
+        .try_into()
+        .unwrap();
+
+    assert_canonical_field_bytes(cs, &counter_bytes);
+
+    let mut preimage = seed_bits.to_vec();
+    let mut counter_bits = Vec::with_capacity(256);
+    for byte in &counter_bytes {
+        let byte_bits = byte_to_le_bits(*byte, cs);
+        preimage.extend_from_slice(&byte_bits);
+
+        // Most significant byte first, so prepend to get the little-endian bits
+        counter_bits.splice(0..0, byte_bits);
+    }
+
+    let recovered_counter = form_le_bits(&counter_bits);
+    cs.assert_equal(counter, recovered_counter, "Invalid counter encoding");
+
+    keccak256(&preimage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bytes_to_le_bits;
+    use ark_ff::Field;
+
+    type F = ark_secq256k1::Fr;
+
+    const SEED_LEN: usize = 32;
+
+    fn salt_circuit(cs: &mut ConstraintSystem<F>) {
+        let seed_bits = cs.alloc_priv_inputs(SEED_LEN * 8);
+        let counter = cs.alloc_priv_input();
+
+        let salt = derive_salt(cs, &seed_bits, counter);
+        for bit in salt {
+            cs.expose_public(bit);
+        }
+    }
+
+    #[test]
+    fn test_derive_salt() {
+        let seed = (1..=SEED_LEN as u8).collect::<Vec<u8>>();
+
+        // keccak256(seed . uint256(counter)) computed off-circuit
+        let cases = [
+            (
+                F::ZERO,
+                "506fecd382403ae0a419388b64adab18ca99d6f0438781fd0e64887a242cf560",
+            ),
+            (
+                F::ONE,
+                "0977641d6d2d0fd53dab886b534d2862f5c5993141113dc0252c2783b9d6ce61",
+            ),
+            (
+                F::from(u64::MAX) + F::from(8u32),
+                "c758d4b503aab57775375881886061b928ad71d85ddafa4381aeb2ae1aa069be",
+            ),
+        ];
+
+        let mut cs = ConstraintSystem::new();
+        cs.set_constraints(&salt_circuit);
+
+        let mut salts = vec![];
+        for (counter, expected) in cases {
+            let expected = bytes_to_le_bits(&hex::decode(expected).unwrap());
+
+            let priv_input = [bytes_to_le_bits(&seed), vec![counter]].concat();
+            let witness = cs.gen_witness(salt_circuit, &expected, &priv_input);
+            assert!(cs.is_sat(&witness, &expected));
+
+            salts.push(expected);
+        }
+
+        // Distinct counters yield distinct salts
+        assert_ne!(salts[0], salts[1]);
+        assert_ne!(salts[1], salts[2]);
+        assert_ne!(salts[0], salts[2]);
+    }
+}
@@ -0,0 +1,253 @@
+use super::bitops::to_le_bits;
+use super::cmp::less_than_or_equal;
+use super::ecc::weierstrass::{ec_add_complete, ec_mul};
+use super::ecc::AffinePoint;
+use super::poseidon::poseidon::PoseidonChip;
+use super::tree::{hash, SPONGE_WIDTH};
+use crate::frontend::constraint_system::{ConstraintSystem, Wire};
+use ark_ff::{Field, PrimeField};
+
+// The two sides s * g and r + e * pubkey of the verification equation of `verify_poseidon_schnorr`
+fn verification_sides<F: PrimeField>(
+    cs: &mut ConstraintSystem<F>,
+    pubkey: AffinePoint<F>,
+    msg: Wire<F>,
+    r: AffinePoint<F>,
+    s: Wire<F>,
+    g: AffinePoint<F>,
+    poseidon: PoseidonChip<F, SPONGE_WIDTH>,
+) -> (AffinePoint<F>, AffinePoint<F>) {
+    let r_hash = hash(r.x, r.y, poseidon.clone());
+    let pubkey_hash = hash(pubkey.x, pubkey.y, poseidon.clone());
+    let e = hash(hash(r_hash, pubkey_hash, poseidon.clone()), msg, poseidon);
+
+    // The challenge is reduced modulo the group order by the scalar multiplication
+    let e_bits = to_le_bits(e);
+    let s_bits = to_le_bits(s);
+
+    let s_g = ec_mul(g, &s_bits, cs);
+    let e_pubkey = ec_mul(pubkey, &e_bits, cs);
+    let expected = ec_add_complete(r, e_pubkey, cs);
+
+    (s_g, expected)
+}
+
+// Verify the Schnorr signature (r, s) of `msg` by `pubkey`, i.e. s * g == r + e * pubkey
+// where the challenge e = H(H(H(r.x, r.y), H(pubkey.x, pubkey.y)), msg)
+// and H is the two-to-one Poseidon hash of the Merkle tree gadget.
+// The curve is a short-Weierstrass curve which base field is the native field,
+// so the group operations and the hash are native arithmetic.
+// The scalar field of the curve must be smaller than the native field (e.g. secp256k1),
+// so that `s` fits in a wire.
+pub fn verify_poseidon_schnorr<F: PrimeField>(
+    cs: &mut ConstraintSystem<F>,
+    pubkey: AffinePoint<F>,
+    msg: Wire<F>,
+    r: AffinePoint<F>,
+    s: Wire<F>,
+    g: AffinePoint<F>,
+    poseidon: PoseidonChip<F, SPONGE_WIDTH>,
+) {
+    let (s_g, expected) = verification_sides(cs, pubkey, msg, r, s, g, poseidon);
+
+    cs.assert_equal(s_g.x, expected.x, "Invalid Schnorr signature");
+    cs.assert_equal(s_g.y, expected.y, "Invalid Schnorr signature");
+}
+
+// A Schnorr signature (r, s) of `verify_poseidon_schnorr`, and the public key to check it against
+#[derive(Clone, Copy)]
+pub struct SchnorrSignature<F: Field> {
+    pub pubkey: AffinePoint<F>,
+    pub r: AffinePoint<F>,
+    pub s: Wire<F>,
+}
+
+// Returns 1 if at least `threshold` of the signatures are valid signatures of `msg`
+// (as in `verify_poseidon_schnorr`), and 0 otherwise.
+// The valid signatures are counted, and the count is compared against the threshold.
+// The public keys must be distinct (e.g. a fixed set of signers),
+// otherwise a signer could be counted more than once.
+pub fn verify_threshold_schnorr<F: PrimeField>(
+    cs: &mut ConstraintSystem<F>,
+    msg: Wire<F>,
+    signatures: &[SchnorrSignature<F>],
+    threshold: usize,
+    g: AffinePoint<F>,
+    poseidon: PoseidonChip<F, SPONGE_WIDTH>,
+) -> Wire<F> {
+    assert!(
+        threshold <= signatures.len(),
+        "The threshold must not exceed the number of signatures"
+    );
+
+    let mut num_valid = Vec::with_capacity(signatures.len());
+    for sig in signatures {
+        let (s_g, expected) =
+            verification_sides(cs, sig.pubkey, msg, sig.r, sig.s, g, poseidon.clone());
+
+        let is_valid = cs.is_equal(s_g.x, expected.x) * cs.is_equal(s_g.y, expected.y);
+        num_valid.push((is_valid, true));
+    }
+    let num_valid = cs.sum(&num_valid);
+
+    // Both the count and the threshold are at most the number of signatures
+    let num_bits = (usize::BITS - signatures.len().leading_zeros()) as usize;
+    let threshold = cs.alloc_const(F::from(threshold as u64));
+    less_than_or_equal(threshold, num_valid, num_bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_var_pub_input;
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_ff::BigInteger;
+    use ark_secp256k1::Affine as Secp256k1Affine;
+    use ark_secp256k1::Fr;
+    use poseidon::{constants::secp256k1_w3, Poseidon};
+
+    type F = ark_secp256k1::Fq;
+
+    // The two-to-one hash of the Merkle tree gadget
+    fn hash_ref(left: F, right: F) -> F {
+        let mut poseidon = Poseidon::<F, SPONGE_WIDTH>::new(secp256k1_w3());
+        poseidon.state[0] = F::from(3u32);
+        poseidon.state[1] = left;
+        poseidon.state[2] = right;
+        poseidon.permute();
+
+        poseidon.state[1]
+    }
+
+    // Sign `msg` with the secret key `x` and the nonce `k`
+    fn sign(x: Fr, k: Fr, msg: F) -> (Secp256k1Affine, Fr) {
+        let g = Secp256k1Affine::generator();
+        let pubkey = (g * x).into_affine();
+        let r = (g * k).into_affine();
+
+        let e = hash_ref(
+            hash_ref(hash_ref(r.x, r.y), hash_ref(pubkey.x, pubkey.y)),
+            msg,
+        );
+        let e = Fr::from_le_bytes_mod_order(&e.into_bigint().to_bytes_le());
+
+        (r, k + e * x)
+    }
+
+    fn schnorr_circuit(cs: &mut ConstraintSystem<F>) {
+        let pubkey_x = cs.alloc_pub_input();
+        let pubkey_y = cs.alloc_pub_input();
+        let msg = cs.alloc_pub_input();
+
+        let r_x = cs.alloc_priv_input();
+        let r_y = cs.alloc_priv_input();
+        let s = cs.alloc_priv_input();
+
+        let g = Secp256k1Affine::generator();
+        let g = AffinePoint::new(cs.alloc_const(g.x), cs.alloc_const(g.y));
+
+        let poseidon = PoseidonChip::<F, SPONGE_WIDTH>::new(cs, secp256k1_w3());
+
+        verify_poseidon_schnorr(
+            cs,
+            AffinePoint::new(pubkey_x, pubkey_y),
+            msg,
+            AffinePoint::new(r_x, r_y),
+            s,
+            g,
+            poseidon,
+        );
+    }
+
+    // The public and private inputs of a valid signature
+    fn signed_message() -> ([F; 3], [F; 3]) {
+        let x = Fr::from(0xdeadbeefu64);
+        let k = Fr::from(0x1234567u64);
+        let msg = F::from(42u32);
+
+        let pubkey = (Secp256k1Affine::generator() * x).into_affine();
+        let (r, s) = sign(x, k, msg);
+
+        // The order of secp256k1 is smaller than its base field, so s fits in a wire
+        let s = F::from_bigint(s.into_bigint()).unwrap();
+
+        ([pubkey.x, pubkey.y, msg], [r.x, r.y, s])
+    }
+
+    #[test]
+    fn test_verify_poseidon_schnorr() {
+        let (pub_input, priv_input) = signed_message();
+        test_var_pub_input(schnorr_circuit, &pub_input, &priv_input);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid Schnorr signature")]
+    fn test_verify_poseidon_schnorr_wrong_msg() {
+        let (mut pub_input, priv_input) = signed_message();
+        pub_input[2] += F::from(1u32);
+
+        let mut cs = ConstraintSystem::new();
+        cs.gen_witness(schnorr_circuit, &pub_input, &priv_input);
+    }
+
+    const NUM_SIGNERS: usize = 3;
+    const THRESHOLD: usize = 2;
+
+    fn threshold_circuit(cs: &mut ConstraintSystem<F>) {
+        let msg = cs.alloc_pub_input();
+        let pubkeys = (0..NUM_SIGNERS)
+            .map(|_| AffinePoint::new(cs.alloc_pub_input(), cs.alloc_pub_input()))
+            .collect::<Vec<AffinePoint<F>>>();
+
+        let signatures = pubkeys
+            .iter()
+            .map(|pubkey| SchnorrSignature {
+                pubkey: *pubkey,
+                r: AffinePoint::new(cs.alloc_priv_input(), cs.alloc_priv_input()),
+                s: cs.alloc_priv_input(),
+            })
+            .collect::<Vec<SchnorrSignature<F>>>();
+
+        let g = Secp256k1Affine::generator();
+        let g = AffinePoint::new(cs.alloc_const(g.x), cs.alloc_const(g.y));
+
+        let poseidon = PoseidonChip::<F, SPONGE_WIDTH>::new(cs, secp256k1_w3());
+
+        let is_valid = verify_threshold_schnorr(cs, msg, &signatures, THRESHOLD, g, poseidon);
+        cs.expose_public(is_valid);
+    }
+
+    // The public and private inputs of the threshold circuit,
+    // where the signers in `valid` sign the message and the others sign another message
+    fn threshold_input(valid: [bool; NUM_SIGNERS]) -> (Vec<F>, Vec<F>) {
+        let msg = F::from(42u32);
+
+        let mut pub_input = vec![msg];
+        let mut priv_input = vec![];
+        for (i, is_valid) in valid.iter().enumerate() {
+            let x = Fr::from(0xdeadbeefu64 + i as u64);
+            let k = Fr::from(0x1234567u64 + i as u64);
+            let pubkey = (Secp256k1Affine::generator() * x).into_affine();
+
+            let signed_msg = if *is_valid { msg } else { msg + F::from(1u32) };
+            let (r, s) = sign(x, k, signed_msg);
+            let s = F::from_bigint(s.into_bigint()).unwrap();
+
+            pub_input.extend([pubkey.x, pubkey.y]);
+            priv_input.extend([r.x, r.y, s]);
+        }
+
+        (pub_input, priv_input)
+    }
+
+    #[test]
+    fn test_verify_threshold_schnorr() {
+        // Two valid signatures out of three pass, and one doesn't
+        for (valid, expected) in [([true, false, true], true), ([false, true, false], false)] {
+            let (mut pub_input, priv_input) = threshold_input(valid);
+            pub_input.push(F::from(expected));
+
+            test_var_pub_input(threshold_circuit, &pub_input, &priv_input);
+        }
+    }
+}
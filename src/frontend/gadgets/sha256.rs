@@ -0,0 +1,229 @@
+use super::bitops::{ch, const_word, maj, rotate_right, shift_right, sum_mod_32, xor, xor_pad};
+use crate::frontend::constraint_system::{ConstraintSystem, Wire};
+use ark_ff::PrimeField;
+
+// A 32-bit word as little-endian bits
+type Word<F> = [Wire<F>; 32];
+
+const BLOCK_LEN: usize = 512;
+
+// The block length of SHA-256 in bytes, for HMAC
+const HMAC_BLOCK_BYTES: usize = 64;
+
+// Section 5.3.3 of https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.180-4.pdf
+pub const H0: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+// Section 4.2.2 of https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.180-4.pdf
+pub const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+// Apply the compression function to a 512-bit block,
+// which bits are little-endian within each byte.
+fn compress<F: PrimeField>(
+    state: [Word<F>; 8],
+    block: &[Wire<F>],
+    cs: &mut ConstraintSystem<F>,
+) -> [Word<F>; 8] {
+    // Message schedule.
+    // The words are big-endian, so the first byte of a word holds its most significant bits.
+    let mut w = Vec::with_capacity(64);
+    for j in 0..16 {
+        let word: Word<F> = std::array::from_fn(|k| block[8 * (4 * j + 3 - k / 8) + k % 8]);
+        w.push(word);
+    }
+
+    for t in 16..64 {
+        let s0: Word<F> = xor(
+            &xor(&rotate_right(w[t - 15], 7), &rotate_right(w[t - 15], 18)),
+            &shift_right(w[t - 15], 3),
+        )
+        .try_into()
+        .unwrap();
+        let s1: Word<F> = xor(
+            &xor(&rotate_right(w[t - 2], 17), &rotate_right(w[t - 2], 19)),
+            &shift_right(w[t - 2], 10),
+        )
+        .try_into()
+        .unwrap();
+
+        let w_t = sum_mod_32(&[w[t - 16], s0, w[t - 7], s1], cs);
+        w.push(w_t);
+    }
+
+    // Rounds
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+    for t in 0..64 {
+        let sigma_1: Word<F> = xor(
+            &xor(&rotate_right(e, 6), &rotate_right(e, 11)),
+            &rotate_right(e, 25),
+        )
+        .try_into()
+        .unwrap();
+        let ch = ch(e, f, g);
+        let k_t = const_word(u64::from(K[t]), cs);
+        let t1 = sum_mod_32(&[h, sigma_1, ch, k_t, w[t]], cs);
+
+        let sigma_0: Word<F> = xor(
+            &xor(&rotate_right(a, 2), &rotate_right(a, 13)),
+            &rotate_right(a, 22),
+        )
+        .try_into()
+        .unwrap();
+        let maj = maj(a, b, c);
+
+        h = g;
+        g = f;
+        f = e;
+        e = sum_mod_32(&[d, t1], cs);
+        d = c;
+        c = b;
+        b = a;
+        a = sum_mod_32(&[t1, sigma_0, maj], cs);
+    }
+
+    let working = [a, b, c, d, e, f, g, h];
+    let mut out = state;
+    for i in 0..8 {
+        out[i] = sum_mod_32(&[state[i], working[i]], cs);
+    }
+
+    out
+}
+
+// SHA-256 of a byte string, as bits that are little-endian within each byte
+// (the same bit order as `keccak256`).
+// The output bits are in the same order, with the digest bytes in the standard order.
+pub fn sha256<F: PrimeField>(cs: &mut ConstraintSystem<F>, input: &[Wire<F>]) -> [Wire<F>; 256] {
+    assert_eq!(input.len() % 8, 0, "Input must be a whole number of bytes");
+
+    let zero = cs.zero();
+    let one = cs.one();
+
+    let byte_bits =
+        move |byte: u8| (0..8).map(move |i| if byte >> i & 1 == 1 { one } else { zero });
+
+    // Pad with a one bit, zeros, and the input length in bits as a big-endian 64-bit integer
+    let mut padded_input = input.to_vec();
+    padded_input.extend(byte_bits(0x80));
+    while padded_input.len() % BLOCK_LEN != BLOCK_LEN - 64 {
+        padded_input.extend(byte_bits(0));
+    }
+    for byte in (input.len() as u64).to_be_bytes() {
+        padded_input.extend(byte_bits(byte));
+    }
+
+    let mut state = H0.map(|h| const_word(u64::from(h), cs));
+    for block in padded_input.chunks(BLOCK_LEN) {
+        state = compress(state, block, cs);
+    }
+
+    let mut out = [zero; 256];
+    for (i, word) in state.iter().enumerate() {
+        for j in 0..4 {
+            // The most significant byte of each word comes first
+            let byte = &word[(8 * (3 - j))..(8 * (4 - j))];
+            out[(32 * i + 8 * j)..(32 * i + 8 * (j + 1))].copy_from_slice(byte);
+        }
+    }
+
+    out
+}
+
+// HMAC-SHA256 (RFC 2104) of a message with a key, both as byte strings of bits
+// that are little-endian within each byte, like `sha256`.
+// The key bits must be binary.
+pub fn hmac_sha256<F: PrimeField>(
+    cs: &mut ConstraintSystem<F>,
+    key: &[Wire<F>],
+    message: &[Wire<F>],
+) -> [Wire<F>; 256] {
+    assert_eq!(key.len() % 8, 0, "Key must be a whole number of bytes");
+
+    // Keys longer than a block are hashed first, and the key is padded with zeros to a block
+    let mut key = if key.len() > 8 * HMAC_BLOCK_BYTES {
+        sha256(cs, key).to_vec()
+    } else {
+        key.to_vec()
+    };
+    key.resize(8 * HMAC_BLOCK_BYTES, cs.zero());
+
+    let mut inner_input = xor_pad(&key, 0x36, cs);
+    inner_input.extend_from_slice(message);
+    let inner = sha256(cs, &inner_input);
+
+    let mut outer_input = xor_pad(&key, 0x5c, cs);
+    outer_input.extend_from_slice(&inner);
+    sha256(cs, &outer_input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{bytes_to_le_bits, test_var_pub_input};
+
+    type F = ark_secq256k1::Fr;
+
+    #[test]
+    fn test_sha256() {
+        // Known-answer vectors from FIPS 180-2
+        let cases: [(&[u8], &str); 2] = [
+            (
+                b"",
+                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            ),
+            (
+                b"abc",
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            ),
+        ];
+
+        for (message, expected_digest) in cases {
+            let synthesizer = |cs: &mut ConstraintSystem<F>| {
+                let input = cs.alloc_priv_inputs(message.len() * 8);
+                let digest = sha256(cs, &input);
+
+                for bit in digest {
+                    cs.expose_public(bit);
+                }
+            };
+
+            let priv_input = bytes_to_le_bits(message);
+            let pub_input = bytes_to_le_bits(&hex::decode(expected_digest).unwrap());
+
+            test_var_pub_input(synthesizer, &pub_input, &priv_input);
+        }
+    }
+
+    #[test]
+    fn test_hmac_sha256() {
+        let key = b"key";
+        let message = b"The quick brown fox jumps over the lazy dog";
+        // Computed with Python's hmac module
+        let expected_mac = "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8";
+
+        let synthesizer = |cs: &mut ConstraintSystem<F>| {
+            let key = cs.alloc_priv_inputs(key.len() * 8);
+            let message = cs.alloc_priv_inputs(message.len() * 8);
+            let mac = hmac_sha256(cs, &key, &message);
+
+            for bit in mac {
+                cs.expose_public(bit);
+            }
+        };
+
+        let priv_input = [bytes_to_le_bits(key), bytes_to_le_bits(message)].concat();
+        let pub_input = bytes_to_le_bits(&hex::decode(expected_mac).unwrap());
+
+        test_var_pub_input(synthesizer, &pub_input, &priv_input);
+    }
+}
@@ -0,0 +1,292 @@
+use super::bitops::{
+    ch, const_word, maj, rotate_right_64, shift_right_64, sum_mod_64, xor_64, xor_pad,
+};
+use crate::frontend::constraint_system::{ConstraintSystem, Wire};
+use ark_ff::PrimeField;
+
+// A 64-bit word as little-endian bits
+type Word<F> = [Wire<F>; 64];
+
+const BLOCK_LEN: usize = 1024;
+
+// Section 5.3.5 of https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.180-4.pdf
+pub const H0: [u64; 8] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+// Section 4.2.3 of https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.180-4.pdf
+pub const K: [u64; 80] = [
+    0x428a2f98d728ae22,
+    0x7137449123ef65cd,
+    0xb5c0fbcfec4d3b2f,
+    0xe9b5dba58189dbbc,
+    0x3956c25bf348b538,
+    0x59f111f1b605d019,
+    0x923f82a4af194f9b,
+    0xab1c5ed5da6d8118,
+    0xd807aa98a3030242,
+    0x12835b0145706fbe,
+    0x243185be4ee4b28c,
+    0x550c7dc3d5ffb4e2,
+    0x72be5d74f27b896f,
+    0x80deb1fe3b1696b1,
+    0x9bdc06a725c71235,
+    0xc19bf174cf692694,
+    0xe49b69c19ef14ad2,
+    0xefbe4786384f25e3,
+    0x0fc19dc68b8cd5b5,
+    0x240ca1cc77ac9c65,
+    0x2de92c6f592b0275,
+    0x4a7484aa6ea6e483,
+    0x5cb0a9dcbd41fbd4,
+    0x76f988da831153b5,
+    0x983e5152ee66dfab,
+    0xa831c66d2db43210,
+    0xb00327c898fb213f,
+    0xbf597fc7beef0ee4,
+    0xc6e00bf33da88fc2,
+    0xd5a79147930aa725,
+    0x06ca6351e003826f,
+    0x142929670a0e6e70,
+    0x27b70a8546d22ffc,
+    0x2e1b21385c26c926,
+    0x4d2c6dfc5ac42aed,
+    0x53380d139d95b3df,
+    0x650a73548baf63de,
+    0x766a0abb3c77b2a8,
+    0x81c2c92e47edaee6,
+    0x92722c851482353b,
+    0xa2bfe8a14cf10364,
+    0xa81a664bbc423001,
+    0xc24b8b70d0f89791,
+    0xc76c51a30654be30,
+    0xd192e819d6ef5218,
+    0xd69906245565a910,
+    0xf40e35855771202a,
+    0x106aa07032bbd1b8,
+    0x19a4c116b8d2d0c8,
+    0x1e376c085141ab53,
+    0x2748774cdf8eeb99,
+    0x34b0bcb5e19b48a8,
+    0x391c0cb3c5c95a63,
+    0x4ed8aa4ae3418acb,
+    0x5b9cca4f7763e373,
+    0x682e6ff3d6b2b8a3,
+    0x748f82ee5defb2fc,
+    0x78a5636f43172f60,
+    0x84c87814a1f0ab72,
+    0x8cc702081a6439ec,
+    0x90befffa23631e28,
+    0xa4506cebde82bde9,
+    0xbef9a3f7b2c67915,
+    0xc67178f2e372532b,
+    0xca273eceea26619c,
+    0xd186b8c721c0c207,
+    0xeada7dd6cde0eb1e,
+    0xf57d4f7fee6ed178,
+    0x06f067aa72176fba,
+    0x0a637dc5a2c898a6,
+    0x113f9804bef90dae,
+    0x1b710b35131c471b,
+    0x28db77f523047d84,
+    0x32caab7b40c72493,
+    0x3c9ebe0a15c9bebc,
+    0x431d67c49c100d4c,
+    0x4cc5d4becb3e42b6,
+    0x597f299cfc657e2a,
+    0x5fcb6fab3ad6faec,
+    0x6c44198c4a475817,
+];
+
+// The block length of SHA-512 in bytes, for HMAC
+const HMAC_BLOCK_BYTES: usize = 128;
+
+// Apply the compression function to a 1024-bit block,
+// which bits are little-endian within each byte.
+fn compress<F: PrimeField>(
+    state: [Word<F>; 8],
+    block: &[Wire<F>],
+    cs: &mut ConstraintSystem<F>,
+) -> [Word<F>; 8] {
+    // Message schedule.
+    // The words are big-endian, so the first byte of a word holds its most significant bits.
+    let mut w = Vec::with_capacity(80);
+    for j in 0..16 {
+        let word: Word<F> = std::array::from_fn(|k| block[8 * (8 * j + 7 - k / 8) + k % 8]);
+        w.push(word);
+    }
+
+    for t in 16..80 {
+        let s0 = xor_64(
+            xor_64(rotate_right_64(w[t - 15], 1), rotate_right_64(w[t - 15], 8)),
+            shift_right_64(w[t - 15], 7),
+        );
+        let s1 = xor_64(
+            xor_64(rotate_right_64(w[t - 2], 19), rotate_right_64(w[t - 2], 61)),
+            shift_right_64(w[t - 2], 6),
+        );
+
+        let w_t = sum_mod_64(&[w[t - 16], s0, w[t - 7], s1], cs);
+        w.push(w_t);
+    }
+
+    // Rounds
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+    for t in 0..80 {
+        let sigma_1 = xor_64(
+            xor_64(rotate_right_64(e, 14), rotate_right_64(e, 18)),
+            rotate_right_64(e, 41),
+        );
+        let ch = ch(e, f, g);
+        let k_t = const_word(K[t], cs);
+        let t1 = sum_mod_64(&[h, sigma_1, ch, k_t, w[t]], cs);
+
+        let sigma_0 = xor_64(
+            xor_64(rotate_right_64(a, 28), rotate_right_64(a, 34)),
+            rotate_right_64(a, 39),
+        );
+        let maj = maj(a, b, c);
+
+        h = g;
+        g = f;
+        f = e;
+        e = sum_mod_64(&[d, t1], cs);
+        d = c;
+        c = b;
+        b = a;
+        a = sum_mod_64(&[t1, sigma_0, maj], cs);
+    }
+
+    let working = [a, b, c, d, e, f, g, h];
+    let mut out = state;
+    for i in 0..8 {
+        out[i] = sum_mod_64(&[state[i], working[i]], cs);
+    }
+
+    out
+}
+
+// SHA-512 of a byte string, as bits that are little-endian within each byte
+// (the same bit order as `sha256`).
+// The output bits are in the same order, with the digest bytes in the standard order.
+pub fn sha512<F: PrimeField>(cs: &mut ConstraintSystem<F>, input: &[Wire<F>]) -> [Wire<F>; 512] {
+    assert_eq!(input.len() % 8, 0, "Input must be a whole number of bytes");
+
+    let zero = cs.zero();
+    let one = cs.one();
+
+    let byte_bits =
+        move |byte: u8| (0..8).map(move |i| if byte >> i & 1 == 1 { one } else { zero });
+
+    // Pad with a one bit, zeros, and the input length in bits as a big-endian 128-bit integer
+    let mut padded_input = input.to_vec();
+    padded_input.extend(byte_bits(0x80));
+    while padded_input.len() % BLOCK_LEN != BLOCK_LEN - 128 {
+        padded_input.extend(byte_bits(0));
+    }
+    for byte in (input.len() as u128).to_be_bytes() {
+        padded_input.extend(byte_bits(byte));
+    }
+
+    let mut state = H0.map(|h| const_word(h, cs));
+    for block in padded_input.chunks(BLOCK_LEN) {
+        state = compress(state, block, cs);
+    }
+
+    let mut out = [zero; 512];
+    for (i, word) in state.iter().enumerate() {
+        for j in 0..8 {
+            // The most significant byte of each word comes first
+            let byte = &word[(8 * (7 - j))..(8 * (8 - j))];
+            out[(64 * i + 8 * j)..(64 * i + 8 * (j + 1))].copy_from_slice(byte);
+        }
+    }
+
+    out
+}
+
+// HMAC-SHA512 (RFC 2104) of a message with a key, both as byte strings of bits
+// that are little-endian within each byte, like `sha512`.
+// The key bits must be binary.
+pub fn hmac_sha512<F: PrimeField>(
+    cs: &mut ConstraintSystem<F>,
+    key: &[Wire<F>],
+    message: &[Wire<F>],
+) -> [Wire<F>; 512] {
+    assert_eq!(key.len() % 8, 0, "Key must be a whole number of bytes");
+
+    // Keys longer than a block are hashed first, and the key is padded with zeros to a block
+    let mut key = if key.len() > 8 * HMAC_BLOCK_BYTES {
+        sha512(cs, key).to_vec()
+    } else {
+        key.to_vec()
+    };
+    key.resize(8 * HMAC_BLOCK_BYTES, cs.zero());
+
+    let mut inner_input = xor_pad(&key, 0x36, cs);
+    inner_input.extend_from_slice(message);
+    let inner = sha512(cs, &inner_input);
+
+    let mut outer_input = xor_pad(&key, 0x5c, cs);
+    outer_input.extend_from_slice(&inner);
+    sha512(cs, &outer_input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{bytes_to_le_bits, test_var_pub_input};
+
+    type F = ark_secq256k1::Fr;
+
+    #[test]
+    fn test_sha512() {
+        // Known-answer vector from FIPS 180-2
+        let message = b"abc";
+        let expected_digest = "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f";
+
+        let synthesizer = |cs: &mut ConstraintSystem<F>| {
+            let input = cs.alloc_priv_inputs(message.len() * 8);
+            let digest = sha512(cs, &input);
+
+            for bit in digest {
+                cs.expose_public(bit);
+            }
+        };
+
+        let priv_input = bytes_to_le_bits(message);
+        let pub_input = bytes_to_le_bits(&hex::decode(expected_digest).unwrap());
+
+        test_var_pub_input(synthesizer, &pub_input, &priv_input);
+    }
+
+    #[test]
+    fn test_hmac_sha512() {
+        let key = b"key";
+        let message = b"The quick brown fox jumps over the lazy dog";
+        // Computed with Python's hmac module
+        let expected_mac = "b42af09057bac1e2d41708e48a902e09b5ff7f12ab428a4fe86653c73dd248fb82f948a549f7b791a5b41915ee4d1ec3935357e4e2317250d0372afa2ebeeb3a";
+
+        let synthesizer = |cs: &mut ConstraintSystem<F>| {
+            let key = cs.alloc_priv_inputs(key.len() * 8);
+            let message = cs.alloc_priv_inputs(message.len() * 8);
+            let mac = hmac_sha512(cs, &key, &message);
+
+            for bit in mac {
+                cs.expose_public(bit);
+            }
+        };
+
+        let priv_input = [bytes_to_le_bits(key), bytes_to_le_bits(message)].concat();
+        let pub_input = bytes_to_le_bits(&hex::decode(expected_mac).unwrap());
+
+        test_var_pub_input(synthesizer, &pub_input, &priv_input);
+    }
+}
@@ -0,0 +1,72 @@
+use crate::frontend::constraint_system::{ConstraintSystem, Wire};
+use ark_ff::PrimeField;
+
+// Assert that the shares `(x_i, y_i)` reconstruct to `secret`,
+// by Lagrange interpolation of the shares at zero:
+// secret = sum_i y_i * prod_{j != i} x_j / (x_j - x_i)
+// The x-coordinates must be distinct, otherwise the circuit is unsatisfiable.
+pub fn verify_shamir_reconstruct<F: PrimeField>(
+    shares: &[(Wire<F>, Wire<F>)],
+    secret: Wire<F>,
+    cs: &mut ConstraintSystem<F>,
+) {
+    assert!(!shares.is_empty(), "At least one share is required");
+
+    let mut terms = Vec::with_capacity(shares.len());
+
+    for (i, (x_i, y_i)) in shares.iter().enumerate() {
+        let mut numerator = cs.one();
+        let mut denominator = cs.one();
+
+        for (j, (x_j, _)) in shares.iter().enumerate() {
+            if i != j {
+                numerator = numerator * *x_j;
+                denominator = denominator * (*x_j - *x_i);
+            }
+        }
+
+        // `div` constrains the denominator to be non-zero
+        let lagrange_coeff = numerator / denominator;
+        terms.push((*y_i * lagrange_coeff, true));
+    }
+
+    let reconstructed = cs.sum(&terms);
+    cs.assert_equal(reconstructed, secret, "Shares don't reconstruct the secret");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_var_pub_input;
+
+    type F = ark_secq256k1::Fr;
+
+    #[test]
+    fn test_verify_shamir_reconstruct() {
+        // Shares of the degree-1 polynomial p(x) = 42 + 7x (i.e. 2-of-3)
+        let secret = F::from(42u32);
+        let shares = [1u32, 2, 3].map(|x| (F::from(x), secret + F::from(7 * x)));
+
+        let subsets: [&[usize]; 4] = [&[0, 1], &[0, 2], &[1, 2], &[0, 1, 2]];
+
+        for subset in subsets {
+            let num_shares = subset.len();
+            let synthesizer = |cs: &mut ConstraintSystem<F>| {
+                let secret = cs.alloc_pub_input();
+                let shares = (0..num_shares)
+                    .map(|_| (cs.alloc_priv_input(), cs.alloc_priv_input()))
+                    .collect::<Vec<(Wire<F>, Wire<F>)>>();
+
+                verify_shamir_reconstruct(&shares, secret, cs);
+            };
+
+            let priv_input = subset
+                .iter()
+                .flat_map(|i| [shares[*i].0, shares[*i].1])
+                .collect::<Vec<F>>();
+            let pub_input = [secret];
+
+            test_var_pub_input(synthesizer, &pub_input, &priv_input);
+        }
+    }
+}
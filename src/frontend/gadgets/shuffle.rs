@@ -0,0 +1,149 @@
+use super::bitops::range_check;
+use crate::frontend::constraint_system::{ConstraintSystem, Wire};
+use ark_ff::PrimeField;
+
+// The random values of the shuffle are less than 2^SHUFFLE_RAND_BITS
+pub const SHUFFLE_RAND_BITS: usize = 64;
+
+// Assert that `output` is the Fisher-Yates shuffle of `input` with the given randomness.
+// For i = n - 1 down to 1, the shuffle swaps the elements at i and j = r mod (i + 1),
+// where r = randomness[n - 1 - i] is less than 2^SHUFFLE_RAND_BITS.
+// The randomness should be derived from a seed (e.g. with `keccak_prf`),
+// and the reduction mod (i + 1) is slightly biased unless i + 1 is a power of two.
+// The swaps only move elements around, so the output is always a permutation of the input.
+pub fn verify_shuffle<F: PrimeField>(
+    cs: &mut ConstraintSystem<F>,
+    input: &[Wire<F>],
+    output: &[Wire<F>],
+    randomness: &[Wire<F>],
+) {
+    let n = input.len();
+    assert_eq!(output.len(), n, "The arrays must have the same length");
+    assert_eq!(
+        randomness.len(),
+        n.saturating_sub(1),
+        "One random value is needed per swap"
+    );
+
+    let one = cs.one();
+    let mut state = input.to_vec();
+
+    for (step, r) in randomness.iter().enumerate() {
+        let i = n - 1 - step;
+        let modulus = (i + 1) as u64;
+
+        // r = q * (i + 1) + j with q < 2^SHUFFLE_RAND_BITS and j <= i.
+        // Both sides are far smaller than the field modulus, so the equation doesn't wrap around.
+        range_check(*r, SHUFFLE_RAND_BITS, cs);
+        let r_val = r.val(cs).map(|val| val.into_bigint().as_ref()[0]);
+
+        let q = cs.alloc_var(F::from(r_val.map_or(0, |r| r / modulus)));
+        range_check(q, SHUFFLE_RAND_BITS, cs);
+
+        // One-hot encoding of j
+        let j_val = r_val.map(|r| r % modulus);
+        let is_j = (0..=i)
+            .map(|k| cs.alloc_var(F::from(j_val == Some(k as u64))))
+            .collect::<Vec<Wire<F>>>();
+
+        for selector in &is_j {
+            cs.assert_equal(*selector * *selector, *selector, "selector is not binary");
+        }
+
+        let num_selected = cs.sum(&is_j.iter().map(|s| (*s, true)).collect::<Vec<_>>());
+        cs.assert_equal(num_selected, one, "exactly one index must be selected");
+
+        let mut j_terms = Vec::with_capacity(i);
+        for (k, selector) in is_j.iter().enumerate().skip(1) {
+            j_terms.push((cs.mul_const(*selector, F::from(k as u64)), true));
+        }
+        let j = cs.sum(&j_terms);
+
+        let q_times_modulus = cs.mul_const(q, F::from(modulus));
+        let recovered_r = q_times_modulus + j;
+        cs.assert_equal(*r, recovered_r, "invalid swap index");
+
+        // Swap the elements at i and j
+        let mut selected_terms = Vec::with_capacity(i + 1);
+        for (selector, elem) in is_j.iter().zip(state.iter()) {
+            selected_terms.push((*selector * *elem, true));
+        }
+        let selected = cs.sum(&selected_terms);
+
+        let last = state[i];
+        for (elem, selector) in state[..i].iter_mut().zip(is_j.iter()) {
+            *elem = cs.mul_add(*selector, last - *elem, *elem);
+        }
+        state[i] = selected;
+    }
+
+    for (shuffled, out) in state.iter().zip(output.iter()) {
+        cs.assert_equal(
+            *shuffled,
+            *out,
+            "The output is not the shuffle of the input",
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_var_pub_input;
+
+    type F = ark_secq256k1::Fr;
+
+    const LEN: usize = 5;
+
+    fn shuffle_circuit(cs: &mut ConstraintSystem<F>) {
+        let output = cs.alloc_pub_inputs(LEN);
+        let input = cs.alloc_priv_inputs(LEN);
+        let randomness = cs.alloc_priv_inputs(LEN - 1);
+
+        verify_shuffle(cs, &input, &output, &randomness);
+    }
+
+    fn fisher_yates(input: &[u64], randomness: &[u64]) -> Vec<u64> {
+        let mut out = input.to_vec();
+        for (step, r) in randomness.iter().enumerate() {
+            let i = input.len() - 1 - step;
+            out.swap(i, (r % (i as u64 + 1)) as usize);
+        }
+        out
+    }
+
+    // The shuffled output, and the input and randomness of a shuffle
+    fn shuffle() -> (Vec<F>, Vec<F>) {
+        let input = [10u64, 20, 30, 40, 50];
+        let randomness = [7u64, 123456, (1 << 40) + 5, u64::MAX];
+
+        let output = fisher_yates(&input, &randomness);
+        assert_ne!(output, input);
+
+        let pub_input = output.iter().map(|x| F::from(*x)).collect::<Vec<F>>();
+        let priv_input = input
+            .iter()
+            .chain(randomness.iter())
+            .map(|x| F::from(*x))
+            .collect::<Vec<F>>();
+
+        (pub_input, priv_input)
+    }
+
+    #[test]
+    fn test_verify_shuffle() {
+        let (pub_input, priv_input) = shuffle();
+        test_var_pub_input(shuffle_circuit, &pub_input, &priv_input);
+    }
+
+    // A different permutation of the input than the one given by the randomness
+    #[test]
+    #[should_panic(expected = "The output is not the shuffle of the input")]
+    fn test_verify_shuffle_wrong_output() {
+        let (mut pub_input, priv_input) = shuffle();
+        pub_input.swap(0, 1);
+
+        let mut cs = ConstraintSystem::new();
+        cs.gen_witness(shuffle_circuit, &pub_input, &priv_input);
+    }
+}
@@ -0,0 +1,76 @@
+use crate::frontend::constraint_system::{ConstraintSystem, Wire};
+use ark_ff::PrimeField;
+
+// Returns a square root of `a`, and a binary wire that is 1 if the square root exists.
+// If `a` is not a quadratic residue, the returned root is the square root of `a * g`,
+// where `g` is the multiplicative generator of the field (which is a non-residue).
+// This proves that `a` doesn't have a square root.
+pub fn sqrt<F: PrimeField>(a: Wire<F>, cs: &mut ConstraintSystem<F>) -> (Wire<F>, Wire<F>) {
+    // Compute the square root as a witness hint
+    let (root_val, exists_val) = match a.val(cs) {
+        Some(a_val) => match a_val.sqrt() {
+            Some(root) => (root, true),
+            None => ((a_val * F::GENERATOR).sqrt().unwrap(), false),
+        },
+        None => (F::ZERO, false),
+    };
+
+    let root = cs.alloc_var(root_val);
+    let exists = cs.alloc_var(F::from(exists_val));
+
+    cs.assert_equal(exists * exists, exists, "sqrt: exists is not binary");
+
+    // Zero always has a square root
+    let a_is_zero = a.is_zero();
+    cs.assert_zero(a_is_zero * !exists);
+
+    // root * root = a if the square root exists, and a * g otherwise
+    let a_non_residue = cs.mul_const(a, F::GENERATOR);
+    let root_squared = cs.if_then(exists, a).else_then(a_non_residue);
+    cs.assert_equal(root * root, root_squared, "sqrt: invalid square root");
+
+    (root, exists)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_var_pub_input;
+    use ark_ff::{FftField, Field};
+
+    type F = ark_secq256k1::Fr;
+
+    #[test]
+    fn test_sqrt() {
+        let synthesizer = |cs: &mut ConstraintSystem<F>| {
+            let a = cs.alloc_priv_input();
+
+            let (root, exists) = sqrt(a, cs);
+
+            cs.expose_public(root * root);
+            cs.expose_public(exists);
+        };
+
+        let cases = [
+            F::ZERO,
+            F::ONE,
+            F::from(4u32),
+            F::from(123456789u32),
+            F::GENERATOR,
+            F::GENERATOR * F::from(9u32),
+        ];
+
+        for a in cases {
+            let (root_squared, exists) = if a.sqrt().is_some() {
+                (a, F::ONE)
+            } else {
+                (a * F::GENERATOR, F::ZERO)
+            };
+
+            let priv_input = [a];
+            let pub_input = [root_squared, exists];
+
+            test_var_pub_input(synthesizer, &pub_input, &priv_input);
+        }
+    }
+}
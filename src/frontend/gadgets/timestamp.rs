@@ -0,0 +1,78 @@
+use super::bitops::range_check;
+use crate::frontend::constraint_system::{ConstraintSystem, Wire};
+use ark_ff::PrimeField;
+
+const TIMESTAMP_BITS: usize = 64;
+
+// Assert that min <= timestamp <= max, where all three are constrained to be less than 2^64
+// (e.g. the timestamps of the first and last blocks of a range).
+pub fn assert_timestamp_in_range<F: PrimeField>(
+    cs: &mut ConstraintSystem<F>,
+    timestamp: Wire<F>,
+    min: Wire<F>,
+    max: Wire<F>,
+) {
+    range_check(timestamp, TIMESTAMP_BITS, cs);
+    range_check(min, TIMESTAMP_BITS, cs);
+    range_check(max, TIMESTAMP_BITS, cs);
+
+    // The differences wrap around the modulus, and fail the range checks,
+    // if the timestamp is out of range.
+    range_check(timestamp - min, TIMESTAMP_BITS, cs);
+    range_check(max - timestamp, TIMESTAMP_BITS, cs);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_var_pub_input;
+
+    type F = ark_secq256k1::Fr;
+
+    const MIN: u64 = 1_700_000_000;
+    const MAX: u64 = 1_700_086_400;
+
+    fn timestamp_circuit(cs: &mut ConstraintSystem<F>) {
+        let min = cs.alloc_pub_input();
+        let max = cs.alloc_pub_input();
+        let timestamp = cs.alloc_priv_input();
+
+        assert_timestamp_in_range(cs, timestamp, min, max);
+    }
+
+    #[test]
+    fn test_assert_timestamp_in_range() {
+        let pub_input = [F::from(MIN), F::from(MAX)];
+
+        for timestamp in [MIN, MIN + 3600, MAX] {
+            test_var_pub_input(timestamp_circuit, &pub_input, &[F::from(timestamp)]);
+        }
+    }
+
+    fn gen_witness(timestamp: F) {
+        let mut cs = ConstraintSystem::new();
+        cs.gen_witness(
+            timestamp_circuit,
+            &[F::from(MIN), F::from(MAX)],
+            &[timestamp],
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "value is out of range")]
+    fn test_assert_timestamp_in_range_before_min() {
+        gen_witness(F::from(MIN - 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "value is out of range")]
+    fn test_assert_timestamp_in_range_after_max() {
+        gen_witness(F::from(MAX + 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "value is out of range")]
+    fn test_assert_timestamp_in_range_out_of_64_bits() {
+        gen_witness(-F::from(1u32));
+    }
+}
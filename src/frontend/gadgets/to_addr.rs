@@ -1,8 +1,8 @@
 use super::bitops::form_le_bits;
-use crate::frontend::constraint_system::Wire;
-use crate::frontend::gadgets::bitops::{not_a_and_b_64, rotate_left_64, xor_64};
+use super::pack::from_bytes_be;
+use crate::frontend::constraint_system::{ConstraintSystem, Wire};
+use crate::frontend::gadgets::bitops::{not_a_and_b_64, rotate_left_64, xor, xor_64};
 use ark_ff::PrimeField;
-use std::ops::Add;
 
 // Keccak256 parameters in bits
 pub const ROUNDS: usize = 24;
@@ -48,25 +48,23 @@ pub const RC: [u64; ROUNDS] = [
     0x8000000080008008u64,
 ];
 
-pub fn to_addr<F: PrimeField>(input: [Wire<F>; 512]) -> Wire<F> {
-    let cs = input[0].cs();
-    let zero = cs.zero();
-    let one = cs.one();
-
-    // Pad
-    let mut pad = [zero; RATE - 512];
-    pad[0] = cs.one();
-    pad[pad.len() - 1] = cs.one();
-
-    let mut padded_input = [zero; 1600];
-    padded_input[..512].copy_from_slice(&input);
-    padded_input[512..(512 + pad.len())].copy_from_slice(&pad);
+// XOR each lane of `a` with the lane of `b` at the same position.
+// The same gates as `xor_64` lane by lane, but the witness of all the lanes is assigned at once.
+fn xor_lanes<F: PrimeField>(a: &[[Wire<F>; 64]], b: &[[Wire<F>; 64]]) -> Vec<[Wire<F>; 64]> {
+    assert_eq!(a.len(), b.len());
 
-    let mut state = [[zero; 64]; 25];
+    xor(&a.concat(), &b.concat())
+        .chunks(64)
+        .map(|lane| lane.try_into().unwrap())
+        .collect()
+}
 
-    for i in 0..25 {
-        state[i] = padded_input[i * 64..(i + 1) * 64].try_into().unwrap();
-    }
+// The Keccak-f[1600] permutation over a state of 25 lanes,
+// where each lane is 64 little-endian bits.
+fn keccak_f<F: PrimeField>(state: &mut [[Wire<F>; 64]; 25]) {
+    let cs = state[0][0].cs();
+    let zero = cs.zero();
+    let one = cs.one();
 
     // Assign the round constants
     let rc: [[Wire<F>; 64]; 24] = RC.map(|c| {
@@ -84,24 +82,23 @@ pub fn to_addr<F: PrimeField>(input: [Wire<F>; 512]) -> Wire<F> {
 
     for i in 0..ROUNDS {
         // Theta
+        // The lanes of each step are XORed in a single batch
         let mut c = [[zero; 64]; 5];
-        let mut d = [[zero; 64]; 5];
 
         for y in 0..5 {
-            for x in 0..5 {
-                c[x] = xor_64(c[x], state[x + y * 5]);
-            }
+            let row = xor_lanes(&c, &state[(y * 5)..((y + 1) * 5)]);
+            c.copy_from_slice(&row);
         }
 
-        for x in 0..5 {
-            d[x] = xor_64(c[(x + 4) % 5], rotate_left_64(c[(x + 1) % 5], 1));
-        }
+        let c_prev = (0..5).map(|x| c[(x + 4) % 5]).collect::<Vec<_>>();
+        let c_next = (0..5)
+            .map(|x| rotate_left_64(c[(x + 1) % 5], 1))
+            .collect::<Vec<_>>();
+        let d = xor_lanes(&c_prev, &c_next);
 
-        for y in 0..5 {
-            for x in 0..5 {
-                state[x + y * 5] = xor_64(state[x + y * 5], d[x]);
-            }
-        }
+        let d = (0..25).map(|i| d[i % 5]).collect::<Vec<_>>();
+        let theta = xor_lanes(&state[..], &d);
+        state.copy_from_slice(&theta);
 
         // ############################################
         // Rho
@@ -154,75 +151,298 @@ pub fn to_addr<F: PrimeField>(input: [Wire<F>; 512]) -> Wire<F> {
 
         state[0] = xor_64(state[0], rc[i]);
     }
+}
+
+// Keccak256 of an arbitrary, non-empty number of bits, which are little-endian within each byte.
+// The output bits are in the same order as the input bits.
+pub fn keccak256<F: PrimeField>(input: &[Wire<F>]) -> [Wire<F>; 256] {
+    let cs = input[0].cs();
+    keccak256_var(cs, input)
+}
+
+// Keccak256 of any number of bits, including none.
+// The input is absorbed in RATE-bit blocks after pad10*1 padding,
+// which adds a full block if the input leaves no room for the two padding bits.
+pub fn keccak256_var<F: PrimeField>(
+    cs: &mut ConstraintSystem<F>,
+    input: &[Wire<F>],
+) -> [Wire<F>; 256] {
+    let zero = cs.zero();
+    let one = cs.one();
+
+    // Pad
+    let mut padded_input = input.to_vec();
+    padded_input.push(one);
+    while padded_input.len() % RATE != RATE - 1 {
+        padded_input.push(zero);
+    }
+    padded_input.push(one);
 
-    let mut address_bits = vec![];
-    let state_1 = state[1][32..].to_vec();
-    let state_2 = state[2].to_vec();
-    let state_3 = state[3].to_vec();
+    keccak256_padded(&padded_input)
+}
 
-    address_bits.extend_from_slice(&state_1);
-    address_bits.extend_from_slice(&state_2);
-    address_bits.extend_from_slice(&state_3);
+// Keccak256 of an input that is already padded to a multiple of RATE bits.
+pub(crate) fn keccak256_padded<F: PrimeField>(padded_input: &[Wire<F>]) -> [Wire<F>; 256] {
+    assert_eq!(padded_input.len() % RATE, 0, "Input is not padded");
 
-    let mut out = cs.zero();
-    let mut pow = F::ONE;
-    for (i, bits) in address_bits.chunks(8).rev().enumerate() {
-        let byte = form_le_bits(&bits);
-        let term = cs.mul_const(byte, pow);
-        out = out.add(term);
+    let cs = padded_input[0].cs();
+    let zero = cs.zero();
+
+    let mut state = [[zero; 64]; 25];
 
-        pow = F::from(2u32).pow(&[8 * ((i + 1) as u64)]);
+    for (i, block) in padded_input.chunks(RATE).enumerate() {
+        for (j, lane) in block.chunks(64).enumerate() {
+            let lane: [Wire<F>; 64] = lane.try_into().unwrap();
+
+            // The state is all zeros before absorbing the first block
+            state[j] = if i == 0 { lane } else { xor_64(state[j], lane) };
+        }
+
+        keccak_f(&mut state);
+    }
+
+    let mut out = [zero; 256];
+    for i in 0..4 {
+        out[(i * 64)..((i + 1) * 64)].copy_from_slice(&state[i]);
     }
 
     out
 }
 
+// Keccak256 of the first `len` bytes of `input`, where `len` is only known at proving time
+// (e.g. the length of a trie node), and is at most the number of bytes of `input`.
+// The input bits are little-endian within each byte, and the bytes from `len` on must be zero.
+// Every block that a prefix of the input may need is absorbed,
+// and the state after the last block of the padded prefix is selected.
+pub(crate) fn keccak256_prefix<F: PrimeField>(
+    cs: &mut ConstraintSystem<F>,
+    input: &[Wire<F>],
+    len: Wire<F>,
+) -> [Wire<F>; 256] {
+    assert_eq!(input.len() % 8, 0, "Input must be a whole number of bytes");
+
+    let zero = cs.zero();
+    let one = cs.one();
+
+    let num_bytes = input.len() / 8;
+    let rate_bytes = RATE / 8;
+    let num_blocks = num_bytes / rate_bytes + 1;
+
+    // is_len[i] is 1 iff i == len, for i up to the number of input bytes
+    let is_len = (0..=num_bytes)
+        .map(|i| {
+            let i = cs.alloc_const(F::from(i as u64));
+            cs.is_equal(len, i)
+        })
+        .collect::<Vec<Wire<F>>>();
+    let is_in_range = cs.sum(&is_len.iter().map(|w| (*w, true)).collect::<Vec<_>>());
+    cs.assert_equal(is_in_range, one, "The length is out of range");
+
+    // The bytes from len on are zero
+    let mut is_past_len = zero;
+    for (i, byte) in input.chunks(8).enumerate() {
+        is_past_len = is_past_len + is_len[i];
+        for bit in byte {
+            cs.assert_equal(
+                is_past_len * *bit,
+                zero,
+                "The bytes past the length must be zero",
+            );
+        }
+    }
+
+    // Pad: set the first bit of the byte at len,
+    // and the last bit of the last byte of the block that holds it
+    let mut padded_input = input.to_vec();
+    padded_input.resize(num_blocks * RATE, zero);
+    for (i, is_len) in is_len.iter().enumerate() {
+        padded_input[8 * i] = padded_input[8 * i] + *is_len;
+    }
+
+    let is_last_block = (0..num_blocks)
+        .map(|k| {
+            let last = std::cmp::min((k + 1) * rate_bytes, num_bytes + 1);
+            let terms = is_len[(k * rate_bytes)..last]
+                .iter()
+                .map(|w| (*w, true))
+                .collect::<Vec<_>>();
+            cs.sum(&terms)
+        })
+        .collect::<Vec<Wire<F>>>();
+
+    for (k, is_last) in is_last_block.iter().enumerate() {
+        let last_bit = (k + 1) * RATE - 1;
+        padded_input[last_bit] = padded_input[last_bit] + *is_last;
+    }
+
+    // Absorb the blocks, and select the state after the last one
+    let mut state = [[zero; 64]; 25];
+    let mut out = [zero; 256];
+    for (k, block) in padded_input.chunks(RATE).enumerate() {
+        for (j, lane) in block.chunks(64).enumerate() {
+            let lane: [Wire<F>; 64] = lane.try_into().unwrap();
+            state[j] = if k == 0 { lane } else { xor_64(state[j], lane) };
+        }
+
+        keccak_f(&mut state);
+
+        for (i, out_bit) in out.iter_mut().enumerate() {
+            *out_bit = cs.mul_add(is_last_block[k], state[i / 64][i % 64], *out_bit);
+        }
+    }
+
+    out
+}
+
+pub fn to_addr<F: PrimeField>(input: [Wire<F>; 512]) -> Wire<F> {
+    let digest = keccak256(&input);
+    pack_address(&digest)
+}
+
+// Pack the last 20 bytes of a Keccak256 digest into a big-endian address
+pub(crate) fn pack_address<F: PrimeField>(digest: &[Wire<F>; 256]) -> Wire<F> {
+    let address_bytes = digest[96..]
+        .chunks(8)
+        .map(form_le_bits)
+        .collect::<Vec<Wire<F>>>();
+
+    from_bytes_be(&address_bytes)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{frontend::constraint_system::ConstraintSystem, test_var_pub_input};
-    use ark_ff::{Field, PrimeField};
+    use crate::eth_utils::address_from_digest;
+    use crate::{
+        bytes_to_le_bits, frontend::constraint_system::ConstraintSystem, test_var_pub_input,
+    };
+    use ark_ff::PrimeField;
     use num_bigint::BigUint;
     type F = ark_secq256k1::Fr;
 
     fn to_addr_circuit<F: PrimeField>(cs: &mut ConstraintSystem<F>) {
-        let pub_key_bits = cs.alloc_priv_inputs(512);
+        let pub_key_bits = cs.alloc_priv_inputs_arr::<512>();
 
-        let addr = to_addr(pub_key_bits.try_into().unwrap());
+        let addr = to_addr(pub_key_bits);
         cs.expose_public(addr);
     }
 
-    #[test]
-    fn test_to_addr() {
-        // Public key which underlies dantehrani.eth
-        let pub_key_str = "765b012d6340fd3baf3068e3e118a68a559b832af2d9ddd05585fedcf9f9c2a95a65f71708281d9e1517e28c3643fa932d7675a233d8cc4edc3440c10684cd95";
-        let pub_key_bytes = hex::decode(pub_key_str).unwrap();
-
-        let expected_address_str = "400ea6522867456e988235675b9cb5b1cf5b79c8";
-        let expected_address = hex::decode(expected_address_str).unwrap();
-
-        let pub_key_bits = pub_key_bytes
-            .iter()
-            .map(|b| {
-                // Little-endian bits
-                let mut bits = Vec::with_capacity(8);
-                for i in 0..8 {
-                    bits.push(if (*b >> i) & 1 == 1 { F::ONE } else { F::ZERO });
-                }
+    // Public key which underlies dantehrani.eth, and its address
+    const PUB_KEY: &str = "765b012d6340fd3baf3068e3e118a68a559b832af2d9ddd05585fedcf9f9c2a95a65f71708281d9e1517e28c3643fa932d7675a233d8cc4edc3440c10684cd95";
+    const ADDRESS: &str = "400ea6522867456e988235675b9cb5b1cf5b79c8";
 
-                bits
-            })
-            .flatten()
-            .collect::<Vec<F>>();
+    // Returns the bits of the public key which underlies dantehrani.eth,
+    // and the address of the public key.
+    fn test_pub_key_and_addr() -> (Vec<F>, F) {
+        let pub_key_bits = bytes_to_le_bits(&hex::decode(PUB_KEY).unwrap());
+        let addr = F::from(BigUint::from_bytes_be(&hex::decode(ADDRESS).unwrap()));
 
+        (pub_key_bits, addr)
+    }
+
+    #[test]
+    fn test_to_addr() {
         let synthesizer = |cs: &mut ConstraintSystem<F>| {
             to_addr_circuit(cs);
         };
 
+        let (pub_key_bits, addr) = test_pub_key_and_addr();
+
         let priv_input = pub_key_bits;
-        let addr = F::from(BigUint::from_bytes_be(&expected_address));
         let pub_input = [addr];
 
         test_var_pub_input(synthesizer, &pub_input, &priv_input);
     }
+
+    #[test]
+    fn test_address_from_digest() {
+        // Keccak256 digest of the public key
+        let digest_str = "4651342b9aff4aa8a52e3ab7400ea6522867456e988235675b9cb5b1cf5b79c8";
+        let digest: [u8; 32] = hex::decode(digest_str).unwrap().try_into().unwrap();
+
+        let (_, addr) = test_pub_key_and_addr();
+        assert_eq!(address_from_digest::<F>(&digest), addr);
+    }
+
+    #[test]
+    fn test_to_addr_dry_run() {
+        let synthesizer = |cs: &mut ConstraintSystem<F>| {
+            to_addr_circuit(cs);
+        };
+
+        let mut cs = ConstraintSystem::new();
+        cs.set_constraints(&synthesizer);
+
+        let mut dry_run_cs = ConstraintSystem::dry_run();
+        dry_run_cs.set_constraints(&synthesizer);
+
+        assert_eq!(dry_run_cs.num_constraints, cs.num_constraints);
+        assert_eq!(dry_run_cs.num_vars(), cs.num_vars());
+    }
+
+    #[test]
+    fn test_keccak256() {
+        let message = b"hello";
+        // ethers::utils::keccak256(b"hello")
+        let expected_digest =
+            hex::decode("1c8aff950685c2ed4bc3174f3472287b56d9517b9c948127319a09a7a36deac8")
+                .unwrap();
+
+        let synthesizer = |cs: &mut ConstraintSystem<F>| {
+            let message_bits = cs.alloc_priv_inputs(message.len() * 8);
+            let digest = keccak256(&message_bits);
+
+            for bit in digest {
+                cs.expose_public(bit);
+            }
+        };
+
+        let priv_input = bytes_to_le_bits(message);
+        let pub_input = bytes_to_le_bits(&expected_digest);
+
+        test_var_pub_input(synthesizer, &pub_input, &priv_input);
+    }
+
+    #[test]
+    fn test_keccak256_var() {
+        // Digests of the first `len` bits of the bytes (i * 31 + 7) mod 256,
+        // computed with a reference implementation of Keccak256
+        let cases = [
+            (
+                0,
+                "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470",
+            ),
+            (
+                RATE,
+                "eaccfc5aa7bf6bf1941809ef7cc9ee6a2fa306a7dd1de3f2e8504849b0a5e3c4",
+            ),
+            (
+                RATE + 1,
+                "df6f23fe2e42deb8801b81f3e22afce00c1d8f220f72e60c684217791050f7b5",
+            ),
+            (
+                2 * RATE,
+                "c62d6a60780d4e03408834062e58004a549cff1c7487c0b9a130810621b0fcae",
+            ),
+        ];
+
+        for (len, expected_digest) in cases {
+            let input_bits = (0..len)
+                .map(|i| F::from(((i / 8 * 31 + 7) % 256) as u64 >> (i % 8) & 1))
+                .collect::<Vec<F>>();
+
+            let expected_bits = bytes_to_le_bits(&hex::decode(expected_digest).unwrap());
+
+            let synthesizer = |cs: &mut ConstraintSystem<F>| {
+                let input = cs.alloc_priv_inputs(len);
+                let digest = keccak256_var(cs, &input);
+
+                for bit in digest {
+                    cs.expose_public(bit);
+                }
+            };
+
+            test_var_pub_input(synthesizer, &expected_bits, &input_bits);
+        }
+    }
 }
@@ -4,9 +4,10 @@ use ark_ff::Field;
 use super::poseidon::poseidon::PoseidonChip;
 
 const ARTY: usize = 2;
-const SPONGE_WIDTH: usize = ARTY + 1; // The sponge capacity is one, so the width is arity + 1
+pub(crate) const SPONGE_WIDTH: usize = ARTY + 1; // The sponge capacity is one, so the width is arity + 1
 
-fn hash<F: Field>(
+// Poseidon hash of two field elements
+pub(crate) fn hash<F: Field>(
     left: Wire<F>,
     right: Wire<F>,
     poseidon: PoseidonChip<F, SPONGE_WIDTH>,
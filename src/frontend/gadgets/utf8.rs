@@ -0,0 +1,179 @@
+use super::bitops::byte_to_le_bits;
+use crate::frontend::constraint_system::{ConstraintSystem, Wire};
+use ark_ff::PrimeField;
+
+// Assert that `bytes` is a well-formed UTF-8 string (Table 3-7 of the Unicode standard),
+// i.e. that every sequence has the right number of continuation bytes,
+// and that there are no overlong encodings, surrogates, or code points above U+10FFFF.
+// All the bytes are part of the string, so the string can't be padded.
+pub fn assert_valid_utf8<F: PrimeField>(cs: &mut ConstraintSystem<F>, bytes: &[Wire<F>]) {
+    let zero = cs.zero();
+    let one = cs.one();
+
+    // The number of continuation bytes still expected after the bytes so far
+    let mut remaining = zero;
+
+    // Whether the previous byte is a lead byte that restricts the range of the next byte
+    let mut prev_lead: Option<[Wire<F>; 4]> = None;
+
+    for byte in bytes {
+        let bits = byte_to_le_bits(*byte, cs);
+
+        let is_ascii = !bits[7];
+        let has_prefix_2 = bits[7] & bits[6];
+        let has_prefix_3 = has_prefix_2 & bits[5];
+        let has_prefix_4 = has_prefix_3 & bits[4];
+
+        // 10xxxxxx, 110xxxxx, 1110xxxx and 11110xxx
+        let is_cont = bits[7] & !bits[6];
+        let is_lead_2 = has_prefix_2 & !bits[5];
+        let is_lead_3 = has_prefix_3 & !bits[4];
+        let is_lead_4 = has_prefix_4 & !bits[3];
+
+        // The classes are disjoint, and the bytes 0xf8 to 0xff are in none of them
+        let num_classes = cs.sum(&[
+            (is_ascii, true),
+            (is_cont, true),
+            (is_lead_2, true),
+            (is_lead_3, true),
+            (is_lead_4, true),
+        ]);
+        cs.assert_equal(num_classes, one, "invalid UTF-8 byte");
+
+        // A continuation byte is expected if and only if the previous sequence is incomplete
+        let is_expected = !remaining.is_zero();
+        cs.assert_equal(is_cont, is_expected, "malformed UTF-8 sequence");
+
+        let is_byte = |cs: &mut ConstraintSystem<F>, c: u32| {
+            let c = cs.alloc_const(F::from(c));
+            byte.is_equal(c)
+        };
+
+        // Overlong two-byte sequences, and code points above U+10FFFF
+        for c in [0xc0, 0xc1, 0xf5, 0xf6, 0xf7] {
+            let is_invalid = is_byte(cs, c);
+            cs.assert_equal(is_invalid, zero, "invalid UTF-8 lead byte");
+        }
+
+        // The range of the byte after 0xe0, 0xed, 0xf0 and 0xf4
+        if let Some([after_e0, after_ed, after_f0, after_f4]) = prev_lead {
+            let at_least_0x90 = bits[5] | bits[4];
+
+            // 0xa0 to 0xbf after 0xe0 (no overlong encodings)
+            cs.assert_equal(after_e0 * !bits[5], zero, "overlong UTF-8 sequence");
+            // 0x80 to 0x9f after 0xed (no surrogates)
+            cs.assert_equal(after_ed * bits[5], zero, "UTF-8 encoded surrogate");
+            // 0x90 to 0xbf after 0xf0 (no overlong encodings)
+            cs.assert_equal(after_f0 * !at_least_0x90, zero, "overlong UTF-8 sequence");
+            // 0x80 to 0x8f after 0xf4 (up to U+10FFFF)
+            cs.assert_equal(after_f4 * at_least_0x90, zero, "UTF-8 code point too large");
+        }
+
+        prev_lead = Some([
+            is_byte(cs, 0xe0),
+            is_byte(cs, 0xed),
+            is_byte(cs, 0xf0),
+            is_byte(cs, 0xf4),
+        ]);
+
+        // A lead byte sets the number of continuation bytes to follow,
+        // and each continuation byte decrements it.
+        let decremented = cs.sub_const(remaining, F::ONE);
+        let lead_3_remaining = cs.mul_const(is_lead_3, F::from(2u32));
+        let lead_4_remaining = cs.mul_const(is_lead_4, F::from(3u32));
+        let cont_remaining = is_cont * decremented;
+        remaining = cs.sum(&[
+            (cont_remaining, true),
+            (is_lead_2, true),
+            (lead_3_remaining, true),
+            (lead_4_remaining, true),
+        ]);
+    }
+
+    // The last sequence must be complete
+    cs.assert_equal(remaining, zero, "truncated UTF-8 sequence");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_var_pub_input;
+
+    type F = ark_secq256k1::Fr;
+
+    fn utf8_circuit(len: usize) -> impl Fn(&mut ConstraintSystem<F>) {
+        move |cs: &mut ConstraintSystem<F>| {
+            let bytes = cs.alloc_priv_inputs(len);
+            assert_valid_utf8(cs, &bytes);
+        }
+    }
+
+    fn to_byte_felts(bytes: &[u8]) -> Vec<F> {
+        bytes.iter().map(|b| F::from(*b)).collect()
+    }
+
+    #[test]
+    fn test_assert_valid_utf8() {
+        // ASCII, and two, three and four-byte sequences
+        let cases = ["hello", "héllo", "€ 10", "\u{10ffff}𝄞"];
+
+        for case in cases {
+            let bytes = case.as_bytes();
+            test_var_pub_input(utf8_circuit(bytes.len()), &[], &to_byte_felts(bytes));
+        }
+    }
+
+    fn gen_witness(bytes: &[u8]) {
+        let mut cs = ConstraintSystem::new();
+        cs.gen_witness(utf8_circuit(bytes.len()), &[], &to_byte_felts(bytes));
+    }
+
+    // Invalid continuation byte
+    #[test]
+    #[should_panic(expected = "malformed UTF-8 sequence")]
+    fn test_assert_valid_utf8_invalid_continuation() {
+        gen_witness(&[0xe2, 0x28, 0xa1]);
+    }
+
+    // Unexpected continuation byte
+    #[test]
+    #[should_panic(expected = "malformed UTF-8 sequence")]
+    fn test_assert_valid_utf8_unexpected_continuation() {
+        gen_witness(&[0x61, 0x80]);
+    }
+
+    // Truncated sequence
+    #[test]
+    #[should_panic(expected = "truncated UTF-8 sequence")]
+    fn test_assert_valid_utf8_truncated() {
+        gen_witness(&[0xe2, 0x82]);
+    }
+
+    // Overlong two-byte encoding
+    #[test]
+    #[should_panic(expected = "invalid UTF-8 lead byte")]
+    fn test_assert_valid_utf8_overlong_2() {
+        gen_witness(&[0xc0, 0xaf]);
+    }
+
+    // Overlong three-byte encoding
+    #[test]
+    #[should_panic(expected = "overlong UTF-8 sequence")]
+    fn test_assert_valid_utf8_overlong_3() {
+        gen_witness(&[0xe0, 0x80, 0xaf]);
+    }
+
+    // Surrogate
+    #[test]
+    #[should_panic(expected = "UTF-8 encoded surrogate")]
+    fn test_assert_valid_utf8_surrogate() {
+        gen_witness(&[0xed, 0xa0, 0x80]);
+    }
+
+    // Above U+10FFFF
+    #[test]
+    #[should_panic(expected = "UTF-8 code point too large")]
+    fn test_assert_valid_utf8_too_large() {
+        gen_witness(&[0xf4, 0x90, 0x80, 0x80]);
+    }
+}
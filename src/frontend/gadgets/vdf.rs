@@ -0,0 +1,76 @@
+use super::to_addr::keccak256;
+use crate::frontend::constraint_system::{ConstraintSystem, Wire};
+use ark_ff::PrimeField;
+
+// Assert that `end` is obtained from `start` by iterating Keccak256 `num_steps` times,
+// i.e. that the two checkpoints of a Keccak hash chain are `num_steps` apart.
+// A long chain is proven as independent segments between consecutive checkpoints,
+// which can be proven in parallel.
+// The checkpoints are bits that are little-endian within each byte.
+pub fn verify_vdf_segment<F: PrimeField>(
+    cs: &mut ConstraintSystem<F>,
+    start: &[Wire<F>; 256],
+    end: &[Wire<F>; 256],
+    num_steps: usize,
+) {
+    let mut state = *start;
+    for _ in 0..num_steps {
+        state = keccak256(&state);
+    }
+
+    for (bit, expected_bit) in state.iter().zip(end.iter()) {
+        cs.assert_equal(
+            *bit,
+            *expected_bit,
+            "The segment doesn't end at the checkpoint",
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{bytes_to_le_bits, test_var_pub_input};
+
+    type F = ark_secq256k1::Fr;
+
+    const NUM_STEPS: usize = 3;
+
+    fn vdf_segment_circuit(cs: &mut ConstraintSystem<F>) {
+        let start = cs.alloc_pub_inputs(256);
+        let end = cs.alloc_pub_inputs(256);
+
+        verify_vdf_segment(
+            cs,
+            &start.try_into().unwrap(),
+            &end.try_into().unwrap(),
+            NUM_STEPS,
+        );
+    }
+
+    // The checkpoints 0x000102..1f and keccak256(keccak256(keccak256(0x000102..1f))),
+    // computed off-circuit
+    fn checkpoints() -> Vec<F> {
+        let start = (0..32).collect::<Vec<u8>>();
+        let end = hex::decode("fb9430281ba0bf3937844be9e08bdb87fb4c95d85c2d9d79402bf5bc258699ca")
+            .unwrap();
+
+        [bytes_to_le_bits(&start), bytes_to_le_bits(&end)].concat()
+    }
+
+    #[test]
+    fn test_verify_vdf_segment() {
+        test_var_pub_input(vdf_segment_circuit, &checkpoints(), &[]);
+    }
+
+    // A checkpoint that isn't NUM_STEPS after the start
+    #[test]
+    #[should_panic(expected = "The segment doesn't end at the checkpoint")]
+    fn test_verify_vdf_segment_wrong_end() {
+        let mut pub_input = checkpoints();
+        pub_input[256] += F::from(1u32);
+
+        let mut cs = ConstraintSystem::new();
+        cs.gen_witness(vdf_segment_circuit, &pub_input, &[]);
+    }
+}
@@ -1,4 +1,10 @@
 pub mod circuit;
 pub mod constraint_system;
 pub mod gadgets;
+pub mod r1cs_file;
+#[cfg(feature = "relations")]
+pub mod relations;
 pub mod test_utils;
+pub mod trace;
+pub mod wires;
+pub mod wtns;
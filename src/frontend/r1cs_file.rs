@@ -0,0 +1,188 @@
+use crate::frontend::constraint_system::ConstraintSystem;
+use crate::r1cs::Matrix;
+use ark_ff::{BigInteger, PrimeField};
+use std::io::{self, Write};
+
+const R1CS_VERSION: u32 = 1;
+const HEADER_SECTION: u32 = 1;
+const CONSTRAINTS_SECTION: u32 = 2;
+const WIRE_TO_LABEL_SECTION: u32 = 3;
+
+impl<F: PrimeField> ConstraintSystem<F> {
+    // Write the constraints in the binary .r1cs format of circom/snarkjs.
+    // The wires are ordered as in circom and `write_wtns`, i.e. the constant one,
+    // the public inputs, and then the witness (as returned by `gen_witness`).
+    // The exposed wires are counted as public inputs, since they aren't distinguished from them.
+    pub fn export_r1cs<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let r1cs = self.to_r1cs();
+
+        let modulus = F::MODULUS.to_bytes_le();
+        let n8 = modulus.len();
+
+        let num_wires = 1 + r1cs.num_input + r1cs.num_vars;
+        let num_constraints = self.num_constraints();
+
+        // The witness starts at the second half of the Z vector
+        let witness_offset = r1cs.num_vars.next_power_of_two();
+        let wire_id = |col: usize| {
+            if col < witness_offset {
+                col
+            } else {
+                1 + r1cs.num_input + (col - witness_offset)
+            }
+        };
+
+        // The linear combinations of each constraint, with the terms sorted by wire
+        let rows = |matrix: &Matrix<F>| {
+            let mut rows = vec![vec![]; num_constraints];
+            for entry in &matrix.entries {
+                rows[entry.row].push((wire_id(entry.col) as u32, entry.val));
+            }
+            for row in rows.iter_mut() {
+                row.sort_by_key(|(wire, _)| *wire);
+            }
+
+            rows
+        };
+        let (a_rows, b_rows, c_rows) = (rows(&r1cs.A), rows(&r1cs.B), rows(&r1cs.C));
+
+        let mut constraints = vec![];
+        for con in 0..num_constraints {
+            for lc in [&a_rows[con], &b_rows[con], &c_rows[con]] {
+                constraints.extend_from_slice(&(lc.len() as u32).to_le_bytes());
+                for (wire, val) in lc {
+                    constraints.extend_from_slice(&wire.to_le_bytes());
+                    constraints.extend_from_slice(&val.into_bigint().to_bytes_le());
+                }
+            }
+        }
+
+        writer.write_all(b"r1cs")?;
+        writer.write_all(&R1CS_VERSION.to_le_bytes())?;
+        // Number of sections
+        writer.write_all(&3u32.to_le_bytes())?;
+
+        // The header section: the size of a field element, the modulus,
+        // the number of wires, public outputs, public inputs, private inputs, labels and constraints
+        writer.write_all(&HEADER_SECTION.to_le_bytes())?;
+        writer.write_all(&((4 + n8 + 4 * 4 + 8 + 4) as u64).to_le_bytes())?;
+        writer.write_all(&(n8 as u32).to_le_bytes())?;
+        writer.write_all(&modulus)?;
+        writer.write_all(&(num_wires as u32).to_le_bytes())?;
+        writer.write_all(&0u32.to_le_bytes())?;
+        writer.write_all(&(r1cs.num_input as u32).to_le_bytes())?;
+        writer.write_all(&(self.num_private_inputs() as u32).to_le_bytes())?;
+        writer.write_all(&(num_wires as u64).to_le_bytes())?;
+        writer.write_all(&(num_constraints as u32).to_le_bytes())?;
+
+        // The constraints section, with each constraint as A * B - C = 0
+        writer.write_all(&CONSTRAINTS_SECTION.to_le_bytes())?;
+        writer.write_all(&(constraints.len() as u64).to_le_bytes())?;
+        writer.write_all(&constraints)?;
+
+        // The wire to label section, which labels each wire with its own id
+        writer.write_all(&WIRE_TO_LABEL_SECTION.to_le_bytes())?;
+        writer.write_all(&((8 * num_wires) as u64).to_le_bytes())?;
+        for wire in 0..num_wires {
+            writer.write_all(&(wire as u64).to_le_bytes())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frontend::gadgets::form_le_bits;
+    use ark_ff::Field;
+
+    type F = ark_secq256k1::Fr;
+
+    const NUM_BITS: usize = 8;
+
+    fn read_u32(bytes: &[u8], offset: &mut usize) -> u32 {
+        let val = u32::from_le_bytes(bytes[*offset..*offset + 4].try_into().unwrap());
+        *offset += 4;
+        val
+    }
+
+    fn read_u64(bytes: &[u8], offset: &mut usize) -> u64 {
+        let val = u64::from_le_bytes(bytes[*offset..*offset + 8].try_into().unwrap());
+        *offset += 8;
+        val
+    }
+
+    fn from_bits_circuit(cs: &mut ConstraintSystem<F>) {
+        let bits = cs.alloc_priv_inputs(NUM_BITS);
+        let out = form_le_bits(&bits);
+
+        cs.expose_public(out);
+    }
+
+    #[test]
+    fn test_export_r1cs() {
+        let mut cs = ConstraintSystem::new();
+        cs.set_constraints(&from_bits_circuit);
+
+        // 0b101
+        let mut priv_input = vec![F::ZERO; NUM_BITS];
+        priv_input[0] = F::ONE;
+        priv_input[2] = F::ONE;
+        let pub_input = vec![F::from(5u32)];
+        let witness = cs.gen_witness(from_bits_circuit, &pub_input, &priv_input);
+
+        let mut bytes = vec![];
+        cs.export_r1cs(&mut bytes).unwrap();
+
+        // Parse the header back
+        let mut offset = 4;
+        assert_eq!(&bytes[..4], b"r1cs");
+        assert_eq!(read_u32(&bytes, &mut offset), R1CS_VERSION);
+        assert_eq!(read_u32(&bytes, &mut offset), 3);
+
+        assert_eq!(read_u32(&bytes, &mut offset), HEADER_SECTION);
+        let header_size = read_u64(&bytes, &mut offset) as usize;
+        let n8 = read_u32(&bytes, &mut offset) as usize;
+        assert_eq!(header_size, 4 + n8 + 4 * 4 + 8 + 4);
+        assert_eq!(bytes[offset..offset + n8], F::MODULUS.to_bytes_le());
+        offset += n8;
+
+        let num_wires = read_u32(&bytes, &mut offset) as usize;
+        assert_eq!(num_wires, 1 + pub_input.len() + witness.len());
+        assert_eq!(read_u32(&bytes, &mut offset), 0);
+        assert_eq!(read_u32(&bytes, &mut offset), 1);
+        assert_eq!(read_u32(&bytes, &mut offset) as usize, NUM_BITS);
+        assert_eq!(read_u64(&bytes, &mut offset) as usize, num_wires);
+        let num_constraints = read_u32(&bytes, &mut offset) as usize;
+        assert_eq!(num_constraints, cs.num_constraints());
+
+        // The constraints are satisfied by the values in the order of `write_wtns`
+        let values = [vec![F::ONE], pub_input, witness].concat();
+
+        assert_eq!(read_u32(&bytes, &mut offset), CONSTRAINTS_SECTION);
+        let constraints_size = read_u64(&bytes, &mut offset) as usize;
+        let constraints_end = offset + constraints_size;
+
+        for _ in 0..num_constraints {
+            let mut evals = [F::ZERO; 3];
+            for eval in evals.iter_mut() {
+                let num_terms = read_u32(&bytes, &mut offset);
+                for _ in 0..num_terms {
+                    let wire = read_u32(&bytes, &mut offset) as usize;
+                    let coeff = F::from_le_bytes_mod_order(&bytes[offset..offset + n8]);
+                    offset += n8;
+
+                    *eval += coeff * values[wire];
+                }
+            }
+
+            assert_eq!(evals[0] * evals[1], evals[2]);
+        }
+        assert_eq!(offset, constraints_end);
+
+        assert_eq!(read_u32(&bytes, &mut offset), WIRE_TO_LABEL_SECTION);
+        assert_eq!(read_u64(&bytes, &mut offset) as usize, 8 * num_wires);
+        assert_eq!(bytes.len(), offset + 8 * num_wires);
+    }
+}
@@ -0,0 +1,83 @@
+use crate::frontend::constraint_system::ConstraintSystem;
+use crate::r1cs::{Matrix, R1CS};
+use ark_ff::PrimeField;
+use ark_relations::r1cs::{
+    ConstraintSystem as ArkConstraintSystem, ConstraintSystemRef, LinearCombination, Variable,
+};
+use std::collections::BTreeMap;
+
+impl<F: PrimeField> ConstraintSystem<F> {
+    // Export the constraints and the assignment to an arkworks constraint system,
+    // so that the circuit can be proven with the arkworks backends (e.g. Groth16 or Marlin).
+    // The public inputs become the instance variables in order,
+    // and only the witness wires that appear in a constraint are allocated.
+    pub fn to_ark_relations(&self, witness: &[F], public_input: &[F]) -> ConstraintSystemRef<F> {
+        let r1cs = self.to_r1cs();
+        let z = R1CS::construct_z(witness, public_input);
+
+        let ark_cs = ArkConstraintSystem::<F>::new_ref();
+
+        let mut variables = BTreeMap::<usize, Variable>::new();
+        variables.insert(0, Variable::One);
+
+        for (i, val) in public_input.iter().enumerate() {
+            let var = ark_cs.new_input_variable(|| Ok(*val)).unwrap();
+            variables.insert(i + 1, var);
+        }
+
+        for matrix in [&r1cs.A, &r1cs.B, &r1cs.C] {
+            for entry in &matrix.entries {
+                if !variables.contains_key(&entry.col) {
+                    let var = ark_cs.new_witness_variable(|| Ok(z[entry.col])).unwrap();
+                    variables.insert(entry.col, var);
+                }
+            }
+        }
+
+        let to_lcs = |matrix: &Matrix<F>| {
+            let mut lcs = vec![LinearCombination::<F>::zero(); matrix.num_rows];
+            for entry in &matrix.entries {
+                lcs[entry.row].0.push((entry.val, variables[&entry.col]));
+            }
+            lcs
+        };
+
+        let (A, B, C) = (to_lcs(&r1cs.A), to_lcs(&r1cs.B), to_lcs(&r1cs.C));
+        for ((a, b), c) in A.into_iter().zip(B).zip(C) {
+            ark_cs.enforce_constraint(a, b, c).unwrap();
+        }
+
+        ark_cs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frontend::test_utils::synthetic_circuit;
+    use ark_ff::Field;
+
+    type F = ark_secq256k1::Fr;
+
+    #[test]
+    fn test_to_ark_relations() {
+        let (synthesizer, pub_input, priv_input, _) = synthetic_circuit::<F>();
+
+        let mut cs = ConstraintSystem::new();
+        cs.set_constraints(&synthesizer);
+        let witness = cs.gen_witness(&synthesizer, &pub_input, &priv_input);
+        assert!(cs.is_sat(&witness, &pub_input));
+
+        let ark_cs = cs.to_ark_relations(&witness, &pub_input);
+        assert_eq!(ark_cs.num_instance_variables(), pub_input.len() + 1);
+        assert!(ark_cs.is_satisfied().unwrap());
+
+        // Should be unsatisfied for an invalid witness
+        let mut invalid_witness = witness.clone();
+        invalid_witness[0] += F::ONE;
+        assert!(!cs.is_sat(&invalid_witness, &pub_input));
+
+        let ark_cs = cs.to_ark_relations(&invalid_witness, &pub_input);
+        assert!(!ark_cs.is_satisfied().unwrap());
+    }
+}
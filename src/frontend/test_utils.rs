@@ -56,6 +56,15 @@ pub fn synthetic_circuit<F: Field>() -> (impl Fn(&mut ConstraintSystem<F>), Vec<
     (synthesizer, pub_input, priv_input, witness)
 }
 
+// Bytes as bits that are little-endian within each byte,
+// the bit order of the byte string gadgets (e.g. `keccak256` and `sha256`)
+pub fn bytes_to_le_bits<F: Field>(bytes: &[u8]) -> Vec<F> {
+    bytes
+        .iter()
+        .flat_map(|b| (0..8).map(move |i| F::from((b >> i) & 1)))
+        .collect()
+}
+
 // 1. Test that the circuit is satisfiable when thew witness and the public input are valid
 // 2. Test that the circuit unsatisfiable when the witness or the public input is invalid.
 pub fn test_satisfiability<F: Field>(
@@ -0,0 +1,358 @@
+use crate::frontend::constraint_system::{ConstraintSystem, Wire};
+use ark_ff::Field;
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+    Write,
+};
+use std::collections::BTreeMap;
+
+// An operation on the constraint system, as recorded by `record_trace`.
+// Wires are referred to by their ids, which are assigned in the order of allocation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TraceOp<F: Field> {
+    AllocVar(F),
+    AllocPrivInput,
+    AllocPubInput,
+    ExposePublic(usize),
+    ExposeNamed(String, usize),
+    Add(usize, usize),
+    Sum(Vec<(usize, bool)>),
+    AddConst(usize, F),
+    Sub(usize, usize),
+    SubConst(usize, F),
+    Neg(usize),
+    Constrain(Vec<(usize, F)>, Vec<(usize, F)>, Vec<(usize, F)>),
+    Mul(usize, usize),
+    MulConst(usize, F),
+    MulAdd(usize, usize, usize),
+    Div(usize, usize),
+    DivOrZero(usize, usize),
+    AssertEqual(usize, usize, String),
+    AssertZero(usize),
+    IsZero(usize),
+}
+
+// The sequence of operations a synthesizer performs on the constraint system.
+// A trace can be serialized, and replayed with `replay_trace`
+// to reconstruct the circuit without running the synthesizer.
+#[derive(Debug, Clone, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Trace<F: Field> {
+    pub ops: Vec<TraceOp<F>>,
+}
+
+impl<F: Field> Trace<F> {
+    // Whether the trace allocates hints, in which case the witness can't be generated from it
+    pub fn has_hints(&self) -> bool {
+        self.ops.iter().any(|op| matches!(op, TraceOp::AllocVar(_)))
+    }
+}
+
+// Run the synthesizer and record the operations it performs on the constraint system.
+// The trace is recorded while setting the constraints, so the values of `alloc_var`
+// (the hints of gadgets such as `to_le_bits` and `range_check`) are placeholders.
+// The constraints of such circuits can be replayed, but their witness can't.
+pub fn record_trace<F: Field, S: Fn(&mut ConstraintSystem<F>)>(synthesizer: &S) -> Trace<F> {
+    let mut cs = ConstraintSystem::new();
+    cs.start_trace();
+    cs.set_constraints(synthesizer);
+
+    Trace {
+        ops: cs.take_trace().unwrap(),
+    }
+}
+
+// Return a synthesizer that replays the operations of the trace.
+// The returned synthesizer can be used in place of the recorded one,
+// both to set the constraints and to generate the witness.
+// Generating the witness panics if the trace allocates hints (see `Trace::has_hints`).
+pub fn replay_trace<F: Field>(trace: &Trace<F>) -> impl Fn(&mut ConstraintSystem<F>) + '_ {
+    move |cs: &mut ConstraintSystem<F>| {
+        let mut wires = BTreeMap::<usize, Wire<F>>::new();
+
+        let one = cs.one();
+        wires.insert(one.id(), one);
+
+        let w = |wires: &BTreeMap<usize, Wire<F>>, id: &usize| -> Wire<F> {
+            *wires
+                .get(id)
+                .unwrap_or_else(|| panic!("Wire {} is not allocated", id))
+        };
+
+        let lc = |wires: &BTreeMap<usize, Wire<F>>, terms: &[(usize, F)]| -> Vec<(Wire<F>, F)> {
+            terms.iter().map(|(id, c)| (w(wires, id), *c)).collect()
+        };
+
+        for op in &trace.ops {
+            let out = match op {
+                TraceOp::AllocVar(val) => {
+                    if cs.is_witness_gen() {
+                        panic!(
+                            "The trace allocates a hint, so the witness can't be generated from it"
+                        );
+                    }
+                    Some(cs.alloc_var(*val))
+                }
+                TraceOp::AllocPrivInput => Some(cs.alloc_priv_input()),
+                TraceOp::AllocPubInput => Some(cs.alloc_pub_input()),
+                TraceOp::ExposePublic(id) => {
+                    cs.expose_public(w(&wires, id));
+                    None
+                }
+                TraceOp::ExposeNamed(name, id) => {
+                    cs.expose_named(name, w(&wires, id));
+                    None
+                }
+                TraceOp::Add(a, b) => Some(cs.add(w(&wires, a), w(&wires, b))),
+                TraceOp::Sum(terms) => {
+                    let terms = terms
+                        .iter()
+                        .map(|(id, sign)| (w(&wires, id), *sign))
+                        .collect::<Vec<(Wire<F>, bool)>>();
+                    Some(cs.sum(&terms))
+                }
+                TraceOp::AddConst(a, c) => Some(cs.add_const(w(&wires, a), *c)),
+                TraceOp::Sub(a, b) => Some(cs.sub(w(&wires, a), w(&wires, b))),
+                TraceOp::SubConst(a, c) => Some(cs.sub_const(w(&wires, a), *c)),
+                TraceOp::Neg(a) => Some(cs.neg(w(&wires, a))),
+                TraceOp::Constrain(a, b, c) => {
+                    Some(cs.constrain(&lc(&wires, a), &lc(&wires, b), &lc(&wires, c)))
+                }
+                TraceOp::Mul(a, b) => Some(cs.mul(w(&wires, a), w(&wires, b))),
+                TraceOp::MulConst(a, c) => Some(cs.mul_const(w(&wires, a), *c)),
+                TraceOp::MulAdd(a, b, c) => {
+                    Some(cs.mul_add(w(&wires, a), w(&wires, b), w(&wires, c)))
+                }
+                TraceOp::Div(a, b) => Some(cs.div(w(&wires, a), w(&wires, b))),
+                TraceOp::DivOrZero(a, b) => Some(cs.div_or_zero(w(&wires, a), w(&wires, b))),
+                TraceOp::AssertEqual(a, b, msg) => {
+                    cs.assert_equal(w(&wires, a), w(&wires, b), msg);
+                    None
+                }
+                TraceOp::AssertZero(a) => {
+                    cs.assert_zero(w(&wires, a));
+                    None
+                }
+                TraceOp::IsZero(a) => Some(cs.is_zero(w(&wires, a))),
+            };
+
+            if let Some(out) = out {
+                wires.insert(out.id(), out);
+            }
+        }
+    }
+}
+
+impl<F: Field> TraceOp<F> {
+    fn tag(&self) -> u8 {
+        match self {
+            TraceOp::AllocVar(_) => 0,
+            TraceOp::AllocPrivInput => 1,
+            TraceOp::AllocPubInput => 2,
+            TraceOp::ExposePublic(_) => 3,
+            TraceOp::ExposeNamed(_, _) => 4,
+            TraceOp::Add(_, _) => 5,
+            TraceOp::Sum(_) => 6,
+            TraceOp::AddConst(_, _) => 7,
+            TraceOp::Sub(_, _) => 8,
+            TraceOp::SubConst(_, _) => 9,
+            TraceOp::Neg(_) => 10,
+            TraceOp::Constrain(_, _, _) => 11,
+            TraceOp::Mul(_, _) => 12,
+            TraceOp::MulConst(_, _) => 13,
+            TraceOp::MulAdd(_, _, _) => 14,
+            TraceOp::Div(_, _) => 15,
+            TraceOp::DivOrZero(_, _) => 16,
+            TraceOp::AssertEqual(_, _, _) => 17,
+            TraceOp::AssertZero(_) => 18,
+            TraceOp::IsZero(_) => 19,
+        }
+    }
+}
+
+impl<F: Field> Valid for TraceOp<F> {
+    fn check(&self) -> Result<(), SerializationError> {
+        Ok(())
+    }
+}
+
+impl<F: Field> CanonicalSerialize for TraceOp<F> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.tag().serialize_with_mode(&mut writer, compress)?;
+
+        match self {
+            TraceOp::AllocVar(val) => val.serialize_with_mode(&mut writer, compress),
+            TraceOp::AllocPrivInput | TraceOp::AllocPubInput => Ok(()),
+            TraceOp::ExposePublic(a)
+            | TraceOp::Neg(a)
+            | TraceOp::AssertZero(a)
+            | TraceOp::IsZero(a) => a.serialize_with_mode(&mut writer, compress),
+            TraceOp::ExposeNamed(name, a) => {
+                name.serialize_with_mode(&mut writer, compress)?;
+                a.serialize_with_mode(&mut writer, compress)
+            }
+            TraceOp::Add(a, b)
+            | TraceOp::Sub(a, b)
+            | TraceOp::Mul(a, b)
+            | TraceOp::Div(a, b)
+            | TraceOp::DivOrZero(a, b) => {
+                a.serialize_with_mode(&mut writer, compress)?;
+                b.serialize_with_mode(&mut writer, compress)
+            }
+            TraceOp::Sum(terms) => terms.serialize_with_mode(&mut writer, compress),
+            TraceOp::AddConst(a, c) | TraceOp::SubConst(a, c) | TraceOp::MulConst(a, c) => {
+                a.serialize_with_mode(&mut writer, compress)?;
+                c.serialize_with_mode(&mut writer, compress)
+            }
+            TraceOp::Constrain(a, b, c) => {
+                a.serialize_with_mode(&mut writer, compress)?;
+                b.serialize_with_mode(&mut writer, compress)?;
+                c.serialize_with_mode(&mut writer, compress)
+            }
+            TraceOp::MulAdd(a, b, c) => {
+                a.serialize_with_mode(&mut writer, compress)?;
+                b.serialize_with_mode(&mut writer, compress)?;
+                c.serialize_with_mode(&mut writer, compress)
+            }
+            TraceOp::AssertEqual(a, b, msg) => {
+                a.serialize_with_mode(&mut writer, compress)?;
+                b.serialize_with_mode(&mut writer, compress)?;
+                msg.serialize_with_mode(&mut writer, compress)
+            }
+        }
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        let mut bytes = vec![];
+        self.serialize_with_mode(&mut bytes, compress).unwrap();
+        bytes.len()
+    }
+}
+
+impl<F: Field> CanonicalDeserialize for TraceOp<F> {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let tag = u8::deserialize_with_mode(&mut reader, compress, validate)?;
+
+        macro_rules! read {
+            () => {
+                CanonicalDeserialize::deserialize_with_mode(&mut reader, compress, validate)?
+            };
+        }
+
+        let op = match tag {
+            0 => TraceOp::AllocVar(read!()),
+            1 => TraceOp::AllocPrivInput,
+            2 => TraceOp::AllocPubInput,
+            3 => TraceOp::ExposePublic(read!()),
+            4 => TraceOp::ExposeNamed(read!(), read!()),
+            5 => TraceOp::Add(read!(), read!()),
+            6 => TraceOp::Sum(read!()),
+            7 => TraceOp::AddConst(read!(), read!()),
+            8 => TraceOp::Sub(read!(), read!()),
+            9 => TraceOp::SubConst(read!(), read!()),
+            10 => TraceOp::Neg(read!()),
+            11 => TraceOp::Constrain(read!(), read!(), read!()),
+            12 => TraceOp::Mul(read!(), read!()),
+            13 => TraceOp::MulConst(read!(), read!()),
+            14 => TraceOp::MulAdd(read!(), read!(), read!()),
+            15 => TraceOp::Div(read!(), read!()),
+            16 => TraceOp::DivOrZero(read!(), read!()),
+            17 => TraceOp::AssertEqual(read!(), read!(), read!()),
+            18 => TraceOp::AssertZero(read!()),
+            19 => TraceOp::IsZero(read!()),
+            _ => return Err(SerializationError::InvalidData),
+        };
+
+        Ok(op)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frontend::gadgets::to_le_bits;
+    use crate::test_var_pub_input;
+    use std::panic;
+
+    type F = ark_secq256k1::Fr;
+
+    fn replay_circuit(cs: &mut ConstraintSystem<F>) {
+        let a = cs.alloc_priv_input();
+        let b = cs.alloc_pub_input();
+
+        let ab = a * b;
+        let c = cs.add_const(ab + a, F::from(7u32));
+
+        cs.expose_public(c);
+    }
+
+    #[test]
+    fn test_replay_trace() {
+        let trace = record_trace(&replay_circuit);
+        assert!(!trace.has_hints());
+
+        // Serialize and deserialize the trace
+        let mut trace_bytes = vec![];
+        trace.serialize_compressed(&mut trace_bytes).unwrap();
+        let trace = Trace::<F>::deserialize_compressed(trace_bytes.as_slice()).unwrap();
+
+        let replayed = replay_trace(&trace);
+
+        let mut cs = ConstraintSystem::new();
+        cs.set_constraints(&replay_circuit);
+
+        let mut replayed_cs = ConstraintSystem::new();
+        replayed_cs.set_constraints(&replayed);
+
+        // The replayed circuit should be identical to the original
+        let mut r1cs_bytes = vec![];
+        cs.to_r1cs().serialize_compressed(&mut r1cs_bytes).unwrap();
+
+        let mut replayed_r1cs_bytes = vec![];
+        replayed_cs
+            .to_r1cs()
+            .serialize_compressed(&mut replayed_r1cs_bytes)
+            .unwrap();
+
+        assert_eq!(r1cs_bytes, replayed_r1cs_bytes);
+
+        // The witness can be generated from the trace as well
+        let (a, b) = (F::from(3u32), F::from(4u32));
+        let c = a * b + a + F::from(7u32);
+        test_var_pub_input(replayed, &[b, c], &[a]);
+    }
+
+    #[test]
+    fn test_replay_hints() {
+        let synthesizer = |cs: &mut ConstraintSystem<F>| {
+            let x = cs.alloc_priv_input();
+            to_le_bits(x);
+        };
+
+        let trace = record_trace(&synthesizer);
+        assert!(trace.has_hints());
+
+        let replayed = replay_trace(&trace);
+
+        // The constraints can be replayed
+        let mut cs = ConstraintSystem::new();
+        cs.set_constraints(&synthesizer);
+
+        let mut replayed_cs = ConstraintSystem::new();
+        replayed_cs.set_constraints(&replayed);
+        assert_eq!(replayed_cs.num_constraints, cs.num_constraints);
+
+        // But not the witness
+        let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            replayed_cs.gen_witness(&replayed, &[], &[F::from(3u32)]);
+        }));
+        assert!(result.is_err());
+    }
+}
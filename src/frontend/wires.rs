@@ -0,0 +1,149 @@
+use ark_ff::Field;
+use std::ops::{Deref, DerefMut};
+
+#[cfg(feature = "mmap")]
+use memmap2::MmapMut;
+#[cfg(feature = "mmap")]
+use std::{fs::OpenOptions, io, marker::PhantomData, mem::size_of, path::Path};
+
+// The values of the wires of a constraint system.
+// The values are kept in memory, or in a memory-mapped file
+// when the `mmap` feature is enabled and a backing file is set
+// (see `ConstraintSystem::set_mmap_backing`).
+pub enum Wires<F: Field> {
+    Memory(Vec<F>),
+    #[cfg(feature = "mmap")]
+    Mmap(MmapWires<F>),
+}
+
+impl<F: Field> Wires<F> {
+    pub const fn new() -> Self {
+        Wires::Memory(Vec::new())
+    }
+
+    // Allocate `len` wires in memory, all set to zero.
+    pub fn zeros(len: usize) -> Self {
+        Wires::Memory(vec![F::ZERO; len])
+    }
+}
+
+impl<F: Field> Deref for Wires<F> {
+    type Target = [F];
+
+    fn deref(&self) -> &[F] {
+        match self {
+            Wires::Memory(wires) => wires,
+            #[cfg(feature = "mmap")]
+            Wires::Mmap(wires) => wires,
+        }
+    }
+}
+
+impl<F: Field> DerefMut for Wires<F> {
+    fn deref_mut(&mut self) -> &mut [F] {
+        match self {
+            Wires::Memory(wires) => wires,
+            #[cfg(feature = "mmap")]
+            Wires::Mmap(wires) => wires,
+        }
+    }
+}
+
+// Cloning copies the values into memory.
+impl<F: Field> Clone for Wires<F> {
+    fn clone(&self) -> Self {
+        Wires::Memory(self.to_vec())
+    }
+}
+
+// Wire values stored in a memory-mapped file.
+// The file only holds the in-memory representation of the field elements,
+// so it's scratch space for the process that created it and not a serialization format.
+#[cfg(feature = "mmap")]
+pub struct MmapWires<F: Field> {
+    mmap: MmapMut,
+    len: usize,
+    _marker: PhantomData<F>,
+}
+
+#[cfg(feature = "mmap")]
+impl<F: Field> MmapWires<F> {
+    // Create (or truncate) the file at `path` and map `len` wires onto it, all set to zero.
+    pub fn zeros(path: &Path, len: usize) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+
+        // Mapping an empty file fails, so we always map at least one element.
+        file.set_len((len.max(1) * size_of::<F>()) as u64)?;
+
+        // Safety: the file was just created by us, and it's only accessed through this mapping.
+        let mmap = unsafe { MmapMut::map_mut(&file)? };
+
+        let mut wires = MmapWires {
+            mmap,
+            len,
+            _marker: PhantomData,
+        };
+        wires.fill(F::ZERO);
+
+        Ok(wires)
+    }
+}
+
+#[cfg(feature = "mmap")]
+impl<F: Field> Deref for MmapWires<F> {
+    type Target = [F];
+
+    fn deref(&self) -> &[F] {
+        // Safety: the mapping is page-aligned, spans at least `len` elements,
+        // and every element has been initialized in `zeros`.
+        unsafe { std::slice::from_raw_parts(self.mmap.as_ptr() as *const F, self.len) }
+    }
+}
+
+#[cfg(feature = "mmap")]
+impl<F: Field> DerefMut for MmapWires<F> {
+    fn deref_mut(&mut self) -> &mut [F] {
+        // Safety: see `deref`
+        unsafe { std::slice::from_raw_parts_mut(self.mmap.as_mut_ptr() as *mut F, self.len) }
+    }
+}
+
+#[cfg(all(test, feature = "mmap"))]
+mod tests {
+    use crate::frontend::constraint_system::ConstraintSystem;
+    use crate::mock_circuit;
+
+    type F = ark_secq256k1::Fr;
+
+    #[test]
+    fn test_mmap_wires() {
+        let synthesizer = mock_circuit::<F>(8);
+
+        let priv_input = [F::from(3u32), F::from(4u32)];
+        let pub_input = [F::from(12u32)];
+
+        let mut cs = ConstraintSystem::new();
+        cs.set_constraints(&synthesizer);
+        let witness = cs.gen_witness(&synthesizer, &pub_input, &priv_input);
+
+        let wires_path = std::env::temp_dir().join("sapir_test_mmap_wires");
+
+        let mut mmap_cs = ConstraintSystem::new();
+        mmap_cs.set_mmap_backing(&wires_path);
+        mmap_cs.set_constraints(&synthesizer);
+        let mmap_witness = mmap_cs.gen_witness(&synthesizer, &pub_input, &priv_input);
+
+        // The memory-mapped run should produce the same wires and witness
+        assert_eq!(mmap_cs.wires.len(), cs.wires.len());
+        assert!(mmap_cs.wires.iter().eq(cs.wires.iter()));
+        assert_eq!(mmap_witness, witness);
+        assert!(mmap_cs.is_sat(&mmap_witness, &pub_input));
+
+        std::fs::remove_file(wires_path).unwrap();
+    }
+}
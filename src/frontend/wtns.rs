@@ -0,0 +1,104 @@
+use ark_ff::{BigInteger, PrimeField};
+use std::io::{self, Write};
+
+const WTNS_VERSION: u32 = 2;
+const HEADER_SECTION: u32 = 1;
+const WITNESS_SECTION: u32 = 2;
+
+// Write the witness in the binary .wtns format of circom/snarkjs.
+// The values are ordered as in circom, i.e. the constant one, the public inputs,
+// and then the witness (as returned by `ConstraintSystem::gen_witness`).
+// Each value is encoded as its little-endian bytes.
+pub fn write_wtns<F: PrimeField, W: Write>(
+    mut writer: W,
+    witness: &[F],
+    pub_input: &[F],
+) -> io::Result<()> {
+    let modulus = F::MODULUS.to_bytes_le();
+    let n8 = modulus.len();
+
+    let num_values = 1 + pub_input.len() + witness.len();
+
+    writer.write_all(b"wtns")?;
+    writer.write_all(&WTNS_VERSION.to_le_bytes())?;
+    // Number of sections
+    writer.write_all(&2u32.to_le_bytes())?;
+
+    // The header section: the size of a field element, the modulus, and the number of values
+    writer.write_all(&HEADER_SECTION.to_le_bytes())?;
+    writer.write_all(&((4 + n8 + 4) as u64).to_le_bytes())?;
+    writer.write_all(&(n8 as u32).to_le_bytes())?;
+    writer.write_all(&modulus)?;
+    writer.write_all(&(num_values as u32).to_le_bytes())?;
+
+    // The witness section
+    writer.write_all(&WITNESS_SECTION.to_le_bytes())?;
+    writer.write_all(&((n8 * num_values) as u64).to_le_bytes())?;
+
+    let values = [F::ONE].iter().chain(pub_input).chain(witness);
+    for value in values {
+        writer.write_all(&value.into_bigint().to_bytes_le())?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frontend::constraint_system::ConstraintSystem;
+    use crate::frontend::test_utils::synthetic_circuit;
+    use ark_ff::Field;
+
+    type F = ark_secq256k1::Fr;
+
+    fn read_u32(bytes: &[u8], offset: &mut usize) -> u32 {
+        let val = u32::from_le_bytes(bytes[*offset..*offset + 4].try_into().unwrap());
+        *offset += 4;
+        val
+    }
+
+    fn read_u64(bytes: &[u8], offset: &mut usize) -> u64 {
+        let val = u64::from_le_bytes(bytes[*offset..*offset + 8].try_into().unwrap());
+        *offset += 8;
+        val
+    }
+
+    #[test]
+    fn test_write_wtns() {
+        let (synthesizer, pub_input, priv_input, _) = synthetic_circuit::<F>();
+
+        let mut cs = ConstraintSystem::new();
+        cs.set_constraints(&synthesizer);
+        let witness = cs.gen_witness(&synthesizer, &pub_input, &priv_input);
+
+        let mut bytes = vec![];
+        write_wtns(&mut bytes, &witness, &pub_input).unwrap();
+
+        // Parse the file back
+        let mut offset = 4;
+        assert_eq!(&bytes[..4], b"wtns");
+        assert_eq!(read_u32(&bytes, &mut offset), WTNS_VERSION);
+        assert_eq!(read_u32(&bytes, &mut offset), 2);
+
+        assert_eq!(read_u32(&bytes, &mut offset), HEADER_SECTION);
+        let header_size = read_u64(&bytes, &mut offset) as usize;
+        let n8 = read_u32(&bytes, &mut offset) as usize;
+        assert_eq!(header_size, 4 + n8 + 4);
+        assert_eq!(bytes[offset..offset + n8], F::MODULUS.to_bytes_le());
+        offset += n8;
+
+        let num_values = read_u32(&bytes, &mut offset) as usize;
+        assert_eq!(num_values, 1 + pub_input.len() + witness.len());
+
+        assert_eq!(read_u32(&bytes, &mut offset), WITNESS_SECTION);
+        assert_eq!(read_u64(&bytes, &mut offset) as usize, n8 * num_values);
+
+        let values = bytes[offset..]
+            .chunks(n8)
+            .map(F::from_le_bytes_mod_order)
+            .collect::<Vec<F>>();
+        assert_eq!(values.len(), num_values);
+        assert_eq!(values, [vec![F::ONE], pub_input, witness].concat());
+    }
+}
@@ -1,4 +1,5 @@
 #![allow(non_snake_case)]
+pub mod eth_utils;
 pub mod frontend;
 pub mod r1cs;
 pub mod spartan;
@@ -1,16 +1,19 @@
 use crate::{
-    spartan::transcript::Transcript,
+    spartan::transcript::TranscriptProtocol,
     spartan::{ipa::IPA, polynomial::eq_poly::EqPoly, utils::inner_prod},
     spartan::{
-        ipa::{IPAComm, InnerProductProof},
+        ipa::{CheckTerms, IPAComm, InnerProductProof},
         utils::msm,
     },
     ScalarField,
 };
 use ark_ec::CurveGroup;
-use ark_ff::{Field, UniformRand};
+use ark_ff::{Field, UniformRand, Zero};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
 pub struct HyraxComm<C: CurveGroup> {
     pub T: Vec<C>,
     pub w: Vec<Vec<ScalarField<C>>>,
@@ -104,11 +107,12 @@ impl<C: CurveGroup> Hyrax<C> {
             .map(|_| ScalarField::<C>::rand(&mut rng))
             .collect::<Vec<ScalarField<C>>>();
 
-        let T = w_rows
-            .iter()
-            .zip(blinders.iter())
-            .map(|(row, blinder)| self.ipa.commit(row.to_vec(), *blinder).comm)
-            .collect::<Vec<C>>();
+        // The `sequential-commit` feature forces the sequential path, e.g. for profiling
+        let T = self.commit_rows(
+            &w_rows,
+            &blinders,
+            cfg!(feature = "parallel") && !cfg!(feature = "sequential-commit"),
+        );
 
         HyraxComm {
             T,
@@ -117,12 +121,38 @@ impl<C: CurveGroup> Hyrax<C> {
         }
     }
 
+    // Commit to each row with its blinder.
+    // The rows are committed across threads if `parallel` is set and the `parallel` feature is enabled.
+    fn commit_rows(
+        &self,
+        rows: &[Vec<ScalarField<C>>],
+        blinders: &[ScalarField<C>],
+        parallel: bool,
+    ) -> Vec<C> {
+        let commit_row = |(row, blinder): (&Vec<ScalarField<C>>, &ScalarField<C>)| {
+            self.ipa.commit(row.to_vec(), *blinder).comm
+        };
+
+        #[cfg(feature = "parallel")]
+        if parallel {
+            return rows
+                .par_iter()
+                .zip(blinders.par_iter())
+                .map(commit_row)
+                .collect();
+        }
+        #[cfg(not(feature = "parallel"))]
+        let _ = parallel;
+
+        rows.iter().zip(blinders.iter()).map(commit_row).collect()
+    }
+
     // Open the committed polynomial `comm_a`'s evaluation at `x`
     pub fn open(
         &self,
         comm_a: &HyraxComm<C>,
         x: Vec<ScalarField<C>>,
-        transcript: &mut Transcript<C>,
+        transcript: &mut impl TranscriptProtocol<C>,
     ) -> PolyEvalProof<C> {
         // Pad `x`
         let mut x = x;
@@ -173,14 +203,71 @@ impl<C: CurveGroup> Hyrax<C> {
         }
     }
 
-    pub fn verify(&self, proof: &PolyEvalProof<C>, transcript: &mut Transcript<C>) {
-        // Pad `x`
-        let mut x = proof.x.clone();
-        // x.resize(self.padded_num_vrs, ScalarField::<C>::ZERO);
+    pub fn verify(&self, proof: &PolyEvalProof<C>, transcript: &mut impl TranscriptProtocol<C>) {
+        let (x, L, R) = self.padded_eqs(&proof.x);
+
+        // Compute the commitment to the L * T
+        let T_prime = msm(&L, &proof.T);
+        assert_eq!(T_prime, proof.inner_prod_proof.comm);
+        assert_eq!(x, proof.x);
+
+        self.ipa.verify(&proof.inner_prod_proof, R, transcript);
+    }
+
+    // Verify the proofs as if they were verified one after the other with `verify`,
+    // with a single multi-scalar multiplication.
+    // The checks of each proof (the commitment to L * T and the inner product argument)
+    // are summed with random weights, so an invalid proof is rejected
+    // except with negligible probability.
+    pub fn verify_batch(
+        &self,
+        proofs: &[PolyEvalProof<C>],
+        transcript: &mut impl TranscriptProtocol<C>,
+    ) {
+        let mut rng = ark_std::rand::thread_rng();
+        let mut terms = CheckTerms::new();
+
+        for proof in proofs {
+            let (x, L, R) = self.padded_eqs(&proof.x);
+            assert_eq!(x, proof.x);
+            assert_eq!(proof.y, proof.inner_prod_proof.y);
+
+            // L * T = comm
+            let mut scalars = L;
+            scalars.push(-ScalarField::<C>::ONE);
+            let mut points = proof.T.clone();
+            points.push(proof.inner_prod_proof.comm);
+
+            let comm_terms = CheckTerms {
+                scalars,
+                points,
+                ..CheckTerms::new()
+            };
+            terms.add_weighted(&comm_terms, ScalarField::<C>::rand(&mut rng));
+
+            let ipa_terms = self.ipa.check_terms(&proof.inner_prod_proof, R, transcript);
+            terms.add_weighted(&ipa_terms, ScalarField::<C>::rand(&mut rng));
+        }
+
+        assert!(
+            self.ipa.eval_check_terms(&terms).is_zero(),
+            "Invalid evaluation proof"
+        );
+    }
+
+    // Pad `x`, and return it with the evaluations of the eq polynomials
+    // of its lower and higher variables
+    fn padded_eqs(
+        &self,
+        x: &[ScalarField<C>],
+    ) -> (
+        Vec<ScalarField<C>>,
+        Vec<ScalarField<C>>,
+        Vec<ScalarField<C>>,
+    ) {
         let mut pad = vec![ScalarField::<C>::ZERO; self.padded_num_vrs - x.len()];
-        // x.resize(self.padded_num_vrs, ScalarField::<C>::ZERO);
-        pad.extend(x);
-        x = pad;
+        pad.extend_from_slice(x);
+        let x = pad;
 
         let num_cols_log2 = (self.padded_num_cols as f64).log2() as usize;
         let num_rows_log2 = (self.padded_num_rows as f64).log2() as usize;
@@ -189,21 +276,36 @@ impl<C: CurveGroup> Hyrax<C> {
         let L = EqPoly::new(x_low).evals();
         let R = EqPoly::new(x_high).evals();
 
-        // Compute the commitment to the L * T
-        let T_prime = msm(&L, &proof.T);
-        assert_eq!(T_prime, proof.inner_prod_proof.comm);
-        assert_eq!(x, proof.x);
-
-        self.ipa.verify(&proof.inner_prod_proof, R, transcript);
+        (x, L, R)
     }
 }
 
+// Checks the evaluation point and the commitment of an opening
+pub(crate) fn check_opening<C: CurveGroup>(
+    hyrax: &Hyrax<C>,
+    proof: &PolyEvalProof<C>,
+    comm: &[C],
+    point: &[ScalarField<C>],
+    transcript: &mut impl TranscriptProtocol<C>,
+) {
+    assert_eq!(proof.T, comm, "The opening is of another commitment");
+
+    let pad = proof.x.len() - point.len();
+    assert!(
+        proof.x[..pad].iter().all(|x| *x == ScalarField::<C>::ZERO) && proof.x[pad..] == *point,
+        "The opening is at another point"
+    );
+
+    hyrax.verify(proof, transcript);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     use crate::{
         spartan::polynomial::ml_poly::MlPoly,
+        spartan::transcript::Transcript,
         timer::{timer_end, timer_start},
     };
 
@@ -234,4 +336,75 @@ mod tests {
         let mut verifier_transcript = Transcript::new(b"test");
         hyrax.verify(&eval_proof, &mut verifier_transcript);
     }
+
+    #[test]
+    fn test_commit_rows_parallel() {
+        let n = 2usize.pow(10);
+        let hyrax = Hyrax::<Curve>::new(n, n);
+        let mut rng = ark_std::rand::thread_rng();
+
+        let rows = (0..hyrax.padded_num_rows)
+            .map(|_| {
+                (0..hyrax.padded_num_cols)
+                    .map(|_| F::rand(&mut rng))
+                    .collect::<Vec<F>>()
+            })
+            .collect::<Vec<Vec<F>>>();
+        let blinders = (0..rows.len())
+            .map(|_| F::rand(&mut rng))
+            .collect::<Vec<F>>();
+
+        // The sequential and the parallel paths give the same commitments
+        assert_eq!(
+            hyrax.commit_rows(&rows, &blinders, false),
+            hyrax.commit_rows(&rows, &blinders, true)
+        );
+    }
+
+    #[test]
+    fn test_hyrax_verify_batch() {
+        let m = 5;
+        let n = 2usize.pow(m as u32);
+        let hyrax = Hyrax::<Curve>::new(n, n);
+
+        let mut prover_transcript = Transcript::new(b"test");
+        let proofs = (0..3)
+            .map(|k| {
+                let a = (0..n)
+                    .map(|i| F::from((i * (k + 2) + 5) as u64))
+                    .collect::<Vec<F>>();
+                let x = (0..m)
+                    .map(|i| F::from((i + 7 * k + 1) as u64))
+                    .collect::<Vec<F>>();
+
+                let comm = hyrax.commit(a);
+                hyrax.open(&comm, x, &mut prover_transcript)
+            })
+            .collect::<Vec<PolyEvalProof<Curve>>>();
+
+        let mut verifier_transcript = Transcript::new(b"test");
+        hyrax.verify_batch(&proofs, &mut verifier_transcript);
+
+        // Should fail if the claimed evaluation of any proof is wrong
+        for k in 0..proofs.len() {
+            let mut proofs = proofs.clone();
+            proofs[k].y += F::from(1u32);
+            proofs[k].inner_prod_proof.y += F::from(1u32);
+
+            let result = std::panic::catch_unwind(|| {
+                let mut verifier_transcript = Transcript::new(b"test");
+                hyrax.verify_batch(&proofs, &mut verifier_transcript);
+            });
+            assert!(result.is_err());
+        }
+
+        // Should fail if the opening of a proof is tampered with
+        let mut tampered = proofs.clone();
+        tampered[1].inner_prod_proof.z2 += F::from(1u32);
+        let result = std::panic::catch_unwind(|| {
+            let mut verifier_transcript = Transcript::new(b"test");
+            hyrax.verify_batch(&tampered, &mut verifier_transcript);
+        });
+        assert!(result.is_err());
+    }
 }
@@ -0,0 +1,177 @@
+use crate::spartan::{
+    hyrax::{check_opening, Hyrax, HyraxComm, PolyEvalProof},
+    polynomial::ml_poly::MlPoly,
+    sumcheck::{
+        sumcheck::{init_blinder_poly, prove_sum, verify_sum},
+        SumCheckProof,
+    },
+    transcript::{Transcript, TranscriptProtocol},
+};
+use crate::ScalarField;
+use ark_ec::CurveGroup;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+// A proof that the inner product of a Hyrax-committed vector v and a public vector a
+// is a public value y, without opening v.
+// The prover runs a sumcheck of sum_x v(x) * a(x) = y over the multilinear extensions,
+// and opens v at the random point of the sumcheck in zero knowledge.
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct CommittedInnerProductProof<C: CurveGroup> {
+    pub sc_proof: SumCheckProof<C>,
+    pub vector_eval_proof: PolyEvalProof<C>,
+}
+
+// The Hyrax instance to commit to vectors of length `len` (a power of two)
+// and prove their inner products.
+pub fn inner_product_hyrax<C: CurveGroup>(len: usize) -> Hyrax<C> {
+    assert!(len.is_power_of_two());
+
+    let num_vars = (len as f64).log2() as usize;
+
+    // The blinder polynomial of the degree 2 sumcheck
+    // has at most 3 * num_vars coefficients.
+    let num_bases = std::cmp::max(
+        (3 * num_vars + 1).next_power_of_two(),
+        Hyrax::<C>::det_num_rows(len),
+    );
+
+    Hyrax::new(len, num_bases)
+}
+
+fn append_statement<C: CurveGroup>(
+    comm: &[C],
+    a: &[ScalarField<C>],
+    y: ScalarField<C>,
+    transcript: &mut Transcript<C>,
+) {
+    transcript.append_points(b"comm_vector", comm);
+    transcript.append_scalars(b"public_vector", a);
+    transcript.append_scalar(b"inner_product", y);
+}
+
+// Prove that the inner product of the committed `vector` and `a` is `y`.
+// `comm` must be the commitment to `vector`, and the length of the vectors a power of two.
+pub fn prove_inner_product<C: CurveGroup>(
+    hyrax: &Hyrax<C>,
+    comm: &HyraxComm<C>,
+    vector: &[ScalarField<C>],
+    a: &[ScalarField<C>],
+    y: ScalarField<C>,
+    transcript: &mut Transcript<C>,
+) -> CommittedInnerProductProof<C> {
+    assert_eq!(vector.len(), a.len());
+    assert!(vector.len().is_power_of_two());
+    assert_eq!(
+        vector
+            .iter()
+            .zip(a)
+            .map(|(v, a)| *v * a)
+            .sum::<ScalarField<C>>(),
+        y,
+        "The inner product is not y"
+    );
+
+    let num_vars = (vector.len() as f64).log2() as usize;
+
+    append_statement(&comm.T, a, y, transcript);
+
+    let (blinder_poly, blinder_poly_comm) = init_blinder_poly(num_vars, 2, hyrax, transcript);
+    let (sc_proof, rx) = prove_sum(
+        num_vars,
+        2,
+        &mut vec![vector.to_vec(), a.to_vec()],
+        |v| v[0] * v[1],
+        hyrax,
+        blinder_poly.sum,
+        blinder_poly,
+        &blinder_poly_comm,
+        transcript,
+        b"sc_inner_product",
+    );
+
+    let vector_eval_proof = hyrax.open(comm, rx, transcript);
+
+    CommittedInnerProductProof {
+        sc_proof,
+        vector_eval_proof,
+    }
+}
+
+// Verify that the inner product of the vector committed in `comm` and `a` is `y`.
+// Panics if the proof is invalid.
+pub fn verify_inner_product<C: CurveGroup>(
+    hyrax: &Hyrax<C>,
+    comm: &[C],
+    a: &[ScalarField<C>],
+    y: ScalarField<C>,
+    proof: &CommittedInnerProductProof<C>,
+    transcript: &mut Transcript<C>,
+) {
+    assert!(a.len().is_power_of_two());
+
+    append_statement(comm, a, y, transcript);
+
+    // The evaluation of v is checked against the opening below,
+    // and the verifier evaluates the public vector.
+    let vector_eval = proof.vector_eval_proof.y;
+    let a_poly = MlPoly::new(a.to_vec());
+
+    let rx = verify_sum(
+        &proof.sc_proof,
+        hyrax,
+        y,
+        |rx| vector_eval * a_poly.eval(rx),
+        2,
+        transcript,
+        b"sc_inner_product",
+    )
+    .unwrap_or_else(|err| panic!("{}", err));
+
+    check_opening(hyrax, &proof.vector_eval_proof, comm, &rx, transcript);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ff::Field;
+    use std::panic;
+
+    type Curve = ark_secq256k1::Projective;
+    type F = ark_secq256k1::Fr;
+
+    const LEN: usize = 16;
+    const LABEL: &[u8] = b"test_inner_product";
+
+    #[test]
+    fn test_inner_product() {
+        let hyrax = inner_product_hyrax::<Curve>(LEN);
+
+        let vector = (0..LEN as u64)
+            .map(|i| F::from(i * 3 + 1))
+            .collect::<Vec<F>>();
+        let a = (0..LEN as u64).map(|i| F::from(i + 5)).collect::<Vec<F>>();
+
+        // sum_i (3i + 1)(i + 5) = 3 * 1240 + 16 * 120 + 5 * 16 = 5720
+        let y = F::from(5720u64);
+
+        let comm = hyrax.commit(vector.clone());
+
+        let mut prover_transcript = Transcript::new(LABEL);
+        let proof = prove_inner_product(&hyrax, &comm, &vector, &a, y, &mut prover_transcript);
+
+        let mut verifier_transcript = Transcript::new(LABEL);
+        verify_inner_product(&hyrax, &comm.T, &a, y, &proof, &mut verifier_transcript);
+
+        // Should fail for another inner product, or another public vector
+        let mut other_a = a.clone();
+        other_a[0] += F::ONE;
+
+        for (a, y) in [(a.clone(), y + F::ONE), (other_a, y)] {
+            let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+                let mut verifier_transcript = Transcript::new(LABEL);
+                verify_inner_product(&hyrax, &comm.T, &a, y, &proof, &mut verifier_transcript);
+            }));
+            assert!(result.is_err());
+        }
+    }
+}
@@ -1,11 +1,11 @@
 use crate::{spartan::utils::inner_prod, ScalarField};
 use ark_ec::CurveGroup;
-use ark_ff::{Field, UniformRand};
+use ark_ff::{Field, UniformRand, Zero};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 
 use super::{
     commitment::Gens,
-    transcript::Transcript,
+    transcript::TranscriptProtocol,
     utils::{msm, msm_affine},
 };
 
@@ -27,6 +27,46 @@ pub struct IPAComm<C: CurveGroup> {
     pub blinder: ScalarField<C>,
 }
 
+// A sum of multiples of the generators G, u and H, and of other points,
+// so that the checks of several proofs can be summed into a single multi-scalar multiplication.
+pub(crate) struct CheckTerms<C: CurveGroup> {
+    pub G_scalars: Vec<ScalarField<C>>,
+    pub u_scalar: ScalarField<C>,
+    pub H_scalar: ScalarField<C>,
+    pub scalars: Vec<ScalarField<C>>,
+    pub points: Vec<C>,
+}
+
+impl<C: CurveGroup> CheckTerms<C> {
+    pub fn new() -> Self {
+        Self {
+            G_scalars: vec![],
+            u_scalar: ScalarField::<C>::ZERO,
+            H_scalar: ScalarField::<C>::ZERO,
+            scalars: vec![],
+            points: vec![],
+        }
+    }
+
+    // Add the terms of `other` multiplied by `weight`
+    pub fn add_weighted(&mut self, other: &CheckTerms<C>, weight: ScalarField<C>) {
+        if self.G_scalars.len() < other.G_scalars.len() {
+            self.G_scalars
+                .resize(other.G_scalars.len(), ScalarField::<C>::ZERO);
+        }
+        for (s, other_s) in self.G_scalars.iter_mut().zip(other.G_scalars.iter()) {
+            *s += weight * other_s;
+        }
+
+        self.u_scalar += weight * other.u_scalar;
+        self.H_scalar += weight * other.H_scalar;
+
+        self.scalars
+            .extend(other.scalars.iter().map(|s| weight * s));
+        self.points.extend_from_slice(&other.points);
+    }
+}
+
 // We implement the Polynomial commitment scheme described in
 // section 3 of the halo paper: https://eprint.iacr.org/2019/1021.pdf
 #[derive(Clone)]
@@ -99,7 +139,7 @@ impl<C: CurveGroup> IPA<C> {
         &self,
         comm_a: &IPAComm<C>,
         b: Vec<ScalarField<C>>,
-        transcript: &mut Transcript<C>,
+        transcript: &mut impl TranscriptProtocol<C>,
     ) -> InnerProductProof<C> {
         let a = comm_a.poly.to_vec();
         let mut n = a.len();
@@ -271,16 +311,40 @@ impl<C: CurveGroup> IPA<C> {
         &self,
         proof: &InnerProductProof<C>,
         b: Vec<ScalarField<C>>,
-        transcript: &mut Transcript<C>,
+        transcript: &mut impl TranscriptProtocol<C>,
     ) {
+        assert!(
+            self.is_valid(proof, b, transcript),
+            "Invalid inner product proof"
+        );
+    }
+
+    // Same as `verify`, returning whether the proof is valid instead of panicking
+    pub fn is_valid(
+        &self,
+        proof: &InnerProductProof<C>,
+        b: Vec<ScalarField<C>>,
+        transcript: &mut impl TranscriptProtocol<C>,
+    ) -> bool {
+        let terms = self.check_terms(proof, b, transcript);
+        self.eval_check_terms(&terms).is_zero()
+    }
+
+    // Replay the transcript of the proof, and return the final check of the verifier
+    // as terms which sum to zero if the proof is valid
+    pub(crate) fn check_terms(
+        &self,
+        proof: &InnerProductProof<C>,
+        b: Vec<ScalarField<C>>,
+        transcript: &mut impl TranscriptProtocol<C>,
+    ) -> CheckTerms<C> {
         let n = b.len();
 
         // Append the claimed evaluation to the transcript
         transcript.append_scalar(b"claimed_eval", proof.y);
 
-        // Rescale `u`
+        // Get the challenge that rescales `u`
         let x = transcript.challenge_scalar(b"x");
-        let u = self.gens.u.unwrap() * x;
 
         // Get all the challenges from the transcript
         let r = proof
@@ -311,28 +375,59 @@ impl<C: CurveGroup> IPA<C> {
 
         let b = b_folded[0];
 
-        let G_final = msm_affine::<C>(&s, &self.gens.G_affine[..s.len()]).into_affine();
+        // Verify the zero-knowledge opening
+
+        transcript.append_point(b"R", proof.R);
+        let c = transcript.challenge_scalar(b"c");
+
+        // Q * c + R = (G_final + u * x * b) * z1 + H * z2,
+        // where Q = comm + u * x * y + sum(L_i * r_i^2) + sum(R_i * r_i^-2)
+        // and G_final = sum(s_i * G_i)
+        let mut scalars = Vec::with_capacity(2 * r.len() + 2);
+        let mut points = Vec::with_capacity(2 * r.len() + 2);
 
-        // Compute Q
-        let mut Q = proof.comm + u * proof.y;
+        scalars.push(c);
+        points.push(proof.comm);
 
         for (r_i, L_i) in r.iter().zip(proof.L_vec.iter()) {
-            Q += *L_i * (r_i.square());
+            scalars.push(c * r_i.square());
+            points.push(*L_i);
         }
 
         for (r_inv_i, R_i) in r_inv.iter().zip(proof.R_vec.iter()) {
-            Q += *R_i * (r_inv_i.square());
+            scalars.push(c * r_inv_i.square());
+            points.push(*R_i);
         }
 
-        // Verify the zero-knowledge opening
+        scalars.push(ScalarField::<C>::ONE);
+        points.push(proof.R);
 
-        transcript.append_point(b"R", proof.R);
-        let c = transcript.challenge_scalar(b"c");
+        CheckTerms {
+            G_scalars: s.iter().map(|s_i| -(*s_i * proof.z1)).collect(),
+            u_scalar: x * (c * proof.y - proof.z1 * b),
+            H_scalar: -proof.z2,
+            scalars,
+            points,
+        }
+    }
+
+    // Sum the terms with a single multi-scalar multiplication
+    pub(crate) fn eval_check_terms(&self, terms: &CheckTerms<C>) -> C {
+        let n = terms.G_scalars.len();
+
+        let mut scalars = Vec::with_capacity(n + 2 + terms.scalars.len());
+        scalars.extend_from_slice(&terms.G_scalars);
+        scalars.push(terms.u_scalar);
+        scalars.push(terms.H_scalar);
+        scalars.extend_from_slice(&terms.scalars);
 
-        let lhs = (Q * c).into_affine() + proof.R;
-        let rhs = (G_final + (u * b).into_affine()) * proof.z1 + self.gens.H.unwrap() * proof.z2;
+        let mut points = Vec::with_capacity(scalars.len());
+        points.extend_from_slice(&self.gens.G[..n]);
+        points.push(self.gens.u.unwrap());
+        points.push(self.gens.H.unwrap());
+        points.extend_from_slice(&terms.points);
 
-        assert_eq!(lhs.into_affine(), rhs.into_affine());
+        msm(&scalars, &points)
     }
 }
 
@@ -342,6 +437,7 @@ mod tests {
     use crate::{
         spartan::polynomial::eq_poly::EqPoly,
         spartan::polynomial::ml_poly::MlPoly,
+        spartan::transcript::Transcript,
         timer::{timer_end, timer_start},
     };
 
@@ -0,0 +1,329 @@
+use crate::spartan::{
+    hyrax::{check_opening, Hyrax, PolyEvalProof},
+    polynomial::{eq_poly::EqPoly, ml_poly::MlPoly},
+    sumcheck::{
+        sumcheck::{init_blinder_poly, prove_sum, verify_sum},
+        SumCheckProof,
+    },
+    transcript::{Transcript, TranscriptProtocol},
+};
+use crate::ScalarField;
+use ark_ec::CurveGroup;
+use ark_ff::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+// A proof that every query is an entry of a public table.
+// With m_j the number of times the table entry t_j is queried,
+// and beta a random challenge, the queries f_i are all in the table iff
+// sum_i 1 / (beta - f_i) = sum_j m_j / (beta - t_j)
+// (the logarithmic derivative variant of Plookup, https://eprint.iacr.org/2022/1530).
+// The prover commits to the queries, the multiplicities, and the summands on each side,
+// and proves with a sumcheck per side that the summands are well-formed and sum to `sum`.
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct LookupProof<C: CurveGroup> {
+    pub comm_queries: Vec<C>,
+    pub comm_multiplicities: Vec<C>,
+    pub comm_query_inverses: Vec<C>,
+    pub comm_table_inverses: Vec<C>,
+    pub sum: ScalarField<C>,
+    pub sc_proof_queries: SumCheckProof<C>,
+    pub sc_proof_table: SumCheckProof<C>,
+    pub queries_eval_proof: PolyEvalProof<C>,
+    pub query_inverses_eval_proof: PolyEvalProof<C>,
+    pub multiplicities_eval_proof: PolyEvalProof<C>,
+    pub table_inverses_eval_proof: PolyEvalProof<C>,
+}
+
+// The Hyrax instance to prove and verify lookups of `num_queries` queries
+// into a table of `table_len` entries. Both must be powers of two.
+pub fn lookup_hyrax<C: CurveGroup>(num_queries: usize, table_len: usize) -> Hyrax<C> {
+    assert!(num_queries.is_power_of_two() && table_len.is_power_of_two());
+
+    let n = std::cmp::max(num_queries, table_len);
+    let num_vars = (n as f64).log2() as usize;
+
+    // The blinder polynomials of the degree 3 sumchecks
+    // have at most 4 * num_vars coefficients.
+    let num_bases = std::cmp::max(
+        (4 * num_vars + 1).next_power_of_two(),
+        Hyrax::<C>::det_num_rows(n),
+    );
+
+    Hyrax::new(n, num_bases)
+}
+
+// Prove that every query is an entry of `table`.
+// The number of queries and the length of the table must be powers of two.
+// Panics if a query isn't in the table.
+pub fn prove_lookups<C: CurveGroup>(
+    hyrax: &Hyrax<C>,
+    queries: &[ScalarField<C>],
+    table: &[ScalarField<C>],
+    transcript: &mut Transcript<C>,
+) -> LookupProof<C> {
+    assert!(queries.len().is_power_of_two() && table.len().is_power_of_two());
+
+    let queries_num_vars = (queries.len() as f64).log2() as usize;
+    let table_num_vars = (table.len() as f64).log2() as usize;
+
+    // Count the queries of each table entry.
+    // Entries that appear more than once in the table are counted at their first position.
+    let mut multiplicities = vec![ScalarField::<C>::ZERO; table.len()];
+    for query in queries {
+        let j = table
+            .iter()
+            .position(|entry| entry == query)
+            .expect("The query is not in the table");
+        multiplicities[j] += ScalarField::<C>::ONE;
+    }
+
+    let committed_queries = hyrax.commit(queries.to_vec());
+    let committed_multiplicities = hyrax.commit(multiplicities.clone());
+    transcript.append_points(b"comm_queries", &committed_queries.T);
+    transcript.append_points(b"comm_multiplicities", &committed_multiplicities.T);
+
+    let beta = transcript.challenge_scalar(b"beta");
+
+    let query_inverses = queries
+        .iter()
+        .map(|query| (beta - query).inverse().unwrap())
+        .collect::<Vec<ScalarField<C>>>();
+    let table_inverses = table
+        .iter()
+        .zip(multiplicities.iter())
+        .map(|(entry, m)| *m * (beta - entry).inverse().unwrap())
+        .collect::<Vec<ScalarField<C>>>();
+
+    let sum = query_inverses.iter().sum::<ScalarField<C>>();
+
+    let committed_query_inverses = hyrax.commit(query_inverses.clone());
+    let committed_table_inverses = hyrax.commit(table_inverses.clone());
+    transcript.append_points(b"comm_query_inverses", &committed_query_inverses.T);
+    transcript.append_points(b"comm_table_inverses", &committed_table_inverses.T);
+    transcript.append_scalar(b"sum", sum);
+
+    let tau_queries = transcript.challenge_scalars(queries_num_vars, b"tau_queries");
+    let tau_table = transcript.challenge_scalars(table_num_vars, b"tau_table");
+    let lambda = transcript.challenge_scalar(b"lambda");
+
+    // sum_x eq(tau, x) * (h(x) * (beta - f(x)) - 1) + lambda * h(x) = lambda * sum
+    let (blinder_poly, blinder_poly_comm) =
+        init_blinder_poly(queries_num_vars, 3, hyrax, transcript);
+    let (sc_proof_queries, rx) = prove_sum(
+        queries_num_vars,
+        3,
+        &mut vec![
+            EqPoly::new(tau_queries).evals(),
+            query_inverses,
+            queries.to_vec(),
+        ],
+        |v| v[0] * (v[1] * (beta - v[2]) - ScalarField::<C>::ONE) + lambda * v[1],
+        hyrax,
+        blinder_poly.sum,
+        blinder_poly,
+        &blinder_poly_comm,
+        transcript,
+        b"sc_lookup_queries",
+    );
+
+    // sum_y eq(tau, y) * (h(y) * (beta - t(y)) - m(y)) + lambda * h(y) = lambda * sum
+    let (blinder_poly, blinder_poly_comm) = init_blinder_poly(table_num_vars, 3, hyrax, transcript);
+    let (sc_proof_table, ry) = prove_sum(
+        table_num_vars,
+        3,
+        &mut vec![
+            EqPoly::new(tau_table).evals(),
+            table_inverses,
+            table.to_vec(),
+            multiplicities,
+        ],
+        |v| v[0] * (v[1] * (beta - v[2]) - v[3]) + lambda * v[1],
+        hyrax,
+        blinder_poly.sum,
+        blinder_poly,
+        &blinder_poly_comm,
+        transcript,
+        b"sc_lookup_table",
+    );
+
+    let queries_eval_proof = hyrax.open(&committed_queries, rx.clone(), transcript);
+    let query_inverses_eval_proof = hyrax.open(&committed_query_inverses, rx, transcript);
+    let multiplicities_eval_proof = hyrax.open(&committed_multiplicities, ry.clone(), transcript);
+    let table_inverses_eval_proof = hyrax.open(&committed_table_inverses, ry, transcript);
+
+    LookupProof {
+        comm_queries: committed_queries.T,
+        comm_multiplicities: committed_multiplicities.T,
+        comm_query_inverses: committed_query_inverses.T,
+        comm_table_inverses: committed_table_inverses.T,
+        sum,
+        sc_proof_queries,
+        sc_proof_table,
+        queries_eval_proof,
+        query_inverses_eval_proof,
+        multiplicities_eval_proof,
+        table_inverses_eval_proof,
+    }
+}
+
+// Verify that the `num_queries` committed queries of `proof` are all entries of `table`.
+// Panics if the proof is invalid.
+pub fn verify_lookups<C: CurveGroup>(
+    hyrax: &Hyrax<C>,
+    num_queries: usize,
+    table: &[ScalarField<C>],
+    proof: &LookupProof<C>,
+    transcript: &mut Transcript<C>,
+) {
+    assert!(num_queries.is_power_of_two() && table.len().is_power_of_two());
+
+    let queries_num_vars = (num_queries as f64).log2() as usize;
+    let table_num_vars = (table.len() as f64).log2() as usize;
+
+    transcript.append_points(b"comm_queries", &proof.comm_queries);
+    transcript.append_points(b"comm_multiplicities", &proof.comm_multiplicities);
+
+    let beta = transcript.challenge_scalar(b"beta");
+
+    transcript.append_points(b"comm_query_inverses", &proof.comm_query_inverses);
+    transcript.append_points(b"comm_table_inverses", &proof.comm_table_inverses);
+    transcript.append_scalar(b"sum", proof.sum);
+
+    let tau_queries = EqPoly::new(transcript.challenge_scalars(queries_num_vars, b"tau_queries"));
+    let tau_table = EqPoly::new(transcript.challenge_scalars(table_num_vars, b"tau_table"));
+    let lambda = transcript.challenge_scalar(b"lambda");
+
+    // The evaluations are checked against the openings below
+    let query_eval = proof.queries_eval_proof.y;
+    let query_inverse_eval = proof.query_inverses_eval_proof.y;
+    let multiplicity_eval = proof.multiplicities_eval_proof.y;
+    let table_inverse_eval = proof.table_inverses_eval_proof.y;
+
+    let rx = verify_sum(
+        &proof.sc_proof_queries,
+        hyrax,
+        lambda * proof.sum,
+        |rx| {
+            tau_queries.eval(rx)
+                * (query_inverse_eval * (beta - query_eval) - ScalarField::<C>::ONE)
+                + lambda * query_inverse_eval
+        },
+        3,
+        transcript,
+        b"sc_lookup_queries",
+    )
+    .unwrap_or_else(|err| panic!("{}", err));
+
+    // The table is public, so the verifier evaluates it
+    let table_poly = MlPoly::new(table.to_vec());
+    let ry = verify_sum(
+        &proof.sc_proof_table,
+        hyrax,
+        lambda * proof.sum,
+        |ry| {
+            tau_table.eval(ry)
+                * (table_inverse_eval * (beta - table_poly.eval(ry)) - multiplicity_eval)
+                + lambda * table_inverse_eval
+        },
+        3,
+        transcript,
+        b"sc_lookup_table",
+    )
+    .unwrap_or_else(|err| panic!("{}", err));
+
+    let openings = [
+        (&proof.queries_eval_proof, &proof.comm_queries, &rx),
+        (
+            &proof.query_inverses_eval_proof,
+            &proof.comm_query_inverses,
+            &rx,
+        ),
+        (
+            &proof.multiplicities_eval_proof,
+            &proof.comm_multiplicities,
+            &ry,
+        ),
+        (
+            &proof.table_inverses_eval_proof,
+            &proof.comm_table_inverses,
+            &ry,
+        ),
+    ];
+
+    for (eval_proof, comm, point) in openings {
+        check_opening(hyrax, eval_proof, comm, point, transcript);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::panic;
+
+    type Curve = ark_secq256k1::Projective;
+    type F = ark_secq256k1::Fr;
+
+    const NUM_QUERIES: usize = 64;
+    const LABEL: &[u8] = b"test_lookups";
+
+    #[test]
+    fn test_lookups() {
+        // The byte range table
+        let table = (0..256u64).map(F::from).collect::<Vec<F>>();
+        let hyrax = lookup_hyrax::<Curve>(NUM_QUERIES, table.len());
+
+        let queries = (0..NUM_QUERIES as u64)
+            .map(|i| F::from((i * 37 + 11) % 256))
+            .collect::<Vec<F>>();
+
+        let mut prover_transcript = Transcript::new(LABEL);
+        let proof = prove_lookups(&hyrax, &queries, &table, &mut prover_transcript);
+
+        let mut verifier_transcript = Transcript::new(LABEL);
+        verify_lookups(
+            &hyrax,
+            NUM_QUERIES,
+            &table,
+            &proof,
+            &mut verifier_transcript,
+        );
+
+        // Should fail for a claimed sum that doesn't match the summands
+        let mut invalid_proof = proof.clone();
+        invalid_proof.sum += F::ONE;
+        let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            let mut verifier_transcript = Transcript::new(LABEL);
+            verify_lookups(
+                &hyrax,
+                NUM_QUERIES,
+                &table,
+                &invalid_proof,
+                &mut verifier_transcript,
+            );
+        }));
+        assert!(result.is_err());
+
+        // Should fail against a table without some of the queried entries
+        let small_table = (0..256u64).map(|i| F::from(i % 128)).collect::<Vec<F>>();
+        let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            let mut verifier_transcript = Transcript::new(LABEL);
+            verify_lookups(
+                &hyrax,
+                NUM_QUERIES,
+                &small_table,
+                &proof,
+                &mut verifier_transcript,
+            );
+        }));
+        assert!(result.is_err());
+
+        // The prover can't prove a query out of the table
+        let mut out_of_range = queries.clone();
+        out_of_range[0] = F::from(256u64);
+        let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            let mut prover_transcript = Transcript::new(LABEL);
+            prove_lookups(&hyrax, &out_of_range, &table, &mut prover_transcript);
+        }));
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,69 @@
+use crate::r1cs::R1CS;
+use crate::ScalarField;
+use ark_ec::CurveGroup;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+// Metadata serialized with a proof, so that the verifier can check
+// the proof was generated for the same curve and circuit before verifying it.
+#[derive(Debug, Clone, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ProofMetadata {
+    // The compressed encoding of the generator of the curve
+    pub curve_id: Vec<u8>,
+    pub num_vars: usize,
+    pub circuit_hash: [u8; 32],
+    // The version of the prover. This is informational only
+    // and isn't checked by the verifier.
+    pub prover_version: String,
+}
+
+impl ProofMetadata {
+    pub fn new<C: CurveGroup>(r1cs: &R1CS<ScalarField<C>>) -> Self {
+        let mut curve_id = vec![];
+        C::generator()
+            .into_affine()
+            .serialize_compressed(&mut curve_id)
+            .unwrap();
+
+        // Hash the serialized R1CS instance
+        let mut r1cs_bytes = vec![];
+        r1cs.serialize_compressed(&mut r1cs_bytes).unwrap();
+
+        let mut transcript = merlin::Transcript::new(b"circuit_hash");
+        transcript.append_message(b"r1cs", &r1cs_bytes);
+
+        let mut circuit_hash = [0u8; 32];
+        transcript.challenge_bytes(b"circuit_hash", &mut circuit_hash);
+
+        Self {
+            curve_id,
+            num_vars: r1cs.num_vars,
+            circuit_hash,
+            prover_version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+
+    // Returns whether a proof with this metadata can be verified by a verifier
+    // that expects the `expected` metadata. Same as `check`, without panicking.
+    pub fn is_compatible(&self, expected: &ProofMetadata) -> bool {
+        self.curve_id == expected.curve_id
+            && self.num_vars == expected.num_vars
+            && self.circuit_hash == expected.circuit_hash
+    }
+
+    // Panics if a proof with this metadata can't be verified by a verifier
+    // that expects the `expected` metadata.
+    pub fn check(&self, expected: &ProofMetadata) {
+        assert_eq!(
+            self.curve_id, expected.curve_id,
+            "The proof was generated over a different curve"
+        );
+        assert_eq!(
+            self.num_vars, expected.num_vars,
+            "The proof was generated for a different number of variables"
+        );
+        assert_eq!(
+            self.circuit_hash, expected.circuit_hash,
+            "The proof was generated for a different circuit"
+        );
+    }
+}
@@ -1,7 +1,11 @@
 pub mod commitment;
 pub mod hyrax;
+pub mod inner_product;
 pub mod ipa;
+pub mod lookup;
+pub mod metadata;
 pub mod polynomial;
+pub mod set_membership;
 pub mod spartan;
 pub mod sumcheck;
 pub mod transcript;
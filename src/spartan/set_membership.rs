@@ -0,0 +1,167 @@
+use crate::spartan::{
+    hyrax::{Hyrax, HyraxComm},
+    inner_product::{
+        inner_product_hyrax, prove_inner_product, verify_inner_product, CommittedInnerProductProof,
+    },
+    transcript::{Transcript, TranscriptProtocol},
+};
+use crate::ScalarField;
+use ark_ec::CurveGroup;
+use ark_ff::Field;
+
+// A set of size n is committed as its characteristic polynomial
+// P(X) = prod_i (X - s_i) = X^n + sum_{j < n} c_j X^j,
+// of which only the coefficients c_0..c_{n-1} are committed so that P is monic
+// and can't be the zero polynomial.
+// An element e is in the set iff P(e) = 0, i.e. iff the inner product of the
+// committed coefficients with (1, e, ..., e^{n-1}) is -e^n,
+// which is proven with a sumcheck without revealing the set.
+// The size of the set is public. A smaller set can be padded by repeating an element.
+pub type SetMembershipProof<C> = CommittedInnerProductProof<C>;
+
+// The Hyrax instance to commit to sets of size `set_size` (a power of two).
+pub fn set_membership_hyrax<C: CurveGroup>(set_size: usize) -> Hyrax<C> {
+    inner_product_hyrax(set_size)
+}
+
+// The coefficients c_0..c_{n-1} of the characteristic polynomial of the set.
+fn char_poly_coeffs<F: Field>(set: &[F]) -> Vec<F> {
+    let mut coeffs = vec![F::ONE];
+    for s in set {
+        // Multiply by (X - s)
+        let mut next = vec![F::ZERO; coeffs.len() + 1];
+        for (j, c) in coeffs.iter().enumerate() {
+            next[j + 1] += c;
+            next[j] -= *c * s;
+        }
+        coeffs = next;
+    }
+
+    // Drop the leading coefficient, which is always 1
+    coeffs.pop();
+    coeffs
+}
+
+// (1, e, ..., e^{n-1}) and -e^n
+fn eval_statement<F: Field>(element: F, set_size: usize) -> (Vec<F>, F) {
+    let mut powers = Vec::with_capacity(set_size);
+    let mut power = F::ONE;
+    for _ in 0..set_size {
+        powers.push(power);
+        power *= element;
+    }
+
+    (powers, -power)
+}
+
+pub fn commit_set<C: CurveGroup>(hyrax: &Hyrax<C>, set: &[ScalarField<C>]) -> HyraxComm<C> {
+    hyrax.commit(char_poly_coeffs(set))
+}
+
+// Prove that `element` is in the set committed in `comm`.
+// `comm` must be the commitment to `set` returned by `commit_set`.
+pub fn prove_set_membership<C: CurveGroup>(
+    hyrax: &Hyrax<C>,
+    comm: &HyraxComm<C>,
+    set: &[ScalarField<C>],
+    element: ScalarField<C>,
+    transcript: &mut Transcript<C>,
+) -> SetMembershipProof<C> {
+    assert!(set.contains(&element), "The element is not in the set");
+
+    let coeffs = char_poly_coeffs(set);
+    let (powers, y) = eval_statement(element, set.len());
+
+    transcript.append_scalar(b"set_element", element);
+    prove_inner_product(hyrax, comm, &coeffs, &powers, y, transcript)
+}
+
+// Verify that `element` is in the set of size `set_size` committed in `comm`.
+// Panics if the proof is invalid.
+pub fn verify_set_membership<C: CurveGroup>(
+    hyrax: &Hyrax<C>,
+    comm: &[C],
+    set_size: usize,
+    element: ScalarField<C>,
+    proof: &SetMembershipProof<C>,
+    transcript: &mut Transcript<C>,
+) {
+    let (powers, y) = eval_statement(element, set_size);
+
+    transcript.append_scalar(b"set_element", element);
+    verify_inner_product(hyrax, comm, &powers, y, proof, transcript);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::panic;
+
+    type Curve = ark_secq256k1::Projective;
+    type F = ark_secq256k1::Fr;
+
+    const SET_SIZE: usize = 8;
+    const LABEL: &[u8] = b"test_set_membership";
+
+    #[test]
+    fn test_char_poly_coeffs() {
+        // (X - 2)(X - 3) = X^2 - 5X + 6
+        let coeffs = char_poly_coeffs(&[F::from(2u32), F::from(3u32)]);
+        assert_eq!(coeffs, vec![F::from(6u32), -F::from(5u32)]);
+    }
+
+    #[test]
+    fn test_set_membership() {
+        let hyrax = set_membership_hyrax::<Curve>(SET_SIZE);
+
+        let set = (0..SET_SIZE as u64)
+            .map(|i| F::from(i * i + 7))
+            .collect::<Vec<F>>();
+        let comm = commit_set(&hyrax, &set);
+
+        let element = set[5];
+        let mut prover_transcript = Transcript::new(LABEL);
+        let proof = prove_set_membership(&hyrax, &comm, &set, element, &mut prover_transcript);
+
+        let mut verifier_transcript = Transcript::new(LABEL);
+        verify_set_membership(
+            &hyrax,
+            &comm.T,
+            SET_SIZE,
+            element,
+            &proof,
+            &mut verifier_transcript,
+        );
+
+        // Should fail to verify the proof for a non-member
+        let non_member = F::from(8u32);
+        let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            let mut verifier_transcript = Transcript::new(LABEL);
+            verify_set_membership(
+                &hyrax,
+                &comm.T,
+                SET_SIZE,
+                non_member,
+                &proof,
+                &mut verifier_transcript,
+            );
+        }));
+        assert!(result.is_err());
+
+        // A non-member doesn't evaluate the polynomial to zero,
+        // so a proof can't be forged by claiming that it does
+        let (powers, y) = eval_statement(non_member, SET_SIZE);
+        let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            let mut prover_transcript = Transcript::new(LABEL);
+            prove_inner_product(
+                &hyrax,
+                &comm,
+                &char_poly_coeffs(&set),
+                &powers,
+                y,
+                &mut prover_transcript,
+            );
+        }));
+        assert!(result.is_err());
+    }
+}
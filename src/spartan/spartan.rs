@@ -1,13 +1,18 @@
 use super::{
     hyrax::PolyEvalProof,
+    ipa::IPAComm,
+    metadata::ProofMetadata,
     polynomial::sparse_ml_poly::SparseMLPoly,
-    sumcheck::{sumcheck::init_blinder_poly, SumCheckProof},
+    sumcheck::{
+        sumcheck::{append_blinder_poly, sample_blinder_poly, BlinderPoly},
+        SumCheckProof,
+    },
 };
 use crate::spartan::{
-    hyrax::Hyrax,
+    hyrax::{Hyrax, HyraxComm},
     polynomial::eq_poly::EqPoly,
-    sumcheck::{sumcheck::verify_sum, SumCheckPhase1, SumCheckPhase2},
-    transcript::Transcript,
+    sumcheck::{sumcheck::verify_sum, unipoly::UniPoly, SumCheckPhase1, SumCheckPhase2},
+    transcript::{Transcript, TranscriptProtocol},
 };
 use crate::{
     r1cs::R1CS,
@@ -20,6 +25,7 @@ use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 
 #[derive(Clone, CanonicalDeserialize, CanonicalSerialize)]
 pub struct SpartanProof<C: CurveGroup> {
+    pub metadata: ProofMetadata,
     pub pub_input: Vec<ScalarField<C>>,
     pub sc_proof_1: SumCheckProof<C>,
     pub sc_proof_2: SumCheckProof<C>,
@@ -29,10 +35,40 @@ pub struct SpartanProof<C: CurveGroup> {
     pub v_C: ScalarField<C>,
 }
 
+// A step of the prover, reported by `Spartan::prove_with_progress`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProverStep {
+    CommitWitness,
+    // A round of the first (1) or the second (2) sumcheck
+    SumcheckRound { sumcheck: usize, round: usize },
+    OpenWitness,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProgressEvent {
+    pub step: ProverStep,
+    // The fraction of the proof that is complete after this step
+    pub fraction: f64,
+}
+
 pub struct Spartan<C: CurveGroup> {
     pub label: &'static [u8],
     pub r1cs: R1CS<ScalarField<C>>,
     pub hyrax: Hyrax<C>,
+    pub metadata: ProofMetadata,
+    // Commit the witness while multiplying the matrices with the Z vector.
+    // Only takes effect with the `parallel` feature, which enables it by default.
+    pub pipelined: bool,
+}
+
+// The outputs of `Spartan::precompute`
+struct Precomputed<C: CurveGroup> {
+    committed_witness: HyraxComm<C>,
+    Az: Vec<ScalarField<C>>,
+    Bz: Vec<ScalarField<C>>,
+    Cz: Vec<ScalarField<C>>,
+    sc1_blinder: (BlinderPoly<ScalarField<C>>, IPAComm<C>),
+    sc2_blinder: (BlinderPoly<ScalarField<C>>, IPAComm<C>),
 }
 
 impl<C: CurveGroup> Spartan<C> {
@@ -45,8 +81,15 @@ impl<C: CurveGroup> Spartan<C> {
         let num_bases = std::cmp::max((4 * m + 1).next_power_of_two(), Hyrax::<C>::det_num_rows(n));
 
         let hyrax = Hyrax::new(n, num_bases);
-
-        Self { label, r1cs, hyrax }
+        let metadata = ProofMetadata::new::<C>(&r1cs);
+
+        Self {
+            label,
+            r1cs,
+            hyrax,
+            metadata,
+            pipelined: cfg!(feature = "parallel"),
+        }
     }
 
     pub fn prove(
@@ -54,6 +97,30 @@ impl<C: CurveGroup> Spartan<C> {
         r1cs_witness: &[ScalarField<C>],
         r1cs_input: &[ScalarField<C>],
     ) -> (SpartanProof<C>, Vec<ScalarField<C>>) {
+        self.prove_with_progress(r1cs_witness, r1cs_input, |_| {})
+    }
+
+    // Same as `prove`, calling `progress` when the witness is committed,
+    // after each sumcheck round, and when the witness is opened.
+    pub fn prove_with_progress(
+        &self,
+        r1cs_witness: &[ScalarField<C>],
+        r1cs_input: &[ScalarField<C>],
+        mut progress: impl FnMut(ProgressEvent),
+    ) -> (SpartanProof<C>, Vec<ScalarField<C>>) {
+        let m = (self.r1cs.z_len() as f64).log2() as usize;
+
+        // Committing and opening the witness, and the rounds of the two sumchecks
+        let num_steps = 2 + 2 * m;
+        let mut completed_steps = 0;
+        let mut report = |step: ProverStep| {
+            completed_steps += 1;
+            progress(ProgressEvent {
+                step,
+                fraction: completed_steps as f64 / num_steps as f64,
+            });
+        };
+
         let mut transcript = Transcript::new(self.label);
 
         // Pad the witness vector to make the length a power of two
@@ -65,10 +132,18 @@ impl<C: CurveGroup> Spartan<C> {
 
         // Construct the `Z` vector from the witness and input
         let Z = R1CS::construct_z(r1cs_witness, r1cs_input);
-        // Commit the witness polynomial
-        let comm_witness_timer = profiler_start("Commit witness");
-        let committed_witness = self.hyrax.commit(padded_r1cs_witness);
-        profiler_end(comm_witness_timer);
+
+        // Commit the witness polynomial and the blinder polynomials,
+        // and multiply the A, B, and C matrices with the Z vector
+        let Precomputed {
+            committed_witness,
+            mut Az,
+            mut Bz,
+            mut Cz,
+            sc1_blinder,
+            sc2_blinder,
+        } = self.precompute(padded_r1cs_witness, &Z, m);
+        report(ProverStep::CommitWitness);
 
         // Add the witness commitment to the transcript
         transcript.append_points(b"T", &committed_witness.T);
@@ -77,13 +152,6 @@ impl<C: CurveGroup> Spartan<C> {
         // Phase 1
         // ###################
 
-        let m = (self.r1cs.z_len() as f64).log2() as usize;
-
-        // Multiply the A, B, and C matrices with the Z vector
-        let mut Az = self.r1cs.A.mul_vector(&Z);
-        let mut Bz = self.r1cs.B.mul_vector(&Z);
-        let mut Cz = self.r1cs.C.mul_vector(&Z);
-
         // Resize the vectors so we can apply the sumcheck
         Az.resize(Z.len(), ScalarField::<C>::ZERO);
         Bz.resize(Z.len(), ScalarField::<C>::ZERO);
@@ -93,10 +161,8 @@ impl<C: CurveGroup> Spartan<C> {
 
         // We implement the zero-knowledge sumcheck protocol
         // described in Section 4.1 https://eprint.iacr.org/2019/317.pdf.
-        let init_blinder_poly_timer = profiler_start("Init blinder poly");
-        let (sc1_blinder_poly, sc1_blinder_poly_comm) =
-            init_blinder_poly(m, 3, &self.hyrax, &mut transcript);
-        profiler_end(init_blinder_poly_timer);
+        let (sc1_blinder_poly, sc1_blinder_poly_comm) = sc1_blinder;
+        append_blinder_poly(&sc1_blinder_poly, &sc1_blinder_poly_comm, &mut transcript);
 
         let sc_phase_1_timer = profiler_start("Sumcheck phase 1");
 
@@ -109,6 +175,7 @@ impl<C: CurveGroup> Spartan<C> {
             sc1_blinder_poly,
             &sc1_blinder_poly_comm,
             &mut transcript,
+            &mut |round| report(ProverStep::SumcheckRound { sumcheck: 1, round }),
         );
 
         profiler_end(sc_phase_1_timer);
@@ -130,8 +197,8 @@ impl<C: CurveGroup> Spartan<C> {
             r.as_slice().try_into().unwrap(),
         );
 
-        let (sc2_blinder_poly, sc2_blinder_poly_comm) =
-            init_blinder_poly(m, 2, &self.hyrax, &mut transcript);
+        let (sc2_blinder_poly, sc2_blinder_poly_comm) = sc2_blinder;
+        append_blinder_poly(&sc2_blinder_poly, &sc2_blinder_poly_comm, &mut transcript);
 
         let (sc_proof_2, ry) = sc_phase_2.prove(
             &self.hyrax,
@@ -139,6 +206,7 @@ impl<C: CurveGroup> Spartan<C> {
             sc2_blinder_poly,
             &sc2_blinder_poly_comm,
             &mut transcript,
+            &mut |round| report(ProverStep::SumcheckRound { sumcheck: 2, round }),
         );
 
         profiler_end(sc_phase_2_timer);
@@ -150,12 +218,14 @@ impl<C: CurveGroup> Spartan<C> {
             self.hyrax
                 .open(&committed_witness, ry[1..].to_vec(), &mut transcript);
         profiler_end(z_open_timer);
+        report(ProverStep::OpenWitness);
 
         // Prove the evaluation of the polynomials A(y), B(y), C(y) at ry
 
         let rx_ry = vec![ry, rx].concat();
         (
             SpartanProof {
+                metadata: self.metadata.clone(),
                 pub_input: r1cs_input.to_vec(),
                 sc_proof_1,
                 sc_proof_2,
@@ -168,7 +238,111 @@ impl<C: CurveGroup> Spartan<C> {
         )
     }
 
+    // The work of the prover that doesn't depend on the transcript:
+    // committing the witness and the blinder polynomials of the two sumchecks,
+    // and multiplying the A, B, and C matrices with the Z vector.
+    // The commitments run alongside the multiplications if `pipelined` is set
+    // and the `parallel` feature is enabled.
+    // Either way, the outputs are appended to the transcript in the same order.
+    fn precompute(
+        &self,
+        padded_r1cs_witness: Vec<ScalarField<C>>,
+        Z: &[ScalarField<C>],
+        m: usize,
+    ) -> Precomputed<C> {
+        let commit = || {
+            let comm_witness_timer = profiler_start("Commit witness");
+            let committed_witness = self.hyrax.commit(padded_r1cs_witness);
+            profiler_end(comm_witness_timer);
+
+            let init_blinder_poly_timer = profiler_start("Init blinder poly");
+            let sc1_blinder = sample_blinder_poly(m, SumCheckPhase1::<C>::POLY_DEGREE, &self.hyrax);
+            let sc2_blinder = sample_blinder_poly(m, SumCheckPhase2::<C>::POLY_DEGREE, &self.hyrax);
+            profiler_end(init_blinder_poly_timer);
+
+            (committed_witness, sc1_blinder, sc2_blinder)
+        };
+
+        let multiply = || {
+            (
+                self.r1cs.A.mul_vector(Z),
+                self.r1cs.B.mul_vector(Z),
+                self.r1cs.C.mul_vector(Z),
+            )
+        };
+
+        #[cfg(feature = "parallel")]
+        let ((committed_witness, sc1_blinder, sc2_blinder), (Az, Bz, Cz)) = if self.pipelined {
+            rayon::join(commit, multiply)
+        } else {
+            (commit(), multiply())
+        };
+
+        #[cfg(not(feature = "parallel"))]
+        let ((committed_witness, sc1_blinder, sc2_blinder), (Az, Bz, Cz)) = (commit(), multiply());
+
+        Precomputed {
+            committed_witness,
+            Az,
+            Bz,
+            Cz,
+            sc1_blinder,
+            sc2_blinder,
+        }
+    }
+
+    // Runs only the cheap checks of `verify`: the metadata, the public input,
+    // the sizes of the sumcheck proofs, and the first round of the first sumcheck.
+    // Returns true if the proof is certainly invalid. A proof that isn't rejected
+    // may still be invalid, so only `verify` can accept a proof.
+    pub fn quick_reject(&self, proof: &SpartanProof<C>, pub_input: &[ScalarField<C>]) -> bool {
+        if !proof.metadata.is_compatible(&self.metadata) {
+            return true;
+        }
+
+        if proof.pub_input.len() != self.r1cs.num_input || proof.pub_input != pub_input {
+            return true;
+        }
+
+        // The sumchecks have a round per variable, with round polynomials
+        // of degree at most the degree of each sumcheck.
+        let m = (self.r1cs.z_len() as f64).log2() as usize;
+        let is_well_sized = |sc_proof: &SumCheckProof<C>, poly_degree: usize| {
+            sc_proof.round_poly_coeffs.len() == m
+                && sc_proof
+                    .round_poly_coeffs
+                    .iter()
+                    .all(|coeffs| !coeffs.is_empty() && coeffs.len() <= poly_degree + 1)
+        };
+
+        if !is_well_sized(&proof.sc_proof_1, SumCheckPhase1::<C>::POLY_DEGREE)
+            || !is_well_sized(&proof.sc_proof_2, SumCheckPhase2::<C>::POLY_DEGREE)
+        {
+            return true;
+        }
+
+        // Replay the transcript of `verify` up to the first round of the first sumcheck
+        let mut transcript = Transcript::new(self.label);
+        transcript.append_points(b"T", &proof.witness_eval_proof.T);
+        let _tau = transcript.challenge_scalars(m, b"tau");
+
+        let sc_proof = &proof.sc_proof_1;
+        transcript.append_scalar(b"blinder_poly_sum", sc_proof.blinder_poly_sum);
+        transcript.append_point(b"blinder_poly_comm", sc_proof.blinder_poly_eval_proof.comm);
+        let rho = transcript.challenge_scalar(b"sc_phase_1");
+
+        // The first sumcheck is over a zero sum
+        let target = rho * sc_proof.blinder_poly_sum;
+
+        let first_round_poly = UniPoly::new(sc_proof.round_poly_coeffs[0].clone());
+        first_round_poly.eval(ScalarField::<C>::ZERO) + first_round_poly.eval(ScalarField::<C>::ONE)
+            != target
+    }
+
     pub fn verify(&self, proof: &SpartanProof<C>) {
+        // Check the proof is compatible with this verifier before verifying it
+        proof.metadata.check(&self.metadata);
+
         let mut transcript = Transcript::new(self.label);
         transcript.append_points(b"T", &proof.witness_eval_proof.T);
 
@@ -201,10 +375,11 @@ impl<C: CurveGroup> Spartan<C> {
             &self.hyrax,
             sc_phase1_sum_target,
             sc_phase1_poly,
-            3,
+            SumCheckPhase1::<C>::POLY_DEGREE,
             &mut transcript,
             b"sc_phase_1",
-        );
+        )
+        .unwrap_or_else(|err| panic!("Invalid sumcheck phase 1 proof: {}", err));
 
         // ############################
         // Verify phase 2 sumcheck
@@ -248,15 +423,16 @@ impl<C: CurveGroup> Spartan<C> {
             eval
         };
 
-        let _ = verify_sum(
+        verify_sum(
             &proof.sc_proof_2,
             &self.hyrax,
             sc_phase2_sum_target,
             sc_phase2_poly,
-            2,
+            SumCheckPhase2::<C>::POLY_DEGREE,
             &mut transcript,
             b"sc_phase_2",
-        );
+        )
+        .unwrap_or_else(|err| panic!("Invalid sumcheck phase 2 proof: {}", err));
 
         let pcs_verify_timer = profiler_start("Verify PCS");
         self.hyrax
@@ -273,6 +449,8 @@ mod tests {
         frontend::test_utils::mock_circuit,
         timer::{timer_end, timer_start},
     };
+    use ark_ec::AffineRepr;
+    use std::panic;
 
     type Curve = ark_secq256k1::Projective;
     type F = ark_secq256k1::Fr;
@@ -317,4 +495,152 @@ mod tests {
         assert!(result.is_err(), "Should assert invalid public input");
          */
     }
+
+    #[test]
+    fn test_spartan_progress() {
+        let synthesizer = mock_circuit(2usize.pow(4));
+        let mut cs = ConstraintSystem::new();
+        cs.set_constraints(&synthesizer);
+        let r1cs = cs.to_r1cs();
+
+        let priv_input = vec![F::from(1), F::from(2)];
+        let pub_input = [priv_input[0] * priv_input[1]];
+        let witness = cs.gen_witness(&synthesizer, &pub_input, &priv_input);
+
+        let spartan = Spartan::<Curve>::new(b"test_spartan_progress", r1cs);
+        let m = (spartan.r1cs.z_len() as f64).log2() as usize;
+
+        let mut events = vec![];
+        let (proof, _) = spartan.prove_with_progress(&witness, &pub_input, |event| {
+            events.push(event);
+        });
+        spartan.verify(&proof);
+
+        let mut expected_steps = vec![ProverStep::CommitWitness];
+        for sumcheck in [1, 2] {
+            for round in 0..m {
+                expected_steps.push(ProverStep::SumcheckRound { sumcheck, round });
+            }
+        }
+        expected_steps.push(ProverStep::OpenWitness);
+
+        let steps = events.iter().map(|e| e.step).collect::<Vec<ProverStep>>();
+        assert_eq!(steps, expected_steps);
+
+        // The fractions increase up to completion
+        assert!(events.windows(2).all(|w| w[0].fraction < w[1].fraction));
+        assert_eq!(events.last().unwrap().fraction, 1.0);
+    }
+
+    #[test]
+    fn test_spartan_pipelined() {
+        let synthesizer = mock_circuit(2usize.pow(4));
+        let mut cs = ConstraintSystem::new();
+        cs.set_constraints(&synthesizer);
+        let r1cs = cs.to_r1cs();
+
+        let priv_input = vec![F::from(1), F::from(2)];
+        let pub_input = [priv_input[0] * priv_input[1]];
+        let witness = cs.gen_witness(&synthesizer, &pub_input, &priv_input);
+
+        let mut spartan = Spartan::<Curve>::new(b"test_spartan_pipelined", r1cs);
+        let m = (spartan.r1cs.z_len() as f64).log2() as usize;
+
+        let mut padded_witness = witness.clone();
+        padded_witness.resize(witness.len().next_power_of_two(), F::ZERO);
+        let Z = R1CS::construct_z(&witness, &pub_input);
+
+        // The work done ahead of the transcript is the same with and without pipelining,
+        // except for the randomness of the commitments
+        spartan.pipelined = false;
+        let sequential = spartan.precompute(padded_witness.clone(), &Z, m);
+        spartan.pipelined = true;
+        let pipelined = spartan.precompute(padded_witness, &Z, m);
+
+        assert_eq!(
+            sequential.committed_witness.w,
+            pipelined.committed_witness.w
+        );
+        assert_eq!(sequential.Az, pipelined.Az);
+        assert_eq!(sequential.Bz, pipelined.Bz);
+        assert_eq!(sequential.Cz, pipelined.Cz);
+        assert_eq!(
+            sequential.sc1_blinder.0.uni_polys.len(),
+            pipelined.sc1_blinder.0.uni_polys.len()
+        );
+        assert_eq!(
+            sequential.sc2_blinder.0.uni_polys.len(),
+            pipelined.sc2_blinder.0.uni_polys.len()
+        );
+
+        // and the proofs of both verify
+        for pipelined in [false, true] {
+            spartan.pipelined = pipelined;
+            let (proof, _) = spartan.prove(&witness, &pub_input);
+            spartan.verify(&proof);
+        }
+    }
+
+    #[test]
+    fn test_spartan_quick_reject() {
+        let synthesizer = mock_circuit(2usize.pow(4));
+        let mut cs = ConstraintSystem::new();
+        cs.set_constraints(&synthesizer);
+        let r1cs = cs.to_r1cs();
+
+        let priv_input = vec![F::from(1), F::from(2)];
+        let pub_input = [priv_input[0] * priv_input[1]];
+        let witness = cs.gen_witness(&synthesizer, &pub_input, &priv_input);
+
+        let spartan = Spartan::<Curve>::new(b"test_spartan_quick_reject", r1cs);
+        let (proof, _) = spartan.prove(&witness, &pub_input);
+
+        assert!(!spartan.quick_reject(&proof, &pub_input));
+        assert!(spartan.quick_reject(&proof, &[pub_input[0] + F::ONE]));
+
+        // A proof with a malformed first round is rejected without verifying it in full
+        let mut invalid_proof = proof.clone();
+        invalid_proof.sc_proof_1.round_poly_coeffs[0][0] += F::ONE;
+        assert!(spartan.quick_reject(&invalid_proof, &pub_input));
+
+        let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            spartan.verify(&invalid_proof);
+        }));
+        assert!(result.is_err());
+
+        // A proof with a missing round is rejected
+        let mut invalid_proof = proof;
+        invalid_proof.sc_proof_2.round_poly_coeffs.pop();
+        assert!(spartan.quick_reject(&invalid_proof, &pub_input));
+    }
+
+    #[test]
+    fn test_spartan_metadata_mismatch() {
+        let synthesizer = mock_circuit(2usize.pow(4));
+        let mut cs = ConstraintSystem::new();
+        cs.set_constraints(&synthesizer);
+        let r1cs = cs.to_r1cs();
+
+        let priv_input = vec![F::from(1), F::from(2)];
+        let pub_input = [priv_input[0] * priv_input[1]];
+
+        let witness = cs.gen_witness(&synthesizer, &pub_input, &priv_input);
+
+        let spartan = Spartan::<Curve>::new(b"test_spartan_metadata", r1cs);
+        let (proof, _) = spartan.prove(&witness, &pub_input);
+
+        // A proof generated over another curve should be rejected
+        let mut invalid_proof = proof.clone();
+        let mut other_curve_id = vec![];
+        ark_secp256k1::Affine::generator()
+            .serialize_compressed(&mut other_curve_id)
+            .unwrap();
+        invalid_proof.metadata.curve_id = other_curve_id;
+        assert_ne!(invalid_proof.metadata.curve_id, proof.metadata.curve_id);
+
+        let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            spartan.verify(&invalid_proof);
+        }));
+        assert!(result.is_err());
+    }
 }
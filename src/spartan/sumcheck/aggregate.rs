@@ -0,0 +1,224 @@
+use super::sumcheck::BlinderPoly;
+use super::SumCheckProof;
+use crate::spartan::hyrax::Hyrax;
+use crate::spartan::ipa::CheckTerms;
+use crate::spartan::sumcheck::unipoly::UniPoly;
+use crate::spartan::transcript::{Transcript, TranscriptProtocol};
+use crate::ScalarField;
+use ark_ec::CurveGroup;
+use ark_ff::{Field, UniformRand, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+#[derive(Debug, Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct AggregateProof<C: CurveGroup> {
+    pub proofs: Vec<SumCheckProof<C>>,
+}
+
+pub fn aggregate<C: CurveGroup>(proofs: &[SumCheckProof<C>]) -> AggregateProof<C> {
+    AggregateProof {
+        proofs: proofs.to_vec(),
+    }
+}
+
+// Verify all the proofs in an aggregate proof in one pass.
+// Instead of checking the round polynomials and the final evaluation of each proof
+// separately, the checks are combined with a random linear combination
+// and verified with a single equation.
+// The openings of the blinder polynomials are combined the same way,
+// and verified with a single multi-scalar multiplication.
+// Returns the challenges of each sumcheck.
+pub fn verify_aggregate<C: CurveGroup>(
+    aggregate_proof: &AggregateProof<C>,
+    hyrax: &Hyrax<C>,
+    sum_targets: &[ScalarField<C>],
+    polys: &[&dyn Fn(&[ScalarField<C>]) -> ScalarField<C>],
+    poly_degree: usize,
+    transcripts: &mut [Transcript<C>],
+    label: &'static [u8],
+) -> Vec<Vec<ScalarField<C>>> {
+    let num_proofs = aggregate_proof.proofs.len();
+    assert_eq!(sum_targets.len(), num_proofs);
+    assert_eq!(polys.len(), num_proofs);
+    assert_eq!(transcripts.len(), num_proofs);
+
+    let mut rng = ark_std::rand::thread_rng();
+
+    // Random linear combination of the checks that should all equal zero
+    let mut combined_check = ScalarField::<C>::ZERO;
+    let mut opening_terms = CheckTerms::new();
+    let mut challenges = Vec::with_capacity(num_proofs);
+
+    for (i, proof) in aggregate_proof.proofs.iter().enumerate() {
+        let transcript = &mut transcripts[i];
+
+        transcript.append_scalar(b"blinder_poly_sum", proof.blinder_poly_sum);
+        transcript.append_point(b"blinder_poly_comm", proof.blinder_poly_eval_proof.comm);
+
+        let rho = transcript.challenge_scalar(label);
+
+        let poly_num_vars = proof.round_poly_coeffs.len();
+        let challenge = transcript.challenge_scalars(poly_num_vars, label);
+
+        let mut target = sum_targets[i] + rho * proof.blinder_poly_sum;
+
+        for (j, coeffs) in proof.round_poly_coeffs.iter().enumerate() {
+            assert!(
+                !coeffs.is_empty() && coeffs.len() <= poly_degree + 1,
+                "Round polynomial {} of proof {} must have at most {} coefficients",
+                j,
+                i,
+                poly_degree + 1
+            );
+
+            let round_poly = UniPoly::new(coeffs.clone());
+            let round_check = round_poly.eval(ScalarField::<C>::ZERO)
+                + round_poly.eval(ScalarField::<C>::ONE)
+                - target;
+
+            combined_check += ScalarField::<C>::rand(&mut rng) * round_check;
+
+            target = round_poly.eval(challenge[j]);
+        }
+
+        let poly_eval = (polys[i])(&challenge) + rho * proof.blinder_poly_eval_proof.y;
+        combined_check += ScalarField::<C>::rand(&mut rng) * (poly_eval - target);
+
+        let round_degrees = proof
+            .round_poly_coeffs
+            .iter()
+            .map(|coeffs| coeffs.len() - 1)
+            .collect::<Vec<usize>>();
+
+        let mut b = BlinderPoly::eval_point_powers_with_degrees(&round_degrees, &challenge);
+        b.resize(b.len().next_power_of_two(), ScalarField::<C>::ZERO);
+
+        let terms = hyrax
+            .ipa
+            .check_terms(&proof.blinder_poly_eval_proof, b, transcript);
+        opening_terms.add_weighted(&terms, ScalarField::<C>::rand(&mut rng));
+
+        challenges.push(challenge);
+    }
+
+    assert_eq!(
+        combined_check,
+        ScalarField::<C>::ZERO,
+        "Aggregate proof verification failed"
+    );
+    assert!(
+        hyrax.ipa.eval_check_terms(&opening_terms).is_zero(),
+        "Invalid blinder polynomial opening"
+    );
+
+    challenges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spartan::polynomial::ml_poly::MlPoly;
+    use crate::spartan::sumcheck::prove_test_sum;
+    use std::panic;
+
+    type Curve = ark_secq256k1::Projective;
+    type Fp = ark_secq256k1::Fr;
+
+    const POLY_NUM_VARS: usize = 4;
+    const POLY_DEGREE: usize = 2;
+    const LABEL: &[u8] = b"test_aggregate";
+
+    // Prove the sum of f_1 * f_2 over the boolean hypercube
+    fn prove_instance(offset: u64) -> (SumCheckProof<Curve>, Fp, MlPoly<Fp>, MlPoly<Fp>) {
+        let poly_num_entries = 2usize.pow(POLY_NUM_VARS as u32);
+
+        let eval_table_1 = (0..poly_num_entries)
+            .map(|i| Fp::from(i as u64 + offset))
+            .collect::<Vec<Fp>>();
+
+        let eval_table_2 = (0..poly_num_entries)
+            .map(|i| Fp::from(i as u64 * offset + 7))
+            .collect::<Vec<Fp>>();
+
+        let poly_1 = MlPoly::new(eval_table_1.clone());
+        let poly_2 = MlPoly::new(eval_table_2.clone());
+
+        let (proof, _, sum_target, _) =
+            prove_test_sum::<Curve>(vec![eval_table_1, eval_table_2], LABEL);
+
+        (proof, sum_target, poly_1, poly_2)
+    }
+
+    #[test]
+    fn test_aggregate() {
+        let poly_num_entries = 2usize.pow(POLY_NUM_VARS as u32);
+        let hyrax = Hyrax::<Curve>::new(poly_num_entries, poly_num_entries);
+
+        let instances = (1..4).map(prove_instance).collect::<Vec<_>>();
+
+        let proofs = instances
+            .iter()
+            .map(|(proof, _, _, _)| proof.clone())
+            .collect::<Vec<SumCheckProof<Curve>>>();
+        let sum_targets = instances
+            .iter()
+            .map(|(_, sum_target, _, _)| *sum_target)
+            .collect::<Vec<Fp>>();
+        let poly_evals = instances
+            .iter()
+            .map(|(_, _, poly_1, poly_2)| move |x: &[Fp]| poly_1.eval(x) * poly_2.eval(x))
+            .collect::<Vec<_>>();
+        let polys = poly_evals
+            .iter()
+            .map(|poly| poly as &dyn Fn(&[Fp]) -> Fp)
+            .collect::<Vec<_>>();
+
+        let aggregate_proof = aggregate(&proofs);
+
+        let mut transcripts = vec![Transcript::<Curve>::new(LABEL); proofs.len()];
+        verify_aggregate(
+            &aggregate_proof,
+            &hyrax,
+            &sum_targets,
+            &polys,
+            POLY_DEGREE,
+            &mut transcripts,
+            LABEL,
+        );
+
+        // Should fail if a round polynomial of one of the proofs is invalid
+        let mut invalid_aggregate_proof = aggregate_proof.clone();
+        invalid_aggregate_proof.proofs[1].round_poly_coeffs[0][0] += Fp::ONE;
+
+        let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            let mut transcripts = vec![Transcript::<Curve>::new(LABEL); proofs.len()];
+            verify_aggregate(
+                &invalid_aggregate_proof,
+                &hyrax,
+                &sum_targets,
+                &polys,
+                POLY_DEGREE,
+                &mut transcripts,
+                LABEL,
+            );
+        }));
+        assert!(result.is_err());
+
+        // Should fail if the blinder opening of one of the proofs is invalid
+        let mut invalid_aggregate_proof = aggregate_proof.clone();
+        invalid_aggregate_proof.proofs[2].blinder_poly_eval_proof.z1 += Fp::ONE;
+
+        let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            let mut transcripts = vec![Transcript::<Curve>::new(LABEL); proofs.len()];
+            verify_aggregate(
+                &invalid_aggregate_proof,
+                &hyrax,
+                &sum_targets,
+                &polys,
+                POLY_DEGREE,
+                &mut transcripts,
+                LABEL,
+            );
+        }));
+        assert!(result.is_err());
+    }
+}
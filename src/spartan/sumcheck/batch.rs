@@ -0,0 +1,309 @@
+use super::sumcheck::{init_blinder_poly, prove_sum, verify_sum};
+use super::{SumCheckError, SumCheckProof};
+use crate::spartan::hyrax::Hyrax;
+use crate::spartan::transcript::{Transcript, TranscriptProtocol};
+use crate::ScalarField;
+use ark_ec::CurveGroup;
+use ark_ff::Field;
+
+// Append the sums of the instances, and get the coefficients that combine them
+fn batch_coeffs<C: CurveGroup>(
+    sums: &[ScalarField<C>],
+    transcript: &mut Transcript<C>,
+) -> Vec<ScalarField<C>> {
+    for sum in sums {
+        transcript.append_scalar(b"batch_sum", *sum);
+    }
+
+    transcript.challenge_scalars(sums.len(), b"batch_coeffs")
+}
+
+// Prove the sums of several instances over the boolean hypercube with a single sumcheck,
+// which proves the sum of a random linear combination of the instances.
+// Each instance is given by its evaluation tables and the function that combines them,
+// as in `prove_sum`, and all the instances must have poly_num_vars variables.
+// The batched proof has a single blinder polynomial, so its evaluation is opened once.
+// Returns the proof and the challenges, at which every instance is evaluated.
+pub fn prove_batch<C: CurveGroup>(
+    poly_num_vars: usize,
+    poly_degree: usize,
+    instances: &[Vec<Vec<ScalarField<C>>>],
+    comb_funcs: &[&(dyn Fn(&[ScalarField<C>]) -> ScalarField<C> + Sync)],
+    hyrax: &Hyrax<C>,
+    transcript: &mut Transcript<C>,
+    label: &'static [u8],
+) -> (SumCheckProof<C>, Vec<ScalarField<C>>) {
+    assert_eq!(
+        instances.len(),
+        comb_funcs.len(),
+        "Each instance needs a combining function"
+    );
+
+    let num_entries = 2usize.pow(poly_num_vars as u32);
+    for (i, eval_tables) in instances.iter().enumerate() {
+        for table in eval_tables {
+            assert_eq!(
+                table.len(),
+                num_entries,
+                "Instance {} has {} entries, but every instance must have {} variables",
+                i,
+                table.len(),
+                poly_num_vars
+            );
+        }
+    }
+
+    let sums = instances
+        .iter()
+        .zip(comb_funcs.iter())
+        .map(|(eval_tables, comb_func)| {
+            (0..num_entries)
+                .map(|x| {
+                    let comb_input = eval_tables
+                        .iter()
+                        .map(|table| table[x])
+                        .collect::<Vec<ScalarField<C>>>();
+                    comb_func(&comb_input)
+                })
+                .sum::<ScalarField<C>>()
+        })
+        .collect::<Vec<ScalarField<C>>>();
+
+    let coeffs = batch_coeffs(&sums, transcript);
+
+    // The tables of all the instances, one after the other
+    let mut eval_tables = instances.concat();
+    let table_counts = instances
+        .iter()
+        .map(|eval_tables| eval_tables.len())
+        .collect::<Vec<usize>>();
+
+    let comb_func = |x: &[ScalarField<C>]| {
+        let mut result = ScalarField::<C>::ZERO;
+        let mut offset = 0;
+        for ((comb_func, count), coeff) in comb_funcs.iter().zip(&table_counts).zip(&coeffs) {
+            result += *coeff * comb_func(&x[offset..(offset + count)]);
+            offset += count;
+        }
+
+        result
+    };
+
+    let (blinder_poly, blinder_poly_comm) =
+        init_blinder_poly(poly_num_vars, poly_degree, hyrax, transcript);
+
+    prove_sum(
+        poly_num_vars,
+        poly_degree,
+        &mut eval_tables,
+        comb_func,
+        hyrax,
+        blinder_poly.sum,
+        blinder_poly,
+        &blinder_poly_comm,
+        transcript,
+        label,
+    )
+}
+
+// Verify a proof of `prove_batch` that the instances sum to `sum_targets`,
+// where `polys` evaluates the combined polynomial of each instance.
+// The proof must have poly_num_vars rounds.
+// Returns the challenges, or the reason the proof is rejected.
+pub fn verify_batch<C: CurveGroup>(
+    proof: &SumCheckProof<C>,
+    poly_num_vars: usize,
+    hyrax: &Hyrax<C>,
+    sum_targets: &[ScalarField<C>],
+    polys: &[&dyn Fn(&[ScalarField<C>]) -> ScalarField<C>],
+    poly_degree: usize,
+    transcript: &mut Transcript<C>,
+    label: &'static [u8],
+) -> Result<Vec<ScalarField<C>>, SumCheckError> {
+    assert_eq!(
+        sum_targets.len(),
+        polys.len(),
+        "Each instance needs a polynomial"
+    );
+    assert_eq!(
+        proof.round_poly_coeffs.len(),
+        poly_num_vars,
+        "The batched proof must have {} rounds",
+        poly_num_vars
+    );
+
+    let coeffs = batch_coeffs(sum_targets, transcript);
+
+    let sum_target = sum_targets
+        .iter()
+        .zip(coeffs.iter())
+        .map(|(sum, coeff)| *sum * coeff)
+        .sum::<ScalarField<C>>();
+
+    let poly = |x: &[ScalarField<C>]| {
+        polys
+            .iter()
+            .zip(coeffs.iter())
+            .map(|(poly, coeff)| *coeff * poly(x))
+            .sum::<ScalarField<C>>()
+    };
+
+    verify_sum(
+        proof,
+        hyrax,
+        sum_target,
+        poly,
+        poly_degree,
+        transcript,
+        label,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spartan::polynomial::ml_poly::MlPoly;
+    use std::panic;
+
+    type Curve = ark_secq256k1::Projective;
+    type Fp = ark_secq256k1::Fr;
+
+    const POLY_NUM_VARS: usize = 3;
+    const POLY_NUM_ENTRIES: usize = 8;
+    const POLY_DEGREE: usize = 2;
+    const LABEL: &[u8] = b"test_batch";
+
+    fn table(num_vars: usize, f: impl Fn(u64) -> u64) -> Vec<Fp> {
+        (0..2u64.pow(num_vars as u32))
+            .map(|i| Fp::from(f(i)))
+            .collect()
+    }
+
+    // f_1 * f_2, f_1 * f_2 - f_3, and f_1
+    fn instances(num_vars: usize) -> Vec<Vec<Vec<Fp>>> {
+        vec![
+            vec![table(num_vars, |i| i + 1), table(num_vars, |i| 2 * i + 3)],
+            vec![
+                table(num_vars, |i| i + 5),
+                table(num_vars, |i| i * i),
+                table(num_vars, |i| 7 * i),
+            ],
+            vec![table(num_vars, |i| 3 * i + 1)],
+        ]
+    }
+
+    fn comb_1(x: &[Fp]) -> Fp {
+        x[0] * x[1]
+    }
+
+    fn comb_2(x: &[Fp]) -> Fp {
+        x[0] * x[1] - x[2]
+    }
+
+    fn comb_3(x: &[Fp]) -> Fp {
+        x[0]
+    }
+
+    #[test]
+    fn test_batch() {
+        let hyrax = Hyrax::<Curve>::new(POLY_NUM_ENTRIES, POLY_NUM_ENTRIES);
+        let instances = instances(POLY_NUM_VARS);
+        let comb_funcs: [&(dyn Fn(&[Fp]) -> Fp + Sync); 3] = [&comb_1, &comb_2, &comb_3];
+
+        let sum_targets = instances
+            .iter()
+            .zip(comb_funcs.iter())
+            .map(|(eval_tables, comb_func)| {
+                (0..eval_tables[0].len())
+                    .map(|x| comb_func(&eval_tables.iter().map(|t| t[x]).collect::<Vec<Fp>>()))
+                    .sum::<Fp>()
+            })
+            .collect::<Vec<Fp>>();
+
+        let mut prover_transcript = Transcript::<Curve>::new(LABEL);
+        let (proof, prover_challenge) = prove_batch(
+            POLY_NUM_VARS,
+            POLY_DEGREE,
+            &instances,
+            &comb_funcs,
+            &hyrax,
+            &mut prover_transcript,
+            LABEL,
+        );
+
+        let ml_polys = instances
+            .iter()
+            .map(|eval_tables| {
+                eval_tables
+                    .iter()
+                    .map(|table| MlPoly::new(table.clone()))
+                    .collect::<Vec<MlPoly<Fp>>>()
+            })
+            .collect::<Vec<_>>();
+        let poly_evals = ml_polys
+            .iter()
+            .zip(comb_funcs.iter())
+            .map(|(ml_polys, comb_func)| {
+                move |x: &[Fp]| {
+                    let evals = ml_polys
+                        .iter()
+                        .map(|poly| poly.eval(x))
+                        .collect::<Vec<Fp>>();
+                    comb_func(&evals)
+                }
+            })
+            .collect::<Vec<_>>();
+        let polys = poly_evals
+            .iter()
+            .map(|poly| poly as &dyn Fn(&[Fp]) -> Fp)
+            .collect::<Vec<_>>();
+
+        let verify = |sum_targets: &[Fp]| {
+            let mut verifier_transcript = Transcript::<Curve>::new(LABEL);
+            verify_batch(
+                &proof,
+                POLY_NUM_VARS,
+                &hyrax,
+                sum_targets,
+                &polys,
+                POLY_DEGREE,
+                &mut verifier_transcript,
+                LABEL,
+            )
+        };
+
+        let verifier_challenge = verify(&sum_targets).unwrap();
+        assert_eq!(prover_challenge, verifier_challenge);
+
+        // Should fail if one of the sums is wrong
+        let mut wrong_sum_targets = sum_targets.clone();
+        wrong_sum_targets[2] += Fp::ONE;
+        assert_eq!(
+            verify(&wrong_sum_targets),
+            Err(SumCheckError::RoundMismatch { round: 0 })
+        );
+    }
+
+    #[test]
+    fn test_batch_different_num_vars() {
+        let hyrax = Hyrax::<Curve>::new(POLY_NUM_ENTRIES, POLY_NUM_ENTRIES);
+
+        let mut instances = instances(POLY_NUM_VARS);
+        instances[1] = self::instances(POLY_NUM_VARS + 1).remove(1);
+        let comb_funcs: [&(dyn Fn(&[Fp]) -> Fp + Sync); 3] = [&comb_1, &comb_2, &comb_3];
+
+        let result = panic::catch_unwind(|| {
+            let mut prover_transcript = Transcript::<Curve>::new(LABEL);
+            prove_batch(
+                POLY_NUM_VARS,
+                POLY_DEGREE,
+                &instances,
+                &comb_funcs,
+                &hyrax,
+                &mut prover_transcript,
+                LABEL,
+            );
+        });
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,153 @@
+use super::SumCheckProof;
+use crate::spartan::ipa::InnerProductProof;
+use crate::spartan::sumcheck::unipoly::UniPoly;
+use crate::spartan::transcript::{Transcript, TranscriptProtocol};
+use crate::ScalarField;
+use ark_ec::CurveGroup;
+use ark_ff::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+// A sumcheck proof where the constant coefficient of each round polynomial is omitted.
+// The coefficients are stored from the highest degree, so the constant coefficient is the last one.
+// The verifier knows the sum p(0) + p(1) = c_d + ... + c_1 + 2 * c_0 of each round polynomial,
+// so the constant coefficient can be recovered from the other coefficients,
+// which saves one scalar per round.
+#[derive(Debug, Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct CompressedSumCheckProof<C: CurveGroup> {
+    pub round_poly_coeffs: Vec<Vec<ScalarField<C>>>,
+    pub blinder_poly_sum: ScalarField<C>,
+    pub blinder_poly_eval_proof: InnerProductProof<C>,
+}
+
+impl<C: CurveGroup> SumCheckProof<C> {
+    pub fn compress(&self) -> CompressedSumCheckProof<C> {
+        CompressedSumCheckProof {
+            round_poly_coeffs: self
+                .round_poly_coeffs
+                .iter()
+                .map(|coeffs| coeffs[..coeffs.len() - 1].to_vec())
+                .collect(),
+            blinder_poly_sum: self.blinder_poly_sum,
+            blinder_poly_eval_proof: self.blinder_poly_eval_proof.clone(),
+        }
+    }
+}
+
+impl<C: CurveGroup> CompressedSumCheckProof<C> {
+    // Recover the constant coefficients of the round polynomials.
+    // The challenges are derived from a copy of the transcript,
+    // which should be in the same state as the one passed to `verify_sum` afterwards.
+    // The sum of each recovered round polynomial matches its target by construction,
+    // so an invalid proof is rejected by the final evaluation check of `verify_sum`.
+    pub fn decompress(
+        &self,
+        sum_target: ScalarField<C>,
+        transcript: &Transcript<C>,
+        label: &'static [u8],
+    ) -> SumCheckProof<C> {
+        let mut transcript = transcript.clone();
+
+        // Derive the challenges the same way as `verify_sum`
+        transcript.append_scalar(b"blinder_poly_sum", self.blinder_poly_sum);
+        transcript.append_point(b"blinder_poly_comm", self.blinder_poly_eval_proof.comm);
+
+        let rho = transcript.challenge_scalar(label);
+        let challenge = transcript.challenge_scalars(self.round_poly_coeffs.len(), label);
+
+        let two_inv = ScalarField::<C>::from(2u32).inverse().unwrap();
+
+        let mut target = sum_target + rho * self.blinder_poly_sum;
+        let mut round_poly_coeffs = Vec::with_capacity(self.round_poly_coeffs.len());
+
+        for (coeffs, challenge_i) in self.round_poly_coeffs.iter().zip(challenge.iter()) {
+            // target = c_d + ... + c_1 + 2 * c_0
+            let c_0 = (target - coeffs.iter().sum::<ScalarField<C>>()) * two_inv;
+
+            let mut round_coeffs = Vec::with_capacity(coeffs.len() + 1);
+            round_coeffs.extend_from_slice(coeffs);
+            round_coeffs.push(c_0);
+
+            target = UniPoly::new(round_coeffs.clone()).eval(*challenge_i);
+            round_poly_coeffs.push(round_coeffs);
+        }
+
+        SumCheckProof {
+            round_poly_coeffs,
+            blinder_poly_sum: self.blinder_poly_sum,
+            blinder_poly_eval_proof: self.blinder_poly_eval_proof.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spartan::polynomial::ml_poly::MlPoly;
+    use crate::spartan::sumcheck::prove_test_sum;
+    use crate::spartan::sumcheck::sumcheck::verify_sum;
+
+    type Curve = ark_secq256k1::Projective;
+    type Fp = ark_secq256k1::Fr;
+
+    #[test]
+    fn test_compressed_sumcheck_proof() {
+        let poly_num_vars = 5;
+        let label = b"test_compressed_sumcheck_proof";
+
+        let evals = (0..2usize.pow(poly_num_vars as u32))
+            .map(|i| Fp::from((i * 7 + 5) as u64))
+            .collect::<Vec<Fp>>();
+        let poly = MlPoly::new(evals.clone());
+
+        let (proof, hyrax, sum_target, verifier_transcript) =
+            prove_test_sum::<Curve>(vec![evals], label);
+
+        let mut proof_bytes = vec![];
+        proof.serialize_compressed(&mut proof_bytes).unwrap();
+
+        let mut compressed_bytes = vec![];
+        proof
+            .compress()
+            .serialize_compressed(&mut compressed_bytes)
+            .unwrap();
+
+        // One scalar is saved per round
+        let scalar_size = Fp::from(0u32).compressed_size();
+        assert_eq!(
+            compressed_bytes.len(),
+            proof_bytes.len() - poly_num_vars * scalar_size
+        );
+
+        let compressed =
+            CompressedSumCheckProof::<Curve>::deserialize_compressed(compressed_bytes.as_slice())
+                .unwrap();
+        let decompressed = compressed.decompress(sum_target, &verifier_transcript, label);
+        assert_eq!(decompressed.round_poly_coeffs, proof.round_poly_coeffs);
+
+        // Both proofs verify, with the same challenges
+        let mut transcript = verifier_transcript.clone();
+        let challenge = verify_sum(
+            &proof,
+            &hyrax,
+            sum_target,
+            |x| poly.eval(x),
+            1,
+            &mut transcript,
+            label,
+        )
+        .unwrap();
+
+        let mut transcript = verifier_transcript.clone();
+        let decompressed_challenge = verify_sum(
+            &decompressed,
+            &hyrax,
+            sum_target,
+            |x| poly.eval(x),
+            1,
+            &mut transcript,
+            label,
+        )
+        .unwrap();
+        assert_eq!(challenge, decompressed_challenge);
+    }
+}
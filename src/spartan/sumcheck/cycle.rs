@@ -0,0 +1,84 @@
+use super::SumCheckProof;
+use ark_ec::CurveGroup;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+// A pair of sumcheck proofs committed on two different curves,
+// e.g. the two curves of a cycle (secp256k1/secq256k1)
+// where the scalar field of each curve is the base field of the other.
+// This is a container only: the two proofs are produced and verified independently.
+#[derive(Debug, Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct CycleSumCheckProof<C1: CurveGroup, C2: CurveGroup> {
+    pub primary: SumCheckProof<C1>,
+    pub secondary: SumCheckProof<C2>,
+}
+
+impl<C1: CurveGroup, C2: CurveGroup> CycleSumCheckProof<C1, C2> {
+    pub fn new(primary: SumCheckProof<C1>, secondary: SumCheckProof<C2>) -> Self {
+        Self { primary, secondary }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spartan::hyrax::Hyrax;
+    use crate::spartan::polynomial::ml_poly::MlPoly;
+    use crate::spartan::sumcheck::prove_test_sum;
+    use crate::spartan::sumcheck::sumcheck::verify_sum;
+    use crate::spartan::transcript::Transcript;
+    use crate::ScalarField;
+
+    type Primary = ark_secq256k1::Projective;
+    type Secondary = ark_secp256k1::Projective;
+
+    const POLY_NUM_VARS: usize = 3;
+    const LABEL: &[u8] = b"test_cycle_sumcheck";
+
+    fn evals<C: CurveGroup>() -> Vec<ScalarField<C>> {
+        (0..2usize.pow(POLY_NUM_VARS as u32))
+            .map(|i| ScalarField::<C>::from(i as u64 + 1))
+            .collect()
+    }
+
+    fn prove<C: CurveGroup>() -> (SumCheckProof<C>, Hyrax<C>) {
+        let (proof, hyrax, _, _) = prove_test_sum(vec![evals::<C>()], LABEL);
+        (proof, hyrax)
+    }
+
+    fn verify<C: CurveGroup>(proof: &SumCheckProof<C>, hyrax: &Hyrax<C>) {
+        let evals = evals::<C>();
+        let poly = MlPoly::new(evals.clone());
+
+        let mut transcript = Transcript::<C>::new(LABEL);
+        verify_sum(
+            proof,
+            hyrax,
+            evals.iter().sum(),
+            |x| poly.eval(x),
+            1,
+            &mut transcript,
+            LABEL,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_cycle_sumcheck_proof() {
+        let (primary_proof, primary_hyrax) = prove::<Primary>();
+        let (secondary_proof, secondary_hyrax) = prove::<Secondary>();
+
+        let proof = CycleSumCheckProof::new(primary_proof, secondary_proof);
+
+        let mut proof_bytes = vec![];
+        proof.serialize_compressed(&mut proof_bytes).unwrap();
+
+        let deserialized = CycleSumCheckProof::<Primary, Secondary>::deserialize_compressed(
+            proof_bytes.as_slice(),
+        )
+        .unwrap();
+
+        // Both components should still verify on their own curve
+        verify(&deserialized.primary, &primary_hyrax);
+        verify(&deserialized.secondary, &secondary_hyrax);
+    }
+}
@@ -1,14 +1,25 @@
+pub mod aggregate;
+pub mod batch;
+pub mod compress;
+pub mod cycle;
 mod sc_phase_1;
 mod sc_phase_2;
+pub mod stream;
 pub mod sumcheck;
 pub mod unipoly;
+pub mod verify_many;
+pub mod versioned;
 
+use super::hyrax::Hyrax;
 use super::ipa::InnerProductProof;
+use super::transcript::Transcript;
 use crate::ScalarField;
 use ark_ec::CurveGroup;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 pub use sc_phase_1::SumCheckPhase1;
 pub use sc_phase_2::SumCheckPhase2;
+use std::fmt;
+use sumcheck::{init_blinder_poly, prove_sum};
 
 #[derive(Debug, Clone, CanonicalSerialize, CanonicalDeserialize)]
 pub struct SumCheckProof<C: CurveGroup> {
@@ -16,3 +27,101 @@ pub struct SumCheckProof<C: CurveGroup> {
     pub blinder_poly_sum: ScalarField<C>,
     pub blinder_poly_eval_proof: InnerProductProof<C>,
 }
+
+// The reason a sumcheck proof is rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SumCheckError {
+    // The round polynomial doesn't sum to the evaluation of the previous round polynomial
+    RoundMismatch { round: usize },
+    // The round polynomial has more coefficients than the degree allows
+    DegreeTooHigh { round: usize },
+    // The round polynomial has fewer coefficients than the claimed degree
+    DegreeTooLow { round: usize },
+    // The last round polynomial doesn't match the evaluation of the polynomial
+    FinalEvalMismatch,
+    // The opening of the blinder polynomial is invalid
+    EvalProofInvalid,
+    // The proof has a different number of rounds than the number of variables
+    NumRoundsMismatch { expected: usize, found: u64 },
+    // The proof can't be deserialized
+    Malformed,
+}
+
+impl fmt::Display for SumCheckError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SumCheckError::RoundMismatch { round } => {
+                write!(
+                    f,
+                    "Round polynomial {} doesn't match the previous round",
+                    round
+                )
+            }
+            SumCheckError::DegreeTooHigh { round } => {
+                write!(f, "Round polynomial {} has too high a degree", round)
+            }
+            SumCheckError::DegreeTooLow { round } => {
+                write!(f, "Round polynomial {} has too low a degree", round)
+            }
+            SumCheckError::FinalEvalMismatch => {
+                write!(f, "The last round polynomial doesn't match the polynomial")
+            }
+            SumCheckError::EvalProofInvalid => {
+                write!(f, "The opening of the blinder polynomial is invalid")
+            }
+            SumCheckError::NumRoundsMismatch { expected, found } => {
+                write!(f, "Expected {} rounds, found {}", expected, found)
+            }
+            SumCheckError::Malformed => {
+                write!(f, "The proof can't be deserialized")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SumCheckError {}
+
+// Prove the sum over the boolean hypercube of the product of multilinear polynomials,
+// given by their evaluation tables. Shared by the sumcheck tests and benchmarks.
+// Returns the proof, the Hyrax instance of the blinder polynomial, the sum,
+// and the transcript before proving, from which the proof can be verified.
+pub fn prove_test_sum<C: CurveGroup>(
+    mut eval_tables: Vec<Vec<ScalarField<C>>>,
+    label: &'static [u8],
+) -> (SumCheckProof<C>, Hyrax<C>, ScalarField<C>, Transcript<C>) {
+    let poly_num_entries = eval_tables[0].len();
+    let poly_num_vars = poly_num_entries.trailing_zeros() as usize;
+    let poly_degree = eval_tables.len();
+
+    let hyrax = Hyrax::new(poly_num_entries, poly_num_entries);
+
+    let sum_target = (0..poly_num_entries)
+        .map(|i| {
+            eval_tables
+                .iter()
+                .map(|table| table[i])
+                .product::<ScalarField<C>>()
+        })
+        .sum::<ScalarField<C>>();
+
+    let mut prover_transcript = Transcript::<C>::new(label);
+    let verifier_transcript = prover_transcript.clone();
+
+    let (blinder_poly, blinder_poly_comm) =
+        init_blinder_poly(poly_num_vars, poly_degree, &hyrax, &mut prover_transcript);
+
+    let (proof, _) = prove_sum(
+        poly_num_vars,
+        poly_degree,
+        &mut eval_tables,
+        |x| x.iter().product(),
+        &hyrax,
+        blinder_poly.sum,
+        blinder_poly,
+        &blinder_poly_comm,
+        &mut prover_transcript,
+        label,
+    );
+
+    (proof, hyrax, sum_target, verifier_transcript)
+}
@@ -1,9 +1,9 @@
-use super::sumcheck::{prove_sum, BlinderPoly};
+use super::sumcheck::{prove_sum_with_progress, BlinderPoly};
 use crate::spartan::hyrax::Hyrax;
 use crate::spartan::ipa::IPAComm;
 use crate::spartan::polynomial::eq_poly::EqPoly;
 use crate::spartan::sumcheck::SumCheckProof;
-use crate::spartan::transcript::Transcript;
+use crate::spartan::transcript::TranscriptProtocol;
 use crate::ScalarField;
 use ark_ec::CurveGroup;
 
@@ -14,6 +14,9 @@ pub struct SumCheckPhase1<C: CurveGroup> {
 }
 
 impl<C: CurveGroup> SumCheckPhase1<C> {
+    // The degree of (Az * Bz - Cz) * eq(tau, x) in each variable
+    pub const POLY_DEGREE: usize = 3;
+
     pub fn new(
         Az_evals: Vec<ScalarField<C>>,
         Bz_evals: Vec<ScalarField<C>>,
@@ -34,14 +37,13 @@ impl<C: CurveGroup> SumCheckPhase1<C> {
         blinder_poly_sum: ScalarField<C>,
         blinder_poly: BlinderPoly<ScalarField<C>>,
         blinder_poly_comm: &IPAComm<C>,
-        transcript: &mut Transcript<C>,
+        transcript: &mut impl TranscriptProtocol<C>,
+        on_round: &mut dyn FnMut(usize),
     ) -> (
         SumCheckProof<C>,
         (ScalarField<C>, ScalarField<C>, ScalarField<C>),
         Vec<ScalarField<C>>,
     ) {
-        let poly_degree = 3;
-
         let mut eval_tables = vec![
             self.Az_evals.clone(),
             self.Bz_evals.clone(),
@@ -50,9 +52,9 @@ impl<C: CurveGroup> SumCheckPhase1<C> {
         ];
         let comb_func = |x: &[ScalarField<C>]| (x[0] * x[1] - x[2]) * x[3];
 
-        let (sumcheck_proof, challenge) = prove_sum(
+        let (sumcheck_proof, challenge) = prove_sum_with_progress(
             poly_num_vars,
-            poly_degree,
+            Self::POLY_DEGREE,
             &mut eval_tables,
             comb_func,
             pcs,
@@ -61,6 +63,7 @@ impl<C: CurveGroup> SumCheckPhase1<C> {
             blinder_poly_comm,
             transcript,
             b"sc_phase_1",
+            on_round,
         );
 
         let v_A = eval_tables[0][0];
@@ -1,10 +1,10 @@
-use super::sumcheck::{prove_sum, BlinderPoly};
+use super::sumcheck::{prove_sum_with_progress, BlinderPoly};
 use crate::r1cs::Matrix;
 use crate::spartan::hyrax::Hyrax;
 use crate::spartan::ipa::IPAComm;
 use crate::spartan::polynomial::eq_poly::EqPoly;
 use crate::spartan::sumcheck::SumCheckProof;
-use crate::spartan::transcript::Transcript;
+use crate::spartan::transcript::TranscriptProtocol;
 use crate::ScalarField;
 use ark_ec::CurveGroup;
 use ark_ff::Field;
@@ -19,6 +19,9 @@ pub struct SumCheckPhase2<C: CurveGroup> {
 }
 
 impl<C: CurveGroup> SumCheckPhase2<C> {
+    // The degree of (r_A * A + r_B * B + r_C * C) * Z in each variable
+    pub const POLY_DEGREE: usize = 2;
+
     pub fn new(
         A_mat: Matrix<ScalarField<C>>,
         B_mat: Matrix<ScalarField<C>>,
@@ -43,7 +46,8 @@ impl<C: CurveGroup> SumCheckPhase2<C> {
         blinder_poly_sum: ScalarField<C>,
         blinder_poly: BlinderPoly<ScalarField<C>>,
         blinder_poly_comm: &IPAComm<C>,
-        transcript: &mut Transcript<C>,
+        transcript: &mut impl TranscriptProtocol<C>,
+        on_round: &mut dyn FnMut(usize),
     ) -> (SumCheckProof<C>, Vec<ScalarField<C>>) {
         let r_A = self.r[0];
         let r_B = self.r[1];
@@ -69,12 +73,11 @@ impl<C: CurveGroup> SumCheckPhase2<C> {
 
         let mut eval_tables = vec![A_evals, B_evals, C_evals, self.Z_evals.clone()];
 
-        let poly_degree = 2;
         let comb_func = |x: &[ScalarField<C>]| (x[0] * r_A + x[1] * r_B + x[2] * r_C) * x[3];
 
-        prove_sum(
+        prove_sum_with_progress(
             num_vars,
-            poly_degree,
+            Self::POLY_DEGREE,
             &mut eval_tables,
             comb_func,
             pcs,
@@ -83,6 +86,7 @@ impl<C: CurveGroup> SumCheckPhase2<C> {
             blinder_poly_comm,
             transcript,
             b"sc_phase_2",
+            on_round,
         )
     }
 }
@@ -0,0 +1,223 @@
+use super::sumcheck::verify_final_eval;
+use super::{SumCheckError, SumCheckProof};
+use crate::spartan::hyrax::Hyrax;
+use crate::spartan::ipa::InnerProductProof;
+use crate::spartan::sumcheck::unipoly::UniPoly;
+use crate::spartan::transcript::{Transcript, TranscriptProtocol};
+use crate::ScalarField;
+use ark_ec::CurveGroup;
+use ark_ff::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use std::io::{Read, Write};
+
+impl<C: CurveGroup> SumCheckProof<C> {
+    // Serialize the proof in the order the verifier uses it,
+    // so that it can be verified round by round with `verify_from_reader`:
+    // the blinder polynomial sum and opening, the number of rounds,
+    // and then the coefficients of each round polynomial.
+    pub fn serialize_streaming<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        self.blinder_poly_sum.serialize_compressed(&mut writer)?;
+        self.blinder_poly_eval_proof
+            .serialize_compressed(&mut writer)?;
+
+        (self.round_poly_coeffs.len() as u64).serialize_compressed(&mut writer)?;
+        for coeffs in &self.round_poly_coeffs {
+            coeffs.serialize_compressed(&mut writer)?;
+        }
+
+        Ok(())
+    }
+}
+
+// Same as `verify_sum`, for a proof serialized with `SumCheckProof::serialize_streaming`.
+// The round polynomials are read and checked one at a time,
+// so only a single round polynomial is held in memory.
+// The number of rounds is read from the proof, and must be `poly_num_vars`.
+pub fn verify_from_reader<C: CurveGroup, R: Read>(
+    mut reader: R,
+    hyrax: &Hyrax<C>,
+    sum_target: ScalarField<C>,
+    poly: impl Fn(&[ScalarField<C>]) -> ScalarField<C>,
+    poly_num_vars: usize,
+    poly_degree: usize,
+    transcript: &mut Transcript<C>,
+    label: &'static [u8],
+) -> Result<Vec<ScalarField<C>>, SumCheckError> {
+    let blinder_poly_sum = ScalarField::<C>::deserialize_compressed(&mut reader)
+        .map_err(|_| SumCheckError::Malformed)?;
+    let blinder_poly_eval_proof = InnerProductProof::<C>::deserialize_compressed(&mut reader)
+        .map_err(|_| SumCheckError::Malformed)?;
+
+    let num_rounds =
+        u64::deserialize_compressed(&mut reader).map_err(|_| SumCheckError::Malformed)?;
+    if num_rounds != poly_num_vars as u64 {
+        return Err(SumCheckError::NumRoundsMismatch {
+            expected: poly_num_vars,
+            found: num_rounds,
+        });
+    }
+
+    // Append the sum and the commitment to the blinder polynomial to the transcript.
+    transcript.append_scalar(b"blinder_poly_sum", blinder_poly_sum);
+    transcript.append_point(b"blinder_poly_comm", blinder_poly_eval_proof.comm);
+
+    // Get the challenge to combine the blinder polynomial with the summed polynomial(s).
+    let rho = transcript.challenge_scalar(label);
+
+    // Get challenges for each round of the sumcheck protocol.
+    let challenge = transcript.challenge_scalars(poly_num_vars, label);
+
+    // Verify the validity of the round polynomials as they are read.
+    let mut target = sum_target + rho * blinder_poly_sum;
+    let mut round_degrees = Vec::with_capacity(poly_num_vars);
+
+    for (i, challenge_i) in challenge.iter().enumerate() {
+        // The coefficients are read one by one after checking their number,
+        // so that the length prefix can't force a large allocation.
+        let num_coeffs =
+            u64::deserialize_compressed(&mut reader).map_err(|_| SumCheckError::Malformed)?;
+        if num_coeffs == 0 {
+            return Err(SumCheckError::DegreeTooLow { round: i });
+        }
+        if num_coeffs > (poly_degree + 1) as u64 {
+            return Err(SumCheckError::DegreeTooHigh { round: i });
+        }
+
+        let coeffs = (0..num_coeffs)
+            .map(|_| ScalarField::<C>::deserialize_compressed(&mut reader))
+            .collect::<Result<Vec<ScalarField<C>>, SerializationError>>()
+            .map_err(|_| SumCheckError::Malformed)?;
+        round_degrees.push(coeffs.len() - 1);
+
+        let round_poly = UniPoly::new(coeffs);
+        if round_poly.eval(ScalarField::<C>::ZERO) + round_poly.eval(ScalarField::<C>::ONE)
+            != target
+        {
+            return Err(SumCheckError::RoundMismatch { round: i });
+        }
+
+        target = round_poly.eval(*challenge_i);
+    }
+
+    verify_final_eval(
+        &blinder_poly_eval_proof,
+        hyrax,
+        poly,
+        target,
+        rho,
+        &round_degrees,
+        &challenge,
+        transcript,
+    )?;
+
+    Ok(challenge)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spartan::polynomial::ml_poly::MlPoly;
+    use crate::spartan::sumcheck::prove_test_sum;
+    use crate::spartan::sumcheck::sumcheck::verify_sum;
+
+    type Curve = ark_secq256k1::Projective;
+    type Fp = ark_secq256k1::Fr;
+
+    #[test]
+    fn test_verify_from_reader() {
+        let poly_num_vars = 5;
+        let label = b"test_verify_from_reader";
+
+        let evals = (0..2usize.pow(poly_num_vars as u32))
+            .map(|i| Fp::from((i * 7 + 5) as u64))
+            .collect::<Vec<Fp>>();
+        let poly = MlPoly::new(evals.clone());
+
+        let (proof, hyrax, sum_target, verifier_transcript) =
+            prove_test_sum::<Curve>(vec![evals], label);
+
+        // Buffered verification
+        let mut transcript = verifier_transcript.clone();
+        let challenge = verify_sum(
+            &proof,
+            &hyrax,
+            sum_target,
+            |x| poly.eval(x),
+            1,
+            &mut transcript,
+            label,
+        )
+        .unwrap();
+
+        // Streamed verification of the same proof
+        let mut bytes = vec![];
+        proof.serialize_streaming(&mut bytes).unwrap();
+
+        let mut streamed_transcript = verifier_transcript.clone();
+        let streamed_challenge = verify_from_reader(
+            bytes.as_slice(),
+            &hyrax,
+            sum_target,
+            |x| poly.eval(x),
+            poly_num_vars,
+            1,
+            &mut streamed_transcript,
+            label,
+        )
+        .unwrap();
+
+        assert_eq!(challenge, streamed_challenge);
+        assert_eq!(
+            transcript.challenge_scalar(b"next"),
+            streamed_transcript.challenge_scalar(b"next")
+        );
+
+        // A truncated proof can't be read
+        let mut transcript = verifier_transcript.clone();
+        let result = verify_from_reader(
+            &bytes[..bytes.len() - 1],
+            &hyrax,
+            sum_target,
+            |x| poly.eval(x),
+            poly_num_vars,
+            1,
+            &mut transcript,
+            label,
+        );
+        assert_eq!(result, Err(SumCheckError::Malformed));
+
+        // A proof with another number of rounds is rejected before reading the rounds
+        let mut transcript = verifier_transcript.clone();
+        let result = verify_from_reader(
+            bytes.as_slice(),
+            &hyrax,
+            sum_target,
+            |x| poly.eval(x),
+            poly_num_vars + 1,
+            1,
+            &mut transcript,
+            label,
+        );
+        assert_eq!(
+            result,
+            Err(SumCheckError::NumRoundsMismatch {
+                expected: poly_num_vars + 1,
+                found: poly_num_vars as u64,
+            })
+        );
+
+        // An invalid proof is rejected with an error instead of a panic
+        let mut transcript = verifier_transcript.clone();
+        let result = verify_from_reader(
+            bytes.as_slice(),
+            &hyrax,
+            sum_target + Fp::from(1u64),
+            |x| poly.eval(x),
+            poly_num_vars,
+            1,
+            &mut transcript,
+            label,
+        );
+        assert_eq!(result, Err(SumCheckError::RoundMismatch { round: 0 }));
+    }
+}
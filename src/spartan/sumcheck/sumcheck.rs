@@ -1,13 +1,16 @@
-use super::SumCheckProof;
+use super::{SumCheckError, SumCheckProof};
 use crate::spartan::hyrax::Hyrax;
-use crate::spartan::ipa::IPAComm;
+use crate::spartan::ipa::{IPAComm, InnerProductProof};
 use crate::spartan::sumcheck::unipoly::UniPoly;
-use crate::spartan::transcript::Transcript;
+use crate::spartan::transcript::TranscriptProtocol;
 use crate::timer::{profiler_end, profiler_start};
 use crate::ScalarField;
 use ark_ec::CurveGroup;
 use ark_ff::{Field, UniformRand};
 
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
 #[derive(Clone)]
 pub struct BlinderPoly<F: Field> {
     pub uni_polys: Vec<UniPoly<F>>,
@@ -48,14 +51,27 @@ impl<F: Field> BlinderPoly<F> {
         }
     }
 
+    // Returns the degree of the blinder polynomial in each variable.
+    pub fn round_degrees(&self) -> Vec<usize> {
+        self.uni_polys
+            .iter()
+            .map(|uni_poly| uni_poly.coeffs.len() - 1)
+            .collect()
+    }
+
     pub fn eval_point_powers(poly_degree: usize, x: &[F]) -> Vec<F> {
+        Self::eval_point_powers_with_degrees(&vec![poly_degree; x.len()], x)
+    }
+
+    // Same as eval_point_powers, but the degree can differ for each variable.
+    pub fn eval_point_powers_with_degrees(round_degrees: &[usize], x: &[F]) -> Vec<F> {
         let mut b = vec![];
 
-        for x_i in x {
+        for (x_i, round_degree) in x.iter().zip(round_degrees.iter()) {
             let mut powers = vec![];
             let mut c_pow = F::ONE;
 
-            for _ in 0..(poly_degree + 1) {
+            for _ in 0..(round_degree + 1) {
                 powers.push(c_pow);
                 c_pow *= *x_i;
             }
@@ -68,11 +84,67 @@ impl<F: Field> BlinderPoly<F> {
     }
 }
 
+// Computes the degree of the summed polynomial in each variable.
+// We assume that each evaluation table appears at most once in each term
+// of the combining function (which holds for all the sumchecks in Spartan),
+// so the degree in a variable is bounded by the number of tables that depend on it.
+pub fn round_degrees<F: Field>(eval_tables: &[Vec<F>], poly_degree: usize) -> Vec<usize> {
+    let num_entries = eval_tables[0].len();
+    let num_vars = (num_entries as f64).log2() as usize;
+
+    (0..num_vars)
+        .map(|j| {
+            let high_index = 2usize.pow((num_vars - j - 1) as u32);
+            let num_dependent_tables = eval_tables
+                .iter()
+                .filter(|table| {
+                    (0..num_entries)
+                        .filter(|i| i & high_index == 0)
+                        .any(|i| table[i] != table[i + high_index])
+                })
+                .count();
+
+            std::cmp::min(num_dependent_tables, poly_degree)
+        })
+        .collect()
+}
+
 pub fn init_blinder_poly<C: CurveGroup>(
     num_vars: usize,
     poly_degree: usize,
     hyrax: &Hyrax<C>,
-    transcript: &mut Transcript<C>,
+    transcript: &mut impl TranscriptProtocol<C>,
+) -> (BlinderPoly<ScalarField<C>>, IPAComm<C>) {
+    init_blinder_poly_with_degrees(&vec![poly_degree; num_vars], hyrax, transcript)
+}
+
+// Samples a blinder polynomial which degree in each variable
+// matches the degree of the corresponding round polynomial.
+pub fn init_blinder_poly_with_degrees<C: CurveGroup>(
+    round_degrees: &[usize],
+    hyrax: &Hyrax<C>,
+    transcript: &mut impl TranscriptProtocol<C>,
+) -> (BlinderPoly<ScalarField<C>>, IPAComm<C>) {
+    let (blinder_poly, blinder_poly_comm) = sample_blinder_poly_with_degrees(round_degrees, hyrax);
+    append_blinder_poly(&blinder_poly, &blinder_poly_comm, transcript);
+
+    (blinder_poly, blinder_poly_comm)
+}
+
+// Same as `init_blinder_poly`, without appending to the transcript,
+// so that the blinder polynomial can be committed ahead of the sumcheck.
+// It must be appended with `append_blinder_poly` before the sumcheck.
+pub fn sample_blinder_poly<C: CurveGroup>(
+    num_vars: usize,
+    poly_degree: usize,
+    hyrax: &Hyrax<C>,
+) -> (BlinderPoly<ScalarField<C>>, IPAComm<C>) {
+    sample_blinder_poly_with_degrees(&vec![poly_degree; num_vars], hyrax)
+}
+
+fn sample_blinder_poly_with_degrees<C: CurveGroup>(
+    round_degrees: &[usize],
+    hyrax: &Hyrax<C>,
 ) -> (BlinderPoly<ScalarField<C>>, IPAComm<C>) {
     // We implement the zero-knowledge sumcheck protocol
     // described in Section 4.1 https://eprint.iacr.org/2019/317.pdf
@@ -82,9 +154,10 @@ pub fn init_blinder_poly<C: CurveGroup>(
     // Sample a blinding polynomial g(x_1, ..., x_m)
 
     // The coefficients are stored from high to low degree.
-    let random_coeffs = (0..num_vars)
-        .map(|_| {
-            (0..(poly_degree + 1))
+    let random_coeffs = round_degrees
+        .iter()
+        .map(|round_degree| {
+            (0..(round_degree + 1))
                 .map(|_| ScalarField::<C>::rand(&mut rng))
                 .collect::<Vec<ScalarField<C>>>()
         })
@@ -109,96 +182,133 @@ pub fn init_blinder_poly<C: CurveGroup>(
     let blinder_poly_comm = hyrax.ipa.commit(random_coeffs_flat, blinder);
     profiler_end(commit_b_timer);
 
-    // Append the sum and the commitment of the blinder polynomial to the transcript.
+    (blinder_poly, blinder_poly_comm)
+}
+
+// Append the sum and the commitment of the blinder polynomial to the transcript.
+pub fn append_blinder_poly<C: CurveGroup>(
+    blinder_poly: &BlinderPoly<ScalarField<C>>,
+    blinder_poly_comm: &IPAComm<C>,
+    transcript: &mut impl TranscriptProtocol<C>,
+) {
     transcript.append_scalar(b"blinder_poly_sum", blinder_poly.sum);
     transcript.append_point(b"blinder_poly_comm", blinder_poly_comm.comm);
-
-    (blinder_poly, blinder_poly_comm)
 }
 
 // This function implements the zero-knowledge sumcheck protocol, and
 // is agnostic of the polynomial(s) being summed.
 // The function caller must provide the polynomial(s)'s evaluation tables,
 // and the function that combines the evaluation tables (i.e. combines the evaluations of polynomials).
+// The degree of each round polynomial follows the degree of the blinder polynomial
+// in the corresponding variable, which is at most poly_degree.
 
 pub fn prove_sum<C: CurveGroup>(
     poly_num_vars: usize,
     poly_degree: usize,
     eval_tables: &mut Vec<Vec<ScalarField<C>>>,
-    comb_func: impl Fn(&[ScalarField<C>]) -> ScalarField<C>,
+    comb_func: impl Fn(&[ScalarField<C>]) -> ScalarField<C> + Sync,
     hyrax: &Hyrax<C>,
     blinder_poly_sum: ScalarField<C>,
     blinder_poly: BlinderPoly<ScalarField<C>>,
     blinder_poly_comm: &IPAComm<C>,
-    transcript: &mut Transcript<C>,
+    transcript: &mut impl TranscriptProtocol<C>,
+    label: &'static [u8],
+) -> (SumCheckProof<C>, Vec<ScalarField<C>>) {
+    prove_sum_with_progress(
+        poly_num_vars,
+        poly_degree,
+        eval_tables,
+        comb_func,
+        hyrax,
+        blinder_poly_sum,
+        blinder_poly,
+        blinder_poly_comm,
+        transcript,
+        label,
+        &mut |_| {},
+    )
+}
+
+// Prove the sum of a combination of the tables of degree poly_degree in each variable,
+// e.g. a custom gate, with a blinder polynomial of the same degree.
+// Every round polynomial is evaluated at poly_degree + 1 points and interpolated,
+// so it has exactly poly_degree + 1 coefficients, as checked by `verify_with_degree`.
+pub fn prove_with_degree<C: CurveGroup>(
+    poly_num_vars: usize,
+    poly_degree: usize,
+    eval_tables: &mut Vec<Vec<ScalarField<C>>>,
+    comb_func: impl Fn(&[ScalarField<C>]) -> ScalarField<C> + Sync,
+    hyrax: &Hyrax<C>,
+    transcript: &mut impl TranscriptProtocol<C>,
     label: &'static [u8],
 ) -> (SumCheckProof<C>, Vec<ScalarField<C>>) {
-    let num_tables = eval_tables.len();
+    let (blinder_poly, blinder_poly_comm) =
+        init_blinder_poly(poly_num_vars, poly_degree, hyrax, transcript);
+
+    prove_sum(
+        poly_num_vars,
+        poly_degree,
+        eval_tables,
+        comb_func,
+        hyrax,
+        blinder_poly.sum,
+        blinder_poly,
+        &blinder_poly_comm,
+        transcript,
+        label,
+    )
+}
+
+// Same as `prove_sum`, calling `on_round` with the index of each round once it's complete.
+pub fn prove_sum_with_progress<C: CurveGroup>(
+    poly_num_vars: usize,
+    poly_degree: usize,
+    eval_tables: &mut Vec<Vec<ScalarField<C>>>,
+    comb_func: impl Fn(&[ScalarField<C>]) -> ScalarField<C> + Sync,
+    hyrax: &Hyrax<C>,
+    blinder_poly_sum: ScalarField<C>,
+    blinder_poly: BlinderPoly<ScalarField<C>>,
+    blinder_poly_comm: &IPAComm<C>,
+    transcript: &mut impl TranscriptProtocol<C>,
+    label: &'static [u8],
+    on_round: &mut dyn FnMut(usize),
+) -> (SumCheckProof<C>, Vec<ScalarField<C>>) {
     let mut round_polys = Vec::<UniPoly<ScalarField<C>>>::with_capacity(poly_num_vars);
 
     let rho = transcript.challenge_scalar(label);
 
     let challenge = transcript.challenge_scalars(poly_num_vars, label);
 
-    let round_poly_domain = (0..(poly_degree + 1)).map(|i| i).collect::<Vec<usize>>();
+    let round_degrees = blinder_poly.round_degrees();
+    assert_eq!(round_degrees.len(), poly_num_vars);
+    assert!(round_degrees.iter().all(|d| *d <= poly_degree));
 
     let sc_timer = profiler_start("Sumcheck");
     for j in 0..poly_num_vars {
         let high_index = 2usize.pow((poly_num_vars - j - 1) as u32);
-        let mut evals = vec![ScalarField::<C>::ZERO; poly_degree + 1];
-
-        let mut bounded_eval = ScalarField::<C>::ZERO;
-        for (l, uni_poly) in blinder_poly.uni_polys[..j].iter().enumerate() {
-            bounded_eval += uni_poly.eval(challenge[l]);
-        }
-
-        // https://eprint.iacr.org/2019/317.pdf#subsection.3.2
-        for b in 0..high_index {
-            let r_y_i = challenge[j];
-
-            // Cache the calculation
-            let table_tmp = eval_tables
-                .iter()
-                .map(|table| (table[b + high_index] - table[b]))
-                .collect::<Vec<ScalarField<C>>>();
-
-            for (i, eval_at) in round_poly_domain.iter().enumerate() {
-                let mut comb_input = Vec::with_capacity(num_tables);
-
-                for (table, tmp) in eval_tables.iter().zip(table_tmp.iter()) {
-                    let table_eval = if *eval_at == 0 {
-                        table[b]
-                    } else if *eval_at == 1 {
-                        table[b] + tmp
-                    } else if *eval_at == 2 {
-                        table[b] + tmp + tmp
-                    } else {
-                        table[b] + tmp + tmp + tmp
-                    };
-
-                    comb_input.push(table_eval);
-                }
-
-                if !comb_input.iter().all(|x| *x == ScalarField::<C>::ZERO) {
-                    evals[i] += comb_func(&comb_input);
-                }
-
-                let mut blinder_eval = bounded_eval;
-                blinder_eval += blinder_poly.uni_polys[j].eval_small(*eval_at);
-                for (l, uni_poly) in blinder_poly.uni_polys[(j + 1)..].iter().enumerate() {
-                    blinder_eval += uni_poly.eval_binary((b >> l) & 1 == 1);
-                }
-
-                evals[i] += rho * blinder_eval;
-            }
+        let evals = round_evals(
+            eval_tables,
+            high_index,
+            &comb_func,
+            &blinder_poly,
+            &challenge,
+            rho,
+            j,
+            round_degrees[j],
+            cfg!(feature = "parallel"),
+        );
 
-            for table in eval_tables.into_iter() {
-                table[b] = table[b] + (table[b + high_index] - table[b]) * r_y_i;
-            }
-        }
+        bind_tables(
+            eval_tables,
+            high_index,
+            challenge[j],
+            cfg!(feature = "parallel"),
+        );
 
         let round_poly = UniPoly::interpolate(&evals);
         round_polys.push(round_poly);
+
+        on_round(j);
     }
 
     profiler_end(sc_timer);
@@ -206,7 +316,7 @@ pub fn prove_sum<C: CurveGroup>(
     let open_blinder_poly_profiler = profiler_start("Open blinder poly");
     // Compute the domain which inner product will be the evaluation of the blinder polynomial
 
-    let mut b = BlinderPoly::eval_point_powers(poly_degree, &challenge);
+    let mut b = BlinderPoly::eval_point_powers_with_degrees(&round_degrees, &challenge);
     b.resize(b.len().next_power_of_two(), ScalarField::<C>::ZERO);
 
     let blinder_poly_eval_proof = hyrax.ipa.open(&blinder_poly_comm, b, transcript);
@@ -226,17 +336,125 @@ pub fn prove_sum<C: CurveGroup>(
     )
 }
 
+// The evaluations of the round polynomial of round j at 0, 1, ..., degree,
+// i.e. the sum over the remaining variables of the combined tables plus rho times the blinder polynomial.
+// The variable of round j splits the bound entries of the tables at `high_index`.
+// https://eprint.iacr.org/2019/317.pdf#subsection.3.2
+// The sum over the hypercube is split across threads if `parallel` is set and the `parallel` feature is enabled.
+fn round_evals<F: Field>(
+    eval_tables: &[Vec<F>],
+    high_index: usize,
+    comb_func: &(impl Fn(&[F]) -> F + Sync),
+    blinder_poly: &BlinderPoly<F>,
+    challenge: &[F],
+    rho: F,
+    j: usize,
+    degree: usize,
+    parallel: bool,
+) -> Vec<F> {
+    let mut bounded_eval = F::ZERO;
+    for (l, uni_poly) in blinder_poly.uni_polys[..j].iter().enumerate() {
+        bounded_eval += uni_poly.eval(challenge[l]);
+    }
+
+    // Add the terms of the evaluations at b
+    let add_evals_at = |evals: &mut [F], b: usize| {
+        // Cache the calculation
+        let table_tmp = eval_tables
+            .iter()
+            .map(|table| (table[b + high_index] - table[b]))
+            .collect::<Vec<F>>();
+
+        let mut comb_input = eval_tables.iter().map(|table| table[b]).collect::<Vec<F>>();
+
+        for eval_at in 0..(degree + 1) {
+            // Step the evaluations of the tables to the next point in the domain
+            if eval_at > 0 {
+                for (table_eval, tmp) in comb_input.iter_mut().zip(table_tmp.iter()) {
+                    *table_eval += tmp;
+                }
+            }
+
+            if !comb_input.iter().all(|x| *x == F::ZERO) {
+                evals[eval_at] += comb_func(&comb_input);
+            }
+
+            let mut blinder_eval = bounded_eval;
+            blinder_eval += blinder_poly.uni_polys[j].eval_small(eval_at);
+            for (l, uni_poly) in blinder_poly.uni_polys[(j + 1)..].iter().enumerate() {
+                blinder_eval += uni_poly.eval_binary((b >> l) & 1 == 1);
+            }
+
+            evals[eval_at] += rho * blinder_eval;
+        }
+    };
+
+    #[cfg(feature = "parallel")]
+    if parallel {
+        return (0..high_index)
+            .into_par_iter()
+            .fold(
+                || vec![F::ZERO; degree + 1],
+                |mut evals, b| {
+                    add_evals_at(&mut evals, b);
+                    evals
+                },
+            )
+            .reduce(
+                || vec![F::ZERO; degree + 1],
+                |mut evals, other| {
+                    for (eval, other_eval) in evals.iter_mut().zip(other.iter()) {
+                        *eval += other_eval;
+                    }
+                    evals
+                },
+            );
+    }
+    #[cfg(not(feature = "parallel"))]
+    let _ = parallel;
+
+    let mut evals = vec![F::ZERO; degree + 1];
+    for b in 0..high_index {
+        add_evals_at(&mut evals, b);
+    }
+
+    evals
+}
+
+// Bind the variable that splits the tables at `high_index` to r,
+// in place in the first `high_index` entries of each table.
+fn bind_tables<F: Field>(eval_tables: &mut [Vec<F>], high_index: usize, r: F, parallel: bool) {
+    let bind = |table: &mut Vec<F>| {
+        let (low, high) = table[..(2 * high_index)].split_at_mut(high_index);
+        for (low, high) in low.iter_mut().zip(high.iter()) {
+            *low = *low + (*high - *low) * r;
+        }
+    };
+
+    #[cfg(feature = "parallel")]
+    if parallel {
+        eval_tables.par_iter_mut().for_each(bind);
+        return;
+    }
+    #[cfg(not(feature = "parallel"))]
+    let _ = parallel;
+
+    eval_tables.iter_mut().for_each(bind);
+}
+
 // Evaluates all the round polynomials at the challenge point,
 // and returns the evaluation of the last round polynomial.
+// Each round polynomial may have any degree up to poly_degree.
+// Returns the challenges, or the reason the proof is rejected.
 pub fn verify_sum<C: CurveGroup>(
     proof: &SumCheckProof<C>,
     hyrax: &Hyrax<C>,
     sum_target: ScalarField<C>,
     poly: impl Fn(&[ScalarField<C>]) -> ScalarField<C>,
     poly_degree: usize,
-    transcript: &mut Transcript<C>,
+    transcript: &mut impl TranscriptProtocol<C>,
     label: &'static [u8],
-) -> Vec<ScalarField<C>> {
+) -> Result<Vec<ScalarField<C>>, SumCheckError> {
     // Append the sum and the commitment to the blinder polynomial to the transcript.
     transcript.append_scalar(b"blinder_poly_sum", proof.blinder_poly_sum);
     transcript.append_point(b"blinder_poly_comm", proof.blinder_poly_eval_proof.comm);
@@ -253,37 +471,106 @@ pub fn verify_sum<C: CurveGroup>(
     //  let mut target = sum_target;
 
     for (i, coeffs) in proof.round_poly_coeffs.iter().enumerate() {
+        if coeffs.is_empty() {
+            return Err(SumCheckError::DegreeTooLow { round: i });
+        }
+        if coeffs.len() > poly_degree + 1 {
+            return Err(SumCheckError::DegreeTooHigh { round: i });
+        }
+
         let round_poly = UniPoly::new(coeffs.clone());
-        assert_eq!(
-            round_poly.eval(ScalarField::<C>::ZERO) + round_poly.eval(ScalarField::<C>::ONE),
-            target,
-            "i = {}",
-            i
-        );
+        if round_poly.eval(ScalarField::<C>::ZERO) + round_poly.eval(ScalarField::<C>::ONE)
+            != target
+        {
+            return Err(SumCheckError::RoundMismatch { round: i });
+        }
 
         target = round_poly.eval(challenge[i]);
     }
 
-    // Verify the opening of the blinder polynomial.
+    let round_degrees = proof
+        .round_poly_coeffs
+        .iter()
+        .map(|coeffs| coeffs.len() - 1)
+        .collect::<Vec<usize>>();
+
+    verify_final_eval(
+        &proof.blinder_poly_eval_proof,
+        hyrax,
+        poly,
+        target,
+        rho,
+        &round_degrees,
+        &challenge,
+        transcript,
+    )?;
+
+    Ok(challenge)
+}
+
+// Verify a proof of `prove_with_degree`.
+// Unlike `verify_sum`, which accepts round polynomials of any degree up to poly_degree,
+// each round polynomial must have exactly the claimed degree.
+pub fn verify_with_degree<C: CurveGroup>(
+    proof: &SumCheckProof<C>,
+    hyrax: &Hyrax<C>,
+    sum_target: ScalarField<C>,
+    poly: impl Fn(&[ScalarField<C>]) -> ScalarField<C>,
+    poly_degree: usize,
+    transcript: &mut impl TranscriptProtocol<C>,
+    label: &'static [u8],
+) -> Result<Vec<ScalarField<C>>, SumCheckError> {
+    for (i, coeffs) in proof.round_poly_coeffs.iter().enumerate() {
+        if coeffs.len() < poly_degree + 1 {
+            return Err(SumCheckError::DegreeTooLow { round: i });
+        }
+    }
 
-    let poly_eval = (poly)(&challenge) + rho * proof.blinder_poly_eval_proof.y;
+    verify_sum(
+        proof,
+        hyrax,
+        sum_target,
+        poly,
+        poly_degree,
+        transcript,
+        label,
+    )
+}
 
-    assert_eq!(poly_eval, target);
+// Checks the evaluation of the polynomial combined with the blinder polynomial
+// against the evaluation of the last round polynomial (`target`),
+// and verifies the opening of the blinder polynomial.
+pub(crate) fn verify_final_eval<C: CurveGroup>(
+    blinder_poly_eval_proof: &InnerProductProof<C>,
+    hyrax: &Hyrax<C>,
+    poly: impl Fn(&[ScalarField<C>]) -> ScalarField<C>,
+    target: ScalarField<C>,
+    rho: ScalarField<C>,
+    round_degrees: &[usize],
+    challenge: &[ScalarField<C>],
+    transcript: &mut impl TranscriptProtocol<C>,
+) -> Result<(), SumCheckError> {
+    let poly_eval = (poly)(challenge) + rho * blinder_poly_eval_proof.y;
+
+    if poly_eval != target {
+        return Err(SumCheckError::FinalEvalMismatch);
+    }
 
-    let mut b = BlinderPoly::eval_point_powers(poly_degree, &challenge);
+    let mut b = BlinderPoly::eval_point_powers_with_degrees(round_degrees, challenge);
     b.resize(b.len().next_power_of_two(), ScalarField::<C>::ZERO);
 
-    hyrax
-        .ipa
-        .verify(&proof.blinder_poly_eval_proof, b, transcript);
+    if !hyrax.ipa.is_valid(blinder_poly_eval_proof, b, transcript) {
+        return Err(SumCheckError::EvalProofInvalid);
+    }
 
-    challenge
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::spartan::polynomial::ml_poly::MlPoly;
+    use crate::spartan::transcript::Transcript;
     use ark_ff::Field;
 
     type Curve = ark_secq256k1::Projective;
@@ -363,6 +650,299 @@ mod tests {
             poly_degree,
             &mut verifier_transcript,
             label,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_sumcheck_mixed_degree() {
+        let poly_num_vars = 4;
+        let poly_num_entries = 2usize.pow(poly_num_vars as u32);
+        let poly_degree = 2;
+        let mut prover_transcript = Transcript::<Curve>::new(b"test_sumcheck_mixed_degree");
+        let mut verifier_transcript = prover_transcript.clone();
+
+        let hyrax = Hyrax::new(poly_num_entries, poly_num_entries);
+
+        let eval_table_1 = (0..poly_num_entries)
+            .map(|i| Fp::from((i + 5) as u64))
+            .collect::<Vec<Fp>>();
+
+        // Only depends on the first variable
+        let eval_table_2 = (0..poly_num_entries)
+            .map(|i| {
+                Fp::from(if i >> (poly_num_vars - 1) == 1 {
+                    7u64
+                } else {
+                    3u64
+                })
+            })
+            .collect::<Vec<Fp>>();
+
+        let mut eval_tables = vec![eval_table_1.clone(), eval_table_2.clone()];
+
+        let poly_1 = MlPoly::new(eval_table_1);
+        let poly_2 = MlPoly::new(eval_table_2);
+
+        let poly = |x: &[Fp]| poly_1.eval(x) * poly_2.eval(x);
+        let comb_func = |x: &[Fp]| x[0] * x[1];
+
+        let sum_target = (0..poly_num_entries)
+            .map(|i| comb_func(&[eval_tables[0][i], eval_tables[1][i]]))
+            .fold(Fp::ZERO, |acc, x| acc + x);
+
+        let degrees = round_degrees(&eval_tables, poly_degree);
+        assert_eq!(degrees, vec![2, 1, 1, 1]);
+
+        let (blinder_poly, blinder_poly_comm) =
+            init_blinder_poly_with_degrees(&degrees, &hyrax, &mut prover_transcript);
+
+        let label = b"test_sumcheck_mixed_degree";
+        let (sumcheck_proof, _) = prove_sum(
+            poly_num_vars,
+            poly_degree,
+            &mut eval_tables,
+            comb_func,
+            &hyrax,
+            blinder_poly.sum,
+            blinder_poly,
+            &blinder_poly_comm,
+            &mut prover_transcript,
+            label,
+        );
+
+        let num_coeffs = sumcheck_proof
+            .round_poly_coeffs
+            .iter()
+            .map(|coeffs| coeffs.len())
+            .collect::<Vec<usize>>();
+        assert_eq!(num_coeffs, vec![3, 2, 2, 2]);
+
+        verify_sum(
+            &sumcheck_proof,
+            &hyrax,
+            sum_target,
+            poly,
+            poly_degree,
+            &mut verifier_transcript,
+            label,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_sumcheck_custom_degree() {
+        let poly_num_vars = 4;
+        let poly_num_entries = 2usize.pow(poly_num_vars as u32);
+        let poly_degree = 3;
+        let label = b"test_sumcheck_custom_degree";
+
+        let hyrax = Hyrax::new(poly_num_entries, poly_num_entries);
+
+        let eval_tables = (0..4u64)
+            .map(|k| {
+                (0..poly_num_entries)
+                    .map(|i| Fp::from(i as u64 * (k + 1) + k * k + 2))
+                    .collect::<Vec<Fp>>()
+            })
+            .collect::<Vec<Vec<Fp>>>();
+
+        let ml_polys = eval_tables
+            .iter()
+            .map(|table| MlPoly::new(table.clone()))
+            .collect::<Vec<MlPoly<Fp>>>();
+
+        // A custom gate of degree 3: f_1 * f_2 * f_3 - f_4
+        let comb_func = |x: &[Fp]| x[0] * x[1] * x[2] - x[3];
+        let poly = |x: &[Fp]| {
+            ml_polys[0].eval(x) * ml_polys[1].eval(x) * ml_polys[2].eval(x) - ml_polys[3].eval(x)
+        };
+
+        let sum_target = (0..poly_num_entries)
+            .map(|i| {
+                comb_func(
+                    &eval_tables
+                        .iter()
+                        .map(|table| table[i])
+                        .collect::<Vec<Fp>>(),
+                )
+            })
+            .fold(Fp::ZERO, |acc, x| acc + x);
+
+        let mut prover_transcript = Transcript::<Curve>::new(label);
+        let (sumcheck_proof, prover_challenge) = prove_with_degree(
+            poly_num_vars,
+            poly_degree,
+            &mut eval_tables.clone(),
+            comb_func,
+            &hyrax,
+            &mut prover_transcript,
+            label,
+        );
+
+        assert!(sumcheck_proof
+            .round_poly_coeffs
+            .iter()
+            .all(|coeffs| coeffs.len() == poly_degree + 1));
+
+        let mut verifier_transcript = Transcript::<Curve>::new(label);
+        let verifier_challenge = verify_with_degree(
+            &sumcheck_proof,
+            &hyrax,
+            sum_target,
+            poly,
+            poly_degree,
+            &mut verifier_transcript,
+            label,
+        )
+        .unwrap();
+        assert_eq!(prover_challenge, verifier_challenge);
+
+        // Should fail if the round polynomials don't have the claimed degree
+        let mut verifier_transcript = Transcript::<Curve>::new(label);
+        let result = verify_with_degree(
+            &sumcheck_proof,
+            &hyrax,
+            sum_target,
+            poly,
+            poly_degree + 1,
+            &mut verifier_transcript,
+            label,
+        );
+        assert_eq!(result, Err(SumCheckError::DegreeTooLow { round: 0 }));
+    }
+
+    #[test]
+    fn test_sumcheck_errors() {
+        let poly_num_vars = 4;
+        let poly_num_entries = 2usize.pow(poly_num_vars as u32);
+        let poly_degree = 2;
+        let label = b"test_sumcheck_errors";
+
+        let hyrax = Hyrax::new(poly_num_entries, poly_num_entries);
+
+        let eval_table_1 = (0..poly_num_entries)
+            .map(|i| Fp::from((i + 9) as u64))
+            .collect::<Vec<Fp>>();
+        let eval_table_2 = (0..poly_num_entries)
+            .map(|i| Fp::from((3 * i + 4) as u64))
+            .collect::<Vec<Fp>>();
+
+        let poly_1 = MlPoly::new(eval_table_1.clone());
+        let poly_2 = MlPoly::new(eval_table_2.clone());
+        let poly = |x: &[Fp]| poly_1.eval(x) * poly_2.eval(x);
+
+        let sum_target = eval_table_1
+            .iter()
+            .zip(eval_table_2.iter())
+            .map(|(x, y)| *x * y)
+            .sum::<Fp>();
+
+        let mut prover_transcript = Transcript::<Curve>::new(label);
+        let (sumcheck_proof, _) = prove_with_degree(
+            poly_num_vars,
+            poly_degree,
+            &mut vec![eval_table_1, eval_table_2],
+            |x: &[Fp]| x[0] * x[1],
+            &hyrax,
+            &mut prover_transcript,
+            label,
+        );
+
+        let verify = |proof: &SumCheckProof<Curve>| {
+            let mut verifier_transcript = Transcript::<Curve>::new(label);
+            verify_sum(
+                proof,
+                &hyrax,
+                sum_target,
+                poly,
+                poly_degree,
+                &mut verifier_transcript,
+                label,
+            )
+        };
+
+        assert!(verify(&sumcheck_proof).is_ok());
+
+        // Changing the constant coefficient of a round polynomial changes its sum
+        let mut proof = sumcheck_proof.clone();
+        proof.round_poly_coeffs[2][poly_degree] += Fp::ONE;
+        assert_eq!(
+            verify(&proof),
+            Err(SumCheckError::RoundMismatch { round: 2 })
+        );
+
+        let mut proof = sumcheck_proof.clone();
+        proof.round_poly_coeffs[1].insert(0, Fp::ONE);
+        assert_eq!(
+            verify(&proof),
+            Err(SumCheckError::DegreeTooHigh { round: 1 })
+        );
+
+        let mut proof = sumcheck_proof.clone();
+        proof.round_poly_coeffs[3].clear();
+        assert_eq!(
+            verify(&proof),
+            Err(SumCheckError::DegreeTooLow { round: 3 })
+        );
+
+        // Adding x^2 - x to the last round polynomial keeps its sum,
+        // but changes its evaluation at the challenge
+        let mut proof = sumcheck_proof.clone();
+        proof.round_poly_coeffs[poly_num_vars - 1][0] += Fp::ONE;
+        proof.round_poly_coeffs[poly_num_vars - 1][1] -= Fp::ONE;
+        assert_eq!(verify(&proof), Err(SumCheckError::FinalEvalMismatch));
+
+        let mut proof = sumcheck_proof.clone();
+        proof.blinder_poly_eval_proof.z1 += Fp::ONE;
+        assert_eq!(verify(&proof), Err(SumCheckError::EvalProofInvalid));
+    }
+
+    #[test]
+    fn test_round_evals_parallel() {
+        let poly_num_vars = 6;
+        let poly_degree = 3;
+        let mut rng = rand::thread_rng();
+
+        let mut rand_vec = |len: usize| (0..len).map(|_| Fp::rand(&mut rng)).collect::<Vec<Fp>>();
+
+        let tables = (0..4)
+            .map(|_| rand_vec(2usize.pow(poly_num_vars as u32)))
+            .collect::<Vec<Vec<Fp>>>();
+        let blinder_poly = BlinderPoly::new(
+            (0..poly_num_vars)
+                .map(|_| rand_vec(poly_degree + 1))
+                .collect(),
         );
+        let challenge = rand_vec(poly_num_vars);
+        let rho = rand_vec(1)[0];
+
+        let comb_func = |x: &[Fp]| (x[0] * x[1] - x[2]) * x[3];
+
+        // The sequential and the parallel paths give the same round polynomials
+        let round_poly_coeffs = |parallel: bool| {
+            let mut eval_tables = tables.clone();
+            (0..poly_num_vars)
+                .map(|j| {
+                    let high_index = 2usize.pow((poly_num_vars - j - 1) as u32);
+                    let evals = round_evals(
+                        &eval_tables,
+                        high_index,
+                        &comb_func,
+                        &blinder_poly,
+                        &challenge,
+                        rho,
+                        j,
+                        poly_degree,
+                        parallel,
+                    );
+                    bind_tables(&mut eval_tables, high_index, challenge[j], parallel);
+
+                    UniPoly::interpolate(&evals).coeffs
+                })
+                .collect::<Vec<Vec<Fp>>>()
+        };
+
+        assert_eq!(round_poly_coeffs(false), round_poly_coeffs(true));
     }
 }
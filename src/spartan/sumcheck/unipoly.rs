@@ -1,5 +1,3 @@
-use core::panic;
-
 use ark_ff::Field;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 
@@ -41,7 +39,7 @@ impl<F: Field> UniPoly<F> {
         } else if x == 3 {
             self.eval_at_3
         } else {
-            panic!("x must be 0, 1, 2, or 3")
+            self.eval(F::from(x as u64))
         }
     }
 
@@ -63,12 +61,55 @@ impl<F: Field> UniPoly<F> {
         }
     }
 
+    // Interpolate the polynomial from its evaluations at 0, 1, ..., n - 1
+    // using Lagrange interpolation. Returns the coefficients from high to low degree.
+    fn interpolate_coeffs(evals: &[F]) -> Vec<F> {
+        let n = evals.len();
+
+        // Coefficients from low to high degree
+        let mut coeffs = vec![F::ZERO; n];
+
+        for i in 0..n {
+            // Compute the Lagrange basis polynomial prod_{k != i} (x - k) / (i - k)
+            let mut basis = vec![F::ONE];
+            let mut denominator = F::ONE;
+
+            for k in 0..n {
+                if k == i {
+                    continue;
+                }
+
+                // Multiply the basis polynomial by (x - k)
+                let mut basis_next = vec![F::ZERO; basis.len() + 1];
+                for (d, c) in basis.iter().enumerate() {
+                    basis_next[d + 1] += c;
+                    basis_next[d] -= *c * F::from(k as u64);
+                }
+                basis = basis_next;
+
+                denominator *= F::from(i as u64) - F::from(k as u64);
+            }
+
+            let scale = evals[i] * denominator.inverse().unwrap();
+            for (d, c) in basis.iter().enumerate() {
+                coeffs[d] += *c * scale;
+            }
+        }
+
+        coeffs.reverse();
+        coeffs
+    }
+
     pub fn interpolate(evals: &[F]) -> Self {
-        debug_assert!(
-            evals.len() == 4 || evals.len() == 3,
-            "Only cubic and quadratic polynomials are supported"
+        assert!(
+            !evals.is_empty(),
+            "At least one evaluation is required to interpolate"
         );
 
+        if evals.len() != 4 && evals.len() != 3 {
+            return Self::new(Self::interpolate_coeffs(evals));
+        }
+
         let two_inv = F::from(2u64).inverse().unwrap();
 
         if evals.len() == 4 {
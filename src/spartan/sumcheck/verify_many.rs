@@ -0,0 +1,105 @@
+use super::sumcheck::verify_sum;
+use super::SumCheckProof;
+use crate::spartan::hyrax::Hyrax;
+use crate::spartan::polynomial::ml_poly::MlPoly;
+use crate::spartan::transcript::Transcript;
+use crate::ScalarField;
+use ark_ec::CurveGroup;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+// Verify many independent sumcheck proofs.
+// Each proof is paired with the evaluation table of the multilinear polynomial
+// which sum over the boolean hypercube it proves.
+// Every proof is verified against a fresh copy of `transcript`.
+// The proofs are verified concurrently when the `parallel` feature is enabled.
+// Returns whether each proof is valid.
+pub fn verify_many<C: CurveGroup>(
+    proofs: &[(SumCheckProof<C>, Vec<ScalarField<C>>)],
+    hyrax: &Hyrax<C>,
+    transcript: &Transcript<C>,
+    label: &'static [u8],
+) -> Vec<bool> {
+    let verify = |(proof, evals): &(SumCheckProof<C>, Vec<ScalarField<C>>)| {
+        let sum_target = evals.iter().sum::<ScalarField<C>>();
+        let poly = MlPoly::new(evals.clone());
+
+        let mut transcript = transcript.clone();
+        verify_sum(
+            proof,
+            hyrax,
+            sum_target,
+            |x| poly.eval(x),
+            1,
+            &mut transcript,
+            label,
+        )
+        .is_ok()
+    };
+
+    #[cfg(feature = "parallel")]
+    let results = proofs.par_iter().map(verify).collect();
+
+    #[cfg(not(feature = "parallel"))]
+    let results = proofs.iter().map(verify).collect();
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spartan::sumcheck::prove_test_sum;
+    use ark_ff::Field;
+
+    type Curve = ark_secq256k1::Projective;
+    type Fp = ark_secq256k1::Fr;
+
+    const POLY_NUM_VARS: usize = 4;
+    const LABEL: &[u8] = b"test_verify_many";
+
+    fn prove_instance(offset: u64) -> (SumCheckProof<Curve>, Vec<Fp>) {
+        let evals = (0..2usize.pow(POLY_NUM_VARS as u32))
+            .map(|i| Fp::from(i as u64 * offset + 3))
+            .collect::<Vec<Fp>>();
+
+        let (proof, _, _, _) = prove_test_sum::<Curve>(vec![evals.clone()], LABEL);
+
+        (proof, evals)
+    }
+
+    #[test]
+    fn test_verify_many() {
+        let poly_num_entries = 2usize.pow(POLY_NUM_VARS as u32);
+        let hyrax = Hyrax::<Curve>::new(poly_num_entries, poly_num_entries);
+
+        let mut proofs = (1..5).map(prove_instance).collect::<Vec<_>>();
+
+        // Invalidate the second proof
+        proofs[1].0.round_poly_coeffs[0][0] += Fp::ONE;
+
+        let transcript = Transcript::<Curve>::new(LABEL);
+
+        // Verify the proofs one by one
+        let expected = proofs
+            .iter()
+            .map(|(proof, evals)| {
+                let poly = MlPoly::new(evals.clone());
+                verify_sum(
+                    proof,
+                    &hyrax,
+                    evals.iter().sum(),
+                    |x| poly.eval(x),
+                    1,
+                    &mut transcript.clone(),
+                    LABEL,
+                )
+                .is_ok()
+            })
+            .collect::<Vec<bool>>();
+
+        assert_eq!(expected, vec![true, false, true, true]);
+        assert_eq!(verify_many(&proofs, &hyrax, &transcript, LABEL), expected);
+    }
+}
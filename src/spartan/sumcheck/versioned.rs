@@ -0,0 +1,126 @@
+use super::SumCheckProof;
+use ark_ec::CurveGroup;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use std::io;
+
+pub const SUMCHECK_PROOF_MAGIC: &[u8; 4] = b"spsc";
+// Bump when the layout of `SumCheckProof` changes
+pub const SUMCHECK_PROOF_VERSION: u16 = 1;
+
+fn invalid_data(msg: String) -> SerializationError {
+    SerializationError::IoError(io::Error::new(io::ErrorKind::InvalidData, msg))
+}
+
+impl<C: CurveGroup> SumCheckProof<C> {
+    // Serialize the proof after a 4-byte magic and the version of the layout (a little-endian u16),
+    // so that `from_bytes` rejects a proof of another layout instead of misreading it.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, SerializationError> {
+        let mut bytes = Vec::with_capacity(6 + self.compressed_size());
+        bytes.extend_from_slice(SUMCHECK_PROOF_MAGIC);
+        bytes.extend_from_slice(&SUMCHECK_PROOF_VERSION.to_le_bytes());
+        self.serialize_compressed(&mut bytes)?;
+
+        Ok(bytes)
+    }
+
+    // Deserialize a proof serialized with `to_bytes`.
+    // Returns an error if the magic or the version doesn't match, or if there are trailing bytes.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SerializationError> {
+        if bytes.len() < 6 {
+            return Err(SerializationError::NotEnoughSpace);
+        }
+
+        if &bytes[..4] != SUMCHECK_PROOF_MAGIC {
+            return Err(invalid_data("Not a sumcheck proof".to_string()));
+        }
+
+        let version = u16::from_le_bytes([bytes[4], bytes[5]]);
+        if version != SUMCHECK_PROOF_VERSION {
+            return Err(invalid_data(format!(
+                "Unsupported sumcheck proof version {} (expected {})",
+                version, SUMCHECK_PROOF_VERSION
+            )));
+        }
+
+        let mut reader = &bytes[6..];
+        let proof = Self::deserialize_compressed(&mut reader)?;
+
+        if !reader.is_empty() {
+            return Err(invalid_data(format!(
+                "{} trailing bytes after the sumcheck proof",
+                reader.len()
+            )));
+        }
+
+        Ok(proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spartan::hyrax::Hyrax;
+    use crate::spartan::sumcheck::sumcheck::prove_with_degree;
+    use crate::spartan::transcript::Transcript;
+
+    type Curve = ark_secq256k1::Projective;
+    type Fp = ark_secq256k1::Fr;
+
+    fn proof_bytes() -> (SumCheckProof<Curve>, Vec<u8>) {
+        let poly_num_vars = 3;
+        let poly_num_entries = 2usize.pow(poly_num_vars as u32);
+        let hyrax = Hyrax::new(poly_num_entries, poly_num_entries);
+
+        let mut eval_tables = vec![(0..poly_num_entries)
+            .map(|i| Fp::from(i as u64 + 1))
+            .collect::<Vec<Fp>>()];
+
+        let mut transcript = Transcript::<Curve>::new(b"test_versioned");
+        let (proof, _) = prove_with_degree(
+            poly_num_vars,
+            1,
+            &mut eval_tables,
+            |x: &[Fp]| x[0],
+            &hyrax,
+            &mut transcript,
+            b"test_versioned",
+        );
+
+        let bytes = proof.to_bytes().unwrap();
+        (proof, bytes)
+    }
+
+    #[test]
+    fn test_to_bytes() {
+        let (proof, bytes) = proof_bytes();
+        assert_eq!(&bytes[..4], SUMCHECK_PROOF_MAGIC);
+
+        let deserialized = SumCheckProof::<Curve>::from_bytes(&bytes).unwrap();
+        assert_eq!(deserialized.round_poly_coeffs, proof.round_poly_coeffs);
+        assert_eq!(deserialized.blinder_poly_sum, proof.blinder_poly_sum);
+        assert_eq!(deserialized.to_bytes().unwrap(), bytes);
+    }
+
+    #[test]
+    fn test_from_bytes_invalid() {
+        let (_, bytes) = proof_bytes();
+
+        // Another version
+        let mut bumped = bytes.clone();
+        bumped[4] += 1;
+        assert!(SumCheckProof::<Curve>::from_bytes(&bumped).is_err());
+
+        // Another magic
+        let mut wrong_magic = bytes.clone();
+        wrong_magic[0] ^= 1;
+        assert!(SumCheckProof::<Curve>::from_bytes(&wrong_magic).is_err());
+
+        // Without the header
+        assert!(SumCheckProof::<Curve>::from_bytes(&bytes[6..]).is_err());
+
+        // With trailing bytes
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(SumCheckProof::<Curve>::from_bytes(&trailing).is_err());
+    }
+}
@@ -4,6 +4,42 @@ use ark_ff::PrimeField;
 use ark_ff::{BigInteger, Field};
 use std::marker::PhantomData;
 
+// The Fiat-Shamir transcript shared by the prover and the verifier.
+// The challenges are derived from everything appended before them,
+// so both sides must append the same messages, with the same labels, in the same order.
+// Implement this trait to derive the challenges differently,
+// e.g. to match the transcript of a specific verifier.
+pub trait TranscriptProtocol<C: CurveGroup> {
+    fn append_scalar(&mut self, label: &'static [u8], s: ScalarField<C>);
+
+    fn append_point(&mut self, label: &'static [u8], p: C);
+
+    fn challenge_scalar(&mut self, label: &'static [u8]) -> ScalarField<C>;
+
+    fn append_scalars(&mut self, label: &'static [u8], scalars: &[ScalarField<C>]) {
+        for s in scalars {
+            self.append_scalar(label, *s);
+        }
+    }
+
+    fn append_points(&mut self, label: &'static [u8], points: &[C]) {
+        for p in points {
+            self.append_point(label, *p);
+        }
+    }
+
+    fn challenge_scalars(&mut self, n: usize, label: &'static [u8]) -> Vec<ScalarField<C>> {
+        let mut c = Vec::with_capacity(n);
+        for _ in 0..n {
+            let c_i = self.challenge_scalar(label);
+            c.push(c_i);
+        }
+
+        c
+    }
+}
+
+// The default transcript, backed by merlin (STROBE over Keccak-f[1600])
 #[derive(Clone)]
 pub struct Transcript<C: CurveGroup> {
     inner: merlin::Transcript,
@@ -19,36 +55,130 @@ impl<C: CurveGroup> Transcript<C> {
             _marker: PhantomData,
         }
     }
+}
 
-    pub fn append_scalar(&mut self, label: &'static [u8], s: ScalarField<C>) {
+impl<C: CurveGroup> TranscriptProtocol<C> for Transcript<C> {
+    fn append_scalar(&mut self, label: &'static [u8], s: ScalarField<C>) {
         self.inner
             .append_message(label, &s.into_bigint().to_bytes_be());
     }
 
-    pub fn append_point(&mut self, label: &'static [u8], p: C) {
+    fn append_point(&mut self, label: &'static [u8], p: C) {
         self.inner.append_message(label, &p.to_string().as_bytes());
     }
 
-    pub fn append_points(&mut self, label: &'static [u8], points: &[C]) {
-        for p in points {
-            self.append_point(label, *p);
-        }
-    }
-
-    pub fn challenge_scalar(&mut self, label: &'static [u8]) -> ScalarField<C> {
+    fn challenge_scalar(&mut self, label: &'static [u8]) -> ScalarField<C> {
         let mut bytes = [0u8; 32];
         self.inner.challenge_bytes(label, &mut bytes);
 
         ScalarField::<C>::from_random_bytes(&bytes).unwrap()
     }
+}
 
-    pub fn challenge_scalars(&mut self, n: usize, label: &'static [u8]) -> Vec<ScalarField<C>> {
-        let mut c = Vec::with_capacity(n);
-        for _ in 0..n {
-            let c_i = self.challenge_scalar(label);
-            c.push(c_i);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spartan::hyrax::Hyrax;
+    use crate::spartan::polynomial::ml_poly::MlPoly;
+    use crate::spartan::sumcheck::sumcheck::{prove_with_degree, verify_with_degree};
+
+    type Curve = ark_secq256k1::Projective;
+    type Fp = ark_secq256k1::Fr;
+
+    // A custom transcript that records the challenges derived by the default transcript
+    struct RecordingTranscript {
+        inner: Transcript<Curve>,
+        challenges: Vec<Fp>,
+    }
+
+    impl RecordingTranscript {
+        fn new(label: &'static [u8]) -> Self {
+            Self {
+                inner: Transcript::new(label),
+                challenges: vec![],
+            }
+        }
+    }
+
+    impl TranscriptProtocol<Curve> for RecordingTranscript {
+        fn append_scalar(&mut self, label: &'static [u8], s: Fp) {
+            self.inner.append_scalar(label, s);
         }
 
-        c
+        fn append_point(&mut self, label: &'static [u8], p: Curve) {
+            self.inner.append_point(label, p);
+        }
+
+        fn challenge_scalar(&mut self, label: &'static [u8]) -> Fp {
+            let c = self.inner.challenge_scalar(label);
+            self.challenges.push(c);
+            c
+        }
+    }
+
+    #[test]
+    fn test_transcript_challenges() {
+        let poly_num_vars = 4;
+        let poly_num_entries = 2usize.pow(poly_num_vars as u32);
+        let poly_degree = 2;
+        let label = b"test_transcript";
+
+        let hyrax = Hyrax::<Curve>::new(poly_num_entries, poly_num_entries);
+
+        let eval_table_1 = (0..poly_num_entries)
+            .map(|i| Fp::from((i + 3) as u64))
+            .collect::<Vec<Fp>>();
+        let eval_table_2 = (0..poly_num_entries)
+            .map(|i| Fp::from((i * 5 + 1) as u64))
+            .collect::<Vec<Fp>>();
+
+        let poly_1 = MlPoly::new(eval_table_1.clone());
+        let poly_2 = MlPoly::new(eval_table_2.clone());
+        let comm_1 = hyrax.commit(eval_table_1.clone());
+
+        let comb_func = |x: &[Fp]| x[0] * x[1];
+        let poly = |x: &[Fp]| poly_1.eval(x) * poly_2.eval(x);
+        let sum_target = eval_table_1
+            .iter()
+            .zip(eval_table_2.iter())
+            .map(|(x, y)| *x * y)
+            .sum::<Fp>();
+
+        // Prove the sum, and open the first polynomial at the challenge
+        let mut prover_transcript = RecordingTranscript::new(label);
+        let (sumcheck_proof, prover_challenge) = prove_with_degree(
+            poly_num_vars,
+            poly_degree,
+            &mut vec![eval_table_1, eval_table_2],
+            comb_func,
+            &hyrax,
+            &mut prover_transcript,
+            label,
+        );
+        let eval_proof = hyrax.open(&comm_1, prover_challenge.clone(), &mut prover_transcript);
+
+        let mut verifier_transcript = RecordingTranscript::new(label);
+        let verifier_challenge = verify_with_degree(
+            &sumcheck_proof,
+            &hyrax,
+            sum_target,
+            poly,
+            poly_degree,
+            &mut verifier_transcript,
+            label,
+        )
+        .unwrap();
+        hyrax.verify(&eval_proof, &mut verifier_transcript);
+
+        // The prover and the verifier derive the same challenges
+        assert_eq!(prover_challenge, verifier_challenge);
+        assert!(!prover_transcript.challenges.is_empty());
+        assert_eq!(prover_transcript.challenges, verifier_transcript.challenges);
+
+        // A transcript with another label derives other challenges
+        let mut other_transcript = Transcript::<Curve>::new(b"other_transcript");
+        let other_challenge = other_transcript.challenge_scalar(label);
+        let mut transcript = Transcript::<Curve>::new(label);
+        assert_ne!(transcript.challenge_scalar(label), other_challenge);
     }
 }
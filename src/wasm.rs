@@ -23,6 +23,14 @@ pub mod prelude {
             .map(|x| F::from_be_bytes_mod_order(x))
             .collect::<Vec<F>>()
     }
+
+    // Decode a hex string, with or without the 0x prefix,
+    // or None if it isn't a valid hex string
+    #[allow(dead_code)]
+    pub fn decode_hex(hex_str: &str) -> Option<Vec<u8>> {
+        let hex_str = hex_str.strip_prefix("0x").unwrap_or(hex_str);
+        hex::decode(hex_str).ok()
+    }
 }
 
 #[allow(unused_imports)]
@@ -86,17 +94,53 @@ macro_rules! embed_to_wasm {
             compressed_bytes
         }
 
-        #[wasm_bindgen]
-        pub fn verify(proof_ser: &[u8]) -> bool {
-            let proof = SpartanProof::<$curve>::deserialize_compressed(proof_ser).unwrap();
+        fn verify_proof(proof: &SpartanProof<$curve>) -> bool {
             let circuit = CIRCUIT.lock().unwrap().clone();
 
             let spartan = Spartan::new($label, circuit);
-            spartan.verify(&proof);
+            spartan.verify(proof);
 
             true
         }
 
+        #[wasm_bindgen]
+        pub fn verify(proof_ser: &[u8]) -> bool {
+            let proof = SpartanProof::<$curve>::deserialize_compressed(proof_ser).unwrap();
+            verify_proof(&proof)
+        }
+
+        // Same as `prove`, with the inputs as hex strings
+        // of concatenated 32-byte big-endian field elements
+        #[wasm_bindgen]
+        pub fn prove_hex(pub_input: &str, priv_input: &str) -> Vec<u8> {
+            prove(
+                &decode_hex(pub_input).expect("Invalid hex string"),
+                &decode_hex(priv_input).expect("Invalid hex string"),
+            )
+        }
+
+        // Same as `verify`, also checking that the proof is for the public input
+        // given as a hex string of concatenated 32-byte big-endian field elements.
+        // Returns false for a malformed proof or public input.
+        #[wasm_bindgen]
+        pub fn verify_hex(proof_ser: &[u8], pub_input: &str) -> bool {
+            let proof = match SpartanProof::<$curve>::deserialize_compressed(proof_ser) {
+                Ok(proof) => proof,
+                Err(_) => return false,
+            };
+
+            let pub_input = match decode_hex(pub_input) {
+                Some(pub_input) => pub_input,
+                None => return false,
+            };
+
+            if proof.pub_input != to_felts::<ScalarField<$curve>>(&pub_input) {
+                return false;
+            }
+
+            verify_proof(&proof)
+        }
+
         /*
         #[wasm_bindgen]
         pub fn generate_tx_input(proof_ser: &[u8], contract_address: &[u8]) -> Vec<u8> {
@@ -177,4 +221,34 @@ mod tests {
         let result = panic::catch_unwind(|| prove_and_verify(&invalid_pub_input, &priv_input));
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_prove_hex() {
+        let to_hex = |felts: &[F]| {
+            let bytes = felts
+                .iter()
+                .map(|x| x.into_bigint().to_bytes_be())
+                .flatten()
+                .collect::<Vec<u8>>();
+            format!("0x{}", hex::encode(bytes))
+        };
+
+        let priv_input = [F::from(5), F::from(6)];
+        let pub_input = [priv_input[0] * priv_input[1]];
+
+        prepare();
+
+        let proof_bytes = prove_hex(&to_hex(&pub_input), &to_hex(&priv_input));
+        assert!(verify_hex(&proof_bytes, &to_hex(&pub_input)));
+
+        // The proof isn't for another public input
+        assert!(!verify_hex(&proof_bytes, &to_hex(&[F::from(31)])));
+
+        // Malformed public inputs and proofs are rejected
+        assert!(!verify_hex(&proof_bytes, "0xnot hex"));
+        assert!(!verify_hex(
+            &proof_bytes[..proof_bytes.len() / 2],
+            &to_hex(&pub_input)
+        ));
+    }
 }